@@ -1,12 +1,12 @@
 //! AutoCompleter
-//! Functionality to support autocompletion with HPO terms
+//! Functionality to support autocompletion with ontology terms (by default, with HPO terms)
 //! //! ```
 //! use std::fs::File;
 //! use std::io::BufReader;
 //! use std::sync::Arc;
 //! use flate2::bufread::GzDecoder;
 //! use fenominal::AutoCompleter;
-//! use fenominal::HpoMatch;
+//! use fenominal::TermMatch;
 //! use ontolius::io::OntologyLoaderBuilder;
 //! use ontolius::ontology::csr::FullCsrOntology;
 //! let hp_path = "resources/hp.v2025-03-03.json.gz";
@@ -22,36 +22,47 @@
 //! // in the front-end and might have entered "macroc" so far
 //! // See tests/autocomplete_test.rs
 //! let text = "macroceph";
-//! let hits: Vec<HpoMatch> = autocompleter.search_hpo(&text);
+//! let hits: Vec<TermMatch> = autocompleter.search(text, 10);
 //! ```
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::OnceLock;
 use ontolius::ontology::{HierarchyWalks, OntologyTerms};
-use ontolius::common::hpo::PHENOTYPIC_ABNORMALITY;
 use ontolius::term::{MinimalTerm, Synonymous};
+use ontolius::TermId;
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use regex::Regex;
 #[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
 
+use crate::hpo::branches::default_roots;
+use crate::hpo::obsolete::TermIdResolver;
+
 
 
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct HpoMatch {
-    /// HPO identifier of the matched concept, e.g., HP:0011995
+pub struct TermMatch {
+    /// Identifier of the matched concept, e.g., HP:0011995
     pub id: String,
-    /// Corresponding HPO label, e.g., Atrial septal dilatation 
+    /// Corresponding label, e.g., Atrial septal dilatation 
     pub label: String,
     /// Text that was matched, e.g., Atrial septal aneurysm
     pub matched_text: String,
 }
 
+#[deprecated(note = "renamed to TermMatch, matches are not restricted to the HPO")]
+pub type HpoMatch = TermMatch;
+
 
 pub struct AutoCompleter {
     /// Strings for autocompletion
-    hpo_auto_complete: Vec<HpoMatch>,
+    auto_complete: Vec<TermMatch>,
+    /// Alternative ID -> primary ID
+    alt_ids: HashMap<String, String>,
+    /// Resolves obsolete IDs, which are not part of the ontolius ontology
+    id_resolver: Option<TermIdResolver>,
 }
 
 impl AutoCompleter {
@@ -60,31 +71,88 @@ impl AutoCompleter {
     pub fn new<O, T>(hpo: Arc<O>) -> Self where
         O: OntologyTerms<T> + HierarchyWalks,
         T: MinimalTerm + Synonymous, {
-        let acomplete = Self::initialize_hpo_autocomplete(hpo.clone());
+        Self::from_roots(hpo, &default_roots())
+    }
+
+    /// Create an AutoCompleter for the descendants of the given root terms, e.g., Phenotypic abnormality
+    /// and Mode of inheritance (see [`crate::branches`])
+    pub fn from_roots<O, T>(hpo: Arc<O>, roots: &[TermId]) -> Self where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm + Synonymous, {
+        let acomplete = Self::initialize_autocomplete(hpo.clone(), roots);
+        let alt_ids = Self::initialize_alt_ids(hpo, roots);
         Self {
-            hpo_auto_complete: acomplete
+            auto_complete: acomplete,
+            alt_ids,
+            id_resolver: None,
+        }
+    }
+
+    /// Add the descendants of the root terms of another ontology, e.g., MONDO:0000001 (disease)
+    pub fn with_ontology<O, T>(mut self, ontology: Arc<O>, roots: &[TermId]) -> Self where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm + Synonymous, {
+        self.auto_complete.extend(Self::initialize_autocomplete(ontology.clone(), roots));
+        self.alt_ids.extend(Self::initialize_alt_ids(ontology, roots));
+        self
+    }
+
+    /// Resolve IDs of obsolete terms to the terms that replace them in ID lookups
+    /// (see [`TermIdResolver::from_obographs`]).
+    pub fn with_id_resolver(mut self, id_resolver: TermIdResolver) -> Self {
+        self.id_resolver = Some(id_resolver);
+        self
+    }
+
+    /// Map the alternative IDs of the descendants of the roots to their primary IDs
+    fn initialize_alt_ids<O, T>(hpo: Arc<O>, roots: &[TermId]) -> HashMap<String, String> where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm, {
+        roots.iter()
+            .flat_map(|root| hpo.iter_descendant_ids(root))
+            .filter_map(|tid| hpo.term_by_id(tid))
+            .flat_map(|term| {
+                let id = term.identifier().to_string();
+                term.iter_alt_term_ids().map(move |alt_id| (alt_id.to_string(), id.clone()))
+            })
+            .collect()
+    }
+
+    /// The primary ID of the current term for an ID, an alternative ID or (if there is an ID resolver)
+    /// an obsolete ID that was replaced
+    fn resolve_id(&self, id: &str) -> Option<String> {
+        if self.auto_complete.iter().any(|item| item.id == id) {
+            return Some(id.to_string());
         }
+        self.alt_ids.get(id).cloned().or_else(|| {
+            self.id_resolver.as_ref().and_then(|r| r.resolve(id)).map(|term| term.id)
+        })
     }
     
 
     /// Set up autocomplete functionality 
-    fn initialize_hpo_autocomplete<O, T>(hpo: Arc<O>) -> Vec<HpoMatch> where
+    fn initialize_autocomplete<O, T>(ontology: Arc<O>, roots: &[TermId]) -> Vec<TermMatch> where
         O: OntologyTerms<T> + HierarchyWalks,
         T: MinimalTerm + Synonymous, {
-        let mut hpo_auto_complete: Vec<HpoMatch> = Vec::new();
-        for tid in  hpo.iter_descendant_ids(&PHENOTYPIC_ABNORMALITY) {
-            match hpo.term_by_id(tid) {
+        let mut auto_complete: Vec<TermMatch> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for tid in roots.iter().flat_map(|root| ontology.iter_descendant_ids(root)) {
+            // terms that descend from several roots are only added once
+            if !seen.insert(tid) {
+                continue;
+            }
+            match ontology.term_by_id(tid) {
                 Some(term) => {
                     let id_str = tid.to_string();
                     let primary_label = term.name().to_string();
-                    hpo_auto_complete.push(HpoMatch {
+                    auto_complete.push(TermMatch {
                         id: id_str.clone(),
                         label: primary_label.clone(),
                         matched_text: primary_label.clone(),
                     });
                     for synonym in term.synonyms() {
                         let label = synonym.name.clone();
-                        hpo_auto_complete.push(HpoMatch {
+                        auto_complete.push(TermMatch {
                             id: id_str.clone(),
                             label: primary_label.clone(),
                             matched_text: label, 
@@ -94,23 +162,27 @@ impl AutoCompleter {
                 None => { eprintln!("Could not retrieve term for {}", tid); } // should never happen
             }
         }
-        hpo_auto_complete
+        auto_complete
     }
 
      /// Provide Strings with TermId - Label that will be used for autocompletion
-    pub fn search_hpo(&self, query: &str, limit: usize) -> Vec<HpoMatch> {
+     ///
+     /// If the query contains a term ID of any ontology (e.g., HP:0001250 or MONDO:0007947), the term
+     /// with that ID is returned. Alternative IDs (and obsolete IDs, see [`AutoCompleter::with_id_resolver`])
+     /// are resolved to the current term.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<TermMatch> {
         let matcher = SkimMatcherV2::default();
         let query_lower = query.to_lowercase();
-        static HPO_ID_REGEX: OnceLock<Regex> = OnceLock::new();
-        let re = HPO_ID_REGEX.get_or_init(|| Regex::new(r"HP:\d{7}").unwrap());
+        static TERM_ID_REGEX: OnceLock<Regex> = OnceLock::new();
+        let re = TERM_ID_REGEX.get_or_init(|| Regex::new(r"\b[A-Za-z][A-Za-z0-9_]*:\d+\b").unwrap());
         if let Some(mat) = re.find(query) {
-            let exact_id = mat.as_str();
-            if let Some(exact_match) = self.hpo_auto_complete.iter().find(|item| item.id == exact_id) {
+            let exact_id = self.resolve_id(mat.as_str());
+            if let Some(exact_match) = self.auto_complete.iter().find(|item| Some(&item.id) == exact_id.as_ref()) {
                 return vec![exact_match.clone()];
             }
         }
         // get fuzzy matches to query
-        let mut matches: Vec<_> = self.hpo_auto_complete
+        let mut matches: Vec<_> = self.auto_complete
             .iter()
             .filter_map(|item| {
                 matcher.fuzzy_match(&item.matched_text, &query_lower)
@@ -119,7 +191,7 @@ impl AutoCompleter {
             .collect();
         // sort by score
         matches.sort_unstable_by_key(|&(score, _)| std::cmp::Reverse(score));
-        // return best hits, but only one hit per HPO id (avoid duplicates because of synonym matches)
+        // return best hits, but only one hit per term id (avoid duplicates because of synonym matches)
         let mut seen = std::collections::HashSet::new();
         matches
             .into_iter()
@@ -130,12 +202,17 @@ impl AutoCompleter {
             .collect()
     }
 
-     /// We want to get the single best match of any HPO term label to the query string
-    pub fn get_best_hpo_match(&self, query: String) -> Option<HpoMatch> {
+    #[deprecated(note = "renamed to search, the terms are not restricted to the HPO")]
+    pub fn search_hpo(&self, query: &str, limit: usize) -> Vec<TermMatch> {
+        self.search(query, limit)
+    }
+
+     /// We want to get the single best match of any term label to the query string
+    pub fn get_best_match(&self, query: String) -> Option<TermMatch> {
         let matcher = SkimMatcherV2::default();
         let query_lower = query.to_lowercase();
         // First, prioritize exact matches
-        let exact_match = self.hpo_auto_complete
+        let exact_match = self.auto_complete
             .iter()
             .find(|item| item.matched_text.to_lowercase() == query_lower);
 
@@ -143,7 +220,7 @@ impl AutoCompleter {
             return Some(item.clone());
         }
         // Otherwise, try to get a good fuzzy match
-        self.hpo_auto_complete
+        self.auto_complete
             .iter()
             .filter_map(|item| {
                 // We score based on the matched_text (could be a synonym or primary label)
@@ -155,6 +232,11 @@ impl AutoCompleter {
             // Return the whole object so you have the ID and Label immediately
             .map(|(item, _)| item.clone())
     }
+
+    #[deprecated(note = "renamed to get_best_match, the terms are not restricted to the HPO")]
+    pub fn get_best_hpo_match(&self, query: String) -> Option<TermMatch> {
+        self.get_best_match(query)
+    }
            
 
 }
@@ -2,10 +2,9 @@
 //! It can be used in front-end tools
 
 /// This represents a match for an HPO term to a potentially partial text
-/// that is being entered by a user 
-/// 
-
+/// that is being entered by a user
 pub mod autocompleter;
 
-pub use self::autocompleter::{AutoCompleter, HpoMatch};
+#[allow(deprecated)]
+pub use self::autocompleter::{AutoCompleter, HpoMatch, TermMatch};
 
@@ -0,0 +1,51 @@
+use clap::Parser;
+use ontolius::io::OntologyLoaderBuilder;
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::FenominalOptions;
+use fenominal::MiningDictionary;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Compile the labels and synonyms of the HPO into a mining dictionary, which `fenominal_main --dictionary`
+/// loads much faster than the HPO JSON file.
+#[derive(Parser, Debug)]
+#[command(version = "0.1.6", about = "Build a precompiled fenominal mining dictionary")]
+struct Args {
+    /// Path to the HPO JSON file
+    #[arg(long, value_name = "FILE")]
+    hp: PathBuf,
+
+    /// Options of text mining (a .toml or .json file); the roots and the label filter are compiled
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Path of the dictionary
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    if !args.hp.exists() {
+        return Err(format!("Could not find HPO JSON file at {}.", args.hp.display()).into());
+    }
+    eprintln!("Processing HPO JSON file: {:?}.", args.hp);
+    let loader = OntologyLoaderBuilder::new().obographs_parser().build();
+    let hpo: FullCsrOntology = loader.load_from_path(&args.hp)?;
+    let options = match &args.config {
+        Some(path) => FenominalOptions::from_path(path)?,
+        None => FenominalOptions::default(),
+    };
+    let dictionary = MiningDictionary::compile(&hpo, &options)?;
+    dictionary.write_to_path(&args.output)?;
+    let version = dictionary.ontology_version();
+    eprintln!(
+        "[INFO] Wrote {} labels and synonyms of {} {} to {:?} ({}).",
+        dictionary.len(),
+        version.prefix,
+        version.version,
+        args.output,
+        dictionary.checksum()
+    );
+    Ok(())
+}
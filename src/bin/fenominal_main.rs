@@ -1,8 +1,18 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use ontolius::io::OntologyLoaderBuilder;
 use ontolius::ontology::csr::FullCsrOntology;
+use ontolius::term::simple::SimpleTerm;
+use fenominal::export::fhir::Bundle;
+use fenominal::export::html::render_html_with_provenance;
+use fenominal::export::phenopacket::Phenopacket;
+use fenominal::export::pubannotation::PubAnnotation;
+use fenominal::export::table::{write_hits, write_provenance, HitRow, TableFormat};
 use fenominal::Fenominal;
-use fenominal::FenominalHit;
+use fenominal::FenominalBuilder;
+use fenominal::FenominalOptions;
+use fenominal::MiningDictionary;
+use fenominal::MiningResult;
+use fenominal::OffsetUnit;
 use std::error::Error;
 use std::path::Path;
 use std::path::PathBuf;
@@ -12,34 +22,120 @@ use std::sync::Arc;
 #[command(version = "0.1.6", about = "Fenominal implementation in Rust")]
 struct Args {
     /// Path to the file
-    #[arg(long, value_name = "FILE")]
-    hp: PathBuf,
+    #[arg(long, value_name = "FILE", required_unless_present = "dictionary")]
+    hp: Option<PathBuf>,
+
+    /// Precompiled mining dictionary (see fenominal_dictionary), used instead of the HPO JSON file.
+    /// Onsets, modifiers and organ systems require the HPO.
+    #[arg(long, value_name = "FILE", conflicts_with = "hp")]
+    dictionary: Option<PathBuf>,
 
     /// Input string
     #[arg(short, long, value_name = "STRING")]
     input: String,
+
+    /// Options of text mining (a .toml or .json file, see FenominalOptions)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    /// Identifier of the input document (recorded in the provenance of the output)
+    #[arg(long, value_name = "ID", default_value = "fenominal")]
+    id: String,
+
+    /// Unit of the offsets of the JSON output: bytes, chars (Unicode scalar values) or utf16 (JavaScript string indices)
+    #[arg(long, value_name = "UNIT", default_value = "bytes")]
+    offset_unit: OffsetUnit,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// The hits and their provenance as JSON
+    Json,
+    /// A GA4GH Phenopacket (v2) with the phenotypic features of the input
+    Phenopacket,
+    /// A FHIR R4 Bundle with the input as a DocumentReference and one Observation per hit
+    Fhir,
+    /// PubAnnotation JSON with one denotation per hit
+    Pubannotation,
+    /// A standalone HTML report with the hits highlighted in the input
+    Html,
+    /// One line per hit, tab-separated
+    Tsv,
+    /// One line per hit, comma-separated
+    Csv,
 }
 
 fn main() -> Result<(), Box<dyn Error>>{
     let args = Args::parse();
-    let hp_json_path = args.hp;
-    let hp_json_path_str: &str = hp_json_path.to_str().expect("Invalid UTF-8 in path");
     let input_string = args.input;
-    let hpo_path = Path::new(hp_json_path_str);
-    if hpo_path.exists() {
-        println!("Processing HPO JSON file: {:?}.", hp_json_path);
-    } else {
-        return Err(format!("Could not find HPO JSON file at {}.", hp_json_path_str).into());
+    // without --config, the builders keep their defaults (e.g., the options a dictionary was compiled with)
+    let options = args.config.as_ref().map(FenominalOptions::from_path).transpose()?;
+    let fenominal: Fenominal<FullCsrOntology, SimpleTerm> = match (&args.hp, &args.dictionary) {
+        (_, Some(dictionary_path)) => {
+            eprintln!("Loading mining dictionary: {:?}.", dictionary_path);
+            let dictionary = MiningDictionary::from_path(dictionary_path)?;
+            let mut builder = FenominalBuilder::from_dictionary(dictionary);
+            if let Some(options) = options {
+                builder = builder.options(options);
+            }
+            builder.build()?
+        }
+        (Some(hp_json_path), None) => {
+            let hp_json_path_str: &str = hp_json_path.to_str().expect("Invalid UTF-8 in path");
+            let hpo_path = Path::new(hp_json_path_str);
+            if hpo_path.exists() {
+                eprintln!("Processing HPO JSON file: {:?}.", hp_json_path);
+            } else {
+                return Err(format!("Could not find HPO JSON file at {}.", hp_json_path_str).into());
+            }
+            let loader = OntologyLoaderBuilder::new().obographs_parser().build();
+            let hpo: FullCsrOntology = loader.load_from_path(hp_json_path_str).unwrap();
+            let mut builder = Fenominal::builder(Arc::new(hpo));
+            if let Some(options) = options {
+                builder = builder.options(options);
+            }
+            builder.build()?
+        }
+        (None, None) => return Err("Either --hp or --dictionary is required.".into()),
+    };
+    eprintln!("[INFO] Input string: {}", input_string);
+    let MiningResult { provenance, hits: fenominal_hits, .. } = fenominal.mine_document(&args.id, &input_string)?;
+
+    match args.format {
+        OutputFormat::Json => {
+            // pretty-print the JSON response
+            let result = MiningResult { provenance, offset_unit: OffsetUnit::Bytes, hits: fenominal_hits }
+                .with_offset_unit(&input_string, args.offset_unit)?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Phenopacket => {
+            let hpo_version = provenance.ontology_version("HP").unwrap_or_default();
+            let phenopacket = Phenopacket::from_hits(args.id, &fenominal_hits, hpo_version).with_provenance(&provenance);
+            println!("{}", serde_json::to_string_pretty(&phenopacket)?);
+        }
+        OutputFormat::Fhir => {
+            let bundle = Bundle::from_hits(&args.id, &input_string, &fenominal_hits).with_provenance(&provenance);
+            println!("{}", serde_json::to_string_pretty(&bundle)?);
+        }
+        OutputFormat::Pubannotation => {
+            let document = PubAnnotation::from_hits(&input_string, &fenominal_hits).with_provenance(provenance);
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+        OutputFormat::Html => {
+            let sentences = fenominal.mine_sentences(&input_string)?;
+            print!("{}", render_html_with_provenance(&args.id, &sentences, &provenance));
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            let format = if args.format == OutputFormat::Tsv { TableFormat::Tsv } else { TableFormat::Csv };
+            let rows = HitRow::from_hits(&fenominal, &args.id, &input_string, &fenominal_hits);
+            let mut stdout = std::io::stdout().lock();
+            write_provenance(&mut stdout, &provenance)?;
+            write_hits(&mut stdout, format, &rows)?;
+        }
     }
-    println!("[INFO] Input string: {}", input_string);
-    let loader = OntologyLoaderBuilder::new().obographs_parser().build();
-    let hpo: FullCsrOntology = loader.load_from_path(hp_json_path_str).unwrap();
-    let hpo = Arc::new(hpo);
-    let fenominal = Fenominal::new(hpo);
-    let fenominal_hits: Vec<FenominalHit> = fenominal.process(&input_string)?;
-    
-    // pretty-print the JSON response
-    let pretty_fenominal_hits = serde_json::to_string_pretty(&fenominal_hits).unwrap();
-    println!("[INFO] Hits:\n{}", &pretty_fenominal_hits);
     Ok(())
 }
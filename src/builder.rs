@@ -0,0 +1,248 @@
+//! FenominalBuilder
+//!
+//! Configures and creates a [`Fenominal`]. The settings are collected in [`FenominalOptions`], which can
+//! also be loaded from a TOML or JSON file; [`FenominalBuilder::build`] validates them against the ontology.
+//! Instead of the ontology, the builder can start from a precompiled [`MiningDictionary`]
+//! (see [`FenominalBuilder::from_dictionary`]).
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use fenominal::{Fenominal, FenominalOptions};
+//! use ontolius::io::OntologyLoaderBuilder;
+//! use ontolius::ontology::csr::FullCsrOntology;
+//!
+//! let loader = OntologyLoaderBuilder::new().obographs_parser().build();
+//! let hpo: FullCsrOntology = loader.load_from_path("hp.json").unwrap();
+//! let options = FenominalOptions::from_path("fenominal.toml").unwrap();
+//! let fenominal = Fenominal::builder(Arc::new(hpo))
+//!     .options(options)
+//!     .negation_window(3)
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::term::{MinimalTerm, Synonymous};
+use ontolius::TermId;
+
+use crate::clinical_section::{SectionFilter, SectionLexicon};
+use crate::fenominal::Fenominal;
+use crate::hpo::mining_dictionary::MiningDictionary;
+use crate::hpo::obsolete::TermIdResolver;
+use crate::hpo::sentence_mapper::SentenceMapper;
+use crate::options::FenominalOptions;
+use crate::util::error::FenominalError;
+
+/// Where the labels and synonyms of the first ontology come from
+enum Source<O> {
+    Ontology(Arc<O>),
+    Dictionary(MiningDictionary),
+}
+
+pub struct FenominalBuilder<O, T>
+where
+    O: OntologyTerms<T> + HierarchyWalks,
+    T: MinimalTerm + Synonymous,
+{
+    source: Source<O>,
+    /// Other ontologies with their roots (see [`FenominalBuilder::ontology`])
+    other_ontologies: Vec<(Arc<O>, Vec<TermId>)>,
+    section_lexicon: SectionLexicon,
+    options: FenominalOptions,
+    id_resolver: Option<TermIdResolver>,
+    _marker: PhantomData<T>,
+}
+
+impl<O, T> FenominalBuilder<O, T>
+where
+    O: OntologyTerms<T> + HierarchyWalks,
+    T: MinimalTerm + Synonymous,
+{
+    /// A builder with the default options, which mines the Phenotypic abnormality branch of the ontology
+    pub fn new(ontology: Arc<O>) -> Self {
+        Self {
+            source: Source::Ontology(ontology),
+            other_ontologies: Vec::new(),
+            section_lexicon: SectionLexicon::empty(),
+            options: FenominalOptions::default(),
+            id_resolver: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// A builder that mines the terms of a precompiled dictionary without loading the ontology. The roots
+    /// and the label filter are those the dictionary was compiled with and cannot be changed. Onsets,
+    /// modifiers and organ systems are not determined, and redundancy removal is not available.
+    pub fn from_dictionary(dictionary: MiningDictionary) -> Self {
+        let options = FenominalOptions {
+            roots: dictionary.root_ids().iter().map(TermId::to_string).collect(),
+            omittable_labels: dictionary.omittable_labels().to_vec(),
+            min_synonym_length: dictionary.min_synonym_length(),
+            ..Default::default()
+        };
+        Self {
+            source: Source::Dictionary(dictionary),
+            other_ontologies: Vec::new(),
+            section_lexicon: SectionLexicon::empty(),
+            options,
+            id_resolver: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Replace all options, e.g., with options loaded from a file
+    pub fn options(mut self, options: FenominalOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Mine the descendants of the given roots (see [`Fenominal::from_roots`])
+    pub fn roots(mut self, roots: &[TermId]) -> Self {
+        self.options.roots = roots.iter().map(TermId::to_string).collect();
+        self
+    }
+
+    /// Also mine the descendants of the given root terms of another ontology (e.g., MONDO:0000001 disease),
+    /// in the same pass. Hits report the prefix of their ontology (see [`crate::FenominalHit::ontology`]).
+    /// If matches of several ontologies overlap, the longest match is kept; matches of the same length are
+    /// resolved in favor of the ontology that was added first.
+    pub fn ontology(mut self, ontology: Arc<O>, roots: &[TermId]) -> Self {
+        self.other_ontologies.push((ontology, roots.to_vec()));
+        self
+    }
+
+    /// Only report hits of the branches with the given roots (e.g., only Mode of inheritance terms).
+    /// The roots must be mined.
+    pub fn branch_filter(mut self, roots: &[TermId]) -> Self {
+        self.options.branch_filter = Some(roots.iter().map(TermId::to_string).collect());
+        self
+    }
+
+    /// Labels and synonyms that are not mined, because they are common in other contexts
+    /// (by default, "negative" and "weakness")
+    pub fn omittable_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.omittable_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Labels and synonyms shorter than this are not mined (by default, 4)
+    pub fn min_synonym_length(mut self, min_synonym_length: usize) -> Self {
+        self.options.min_synonym_length = min_synonym_length;
+        self
+    }
+
+    /// Words that are ignored when matching labels and synonyms (by default, articles and some prepositions)
+    pub fn stopwords<I, S>(mut self, stopwords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.stopwords = stopwords.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The number of words before and after a hit that are searched for negation triggers (by default, 5)
+    pub fn negation_window(mut self, negation_window: usize) -> Self {
+        self.options.negation_window = negation_window;
+        self
+    }
+
+    /// If `proband_only` is set, findings that refer to family members or other persons
+    /// (e.g., "his mother has hearing loss") are not reported.
+    pub fn proband_only(mut self, proband_only: bool) -> Self {
+        self.options.proband_only = proband_only;
+        self
+    }
+
+    /// Detect the sections of clinical notes with a lexicon of section headers (e.g., [`SectionLexicon::default`]),
+    /// and tag sentences and hits with their section. Section detection is off by default.
+    pub fn section_lexicon(mut self, lexicon: SectionLexicon) -> Self {
+        self.section_lexicon = lexicon;
+        self
+    }
+
+    /// Restrict text mining to some sections of a clinical note, e.g., to skip the "Plan". If no section
+    /// lexicon has been set, the sections are detected with [`SectionLexicon::default`].
+    pub fn section_filter(mut self, filter: SectionFilter) -> Self {
+        self.options.section_filter = filter;
+        self
+    }
+
+    /// Replace outdated term IDs of the hits by the IDs of the current terms, e.g., if the terms are mined
+    /// from a dictionary that was compiled from an older release (see [`TermIdResolver::remap_hits`]).
+    /// This applies to the hits of [`Fenominal::mine_sentences`] as well; the branch and the categories
+    /// of a replaced hit are those of the current term.
+    pub fn id_resolver(mut self, id_resolver: TermIdResolver) -> Self {
+        self.id_resolver = Some(id_resolver);
+        self
+    }
+
+    /// If `structured_forms` is set, lines laid out as key/value fields (`Seizures: No`, `Hypotonia - yes`)
+    /// or checklists (`[x] Scoliosis  [ ] Kyphosis`) are recognized by [`Fenominal::process`]. The key is
+    /// mapped to HPO terms and the value (or checkbox) determines whether the terms are observed or excluded.
+    pub fn structured_forms(mut self, structured_forms: bool) -> Self {
+        self.options.structured_forms = structured_forms;
+        self
+    }
+
+    /// If `remove_redundant` is set, only the most specific terms are reported: observed hits whose term is an
+    /// ancestor of another observed term ("seizures ... generalized tonic-clonic seizures") and excluded hits
+    /// whose term is a descendant of another excluded term are removed (see [`crate::hpo::redundancy`]).
+    /// Requires the ontology.
+    pub fn remove_redundant(mut self, remove_redundant: bool) -> Self {
+        self.options.remove_redundant = remove_redundant;
+        self
+    }
+
+    /// Validate the options (see [`FenominalOptions::validate`]) and check that the roots are terms
+    /// of their ontologies, or match the precompiled dictionary.
+    pub fn build(self) -> Result<Fenominal<O, T>, FenominalError> {
+        self.options.validate()?;
+        let mut sentence_mappers = Vec::new();
+        match self.source {
+            Source::Ontology(ontology) => {
+                let roots = self.options.root_ids()?;
+                check_roots(ontology.as_ref(), &roots)?;
+                sentence_mappers.push(SentenceMapper::with_options(ontology, &roots, &self.options));
+            }
+            Source::Dictionary(dictionary) => {
+                dictionary.check_options(&self.options)?;
+                if self.options.remove_redundant {
+                    return Err(FenominalError::invalid_option("remove_redundant", "requires the ontology"));
+                }
+                sentence_mappers.push(SentenceMapper::from_dictionary(&dictionary, None, &self.options));
+            }
+        }
+        for (ontology, roots) in self.other_ontologies {
+            check_roots(ontology.as_ref(), &roots)?;
+            sentence_mappers.push(SentenceMapper::with_options(ontology, &roots, &self.options));
+        }
+        for root in self.options.branch_filter_ids()?.unwrap_or_default() {
+            if !sentence_mappers.iter().any(|m| m.roots().contains(&root)) {
+                return Err(FenominalError::invalid_option("branch_filter", format!("{} is not one of the roots", root)));
+            }
+        }
+        Ok(Fenominal::from_options(sentence_mappers, self.section_lexicon, self.options, self.id_resolver))
+    }
+}
+
+/// Check that there is at least one root and that all roots are terms of the ontology
+pub(crate) fn check_roots<O, T>(ontology: &O, roots: &[TermId]) -> Result<(), FenominalError>
+where
+    O: OntologyTerms<T>,
+{
+    if roots.is_empty() {
+        return Err(FenominalError::invalid_option("roots", "at least one root is required"));
+    }
+    if let Some(root) = roots.iter().find(|r| ontology.term_by_id(*r).is_none()) {
+        return Err(FenominalError::invalid_option("roots", format!("{} is not a term of the ontology", root)));
+    }
+    Ok(())
+}
@@ -0,0 +1,298 @@
+//! ClinicalSection
+//!
+//! Clinical notes are organized in sections such as "Chief complaint", "Family history" or "Plan".
+//! The section in which a finding is mentioned changes its meaning; for instance, findings listed
+//! in the family history do not refer to the proband.
+//!
+//! A [`SectionLexicon`] maps header phrases to sections. A header is recognized if it starts a line
+//! (e.g., "History of present illness" on a line of its own, or "Chief complaint: seizures"), or if it
+//! starts a sentence and is followed by a colon ("... unremarkable. Family history: ...").
+
+use std::fmt;
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A section of a clinical note.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ClinicalSection {
+    ChiefComplaint,
+    HistoryOfPresentIllness,
+    PastMedicalHistory,
+    FamilyHistory,
+    SocialHistory,
+    ReviewOfSystems,
+    PhysicalExamination,
+    Investigations,
+    Impression,
+    Plan,
+    /// A section defined by a user-supplied header lexicon
+    Custom(String),
+}
+
+impl ClinicalSection {
+    /// Get the section from its identifier (e.g., `family_history`). Unknown identifiers
+    /// are returned as [`ClinicalSection::Custom`].
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "chief_complaint" => ClinicalSection::ChiefComplaint,
+            "history_of_present_illness" => ClinicalSection::HistoryOfPresentIllness,
+            "past_medical_history" => ClinicalSection::PastMedicalHistory,
+            "family_history" => ClinicalSection::FamilyHistory,
+            "social_history" => ClinicalSection::SocialHistory,
+            "review_of_systems" => ClinicalSection::ReviewOfSystems,
+            "physical_examination" => ClinicalSection::PhysicalExamination,
+            "investigations" => ClinicalSection::Investigations,
+            "impression" => ClinicalSection::Impression,
+            "plan" => ClinicalSection::Plan,
+            other => ClinicalSection::Custom(other.to_string()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            ClinicalSection::ChiefComplaint => "chief_complaint",
+            ClinicalSection::HistoryOfPresentIllness => "history_of_present_illness",
+            ClinicalSection::PastMedicalHistory => "past_medical_history",
+            ClinicalSection::FamilyHistory => "family_history",
+            ClinicalSection::SocialHistory => "social_history",
+            ClinicalSection::ReviewOfSystems => "review_of_systems",
+            ClinicalSection::PhysicalExamination => "physical_examination",
+            ClinicalSection::Investigations => "investigations",
+            ClinicalSection::Impression => "impression",
+            ClinicalSection::Plan => "plan",
+            ClinicalSection::Custom(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for ClinicalSection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// Header phrases that start a section of a clinical note.
+#[derive(Debug, Clone)]
+pub struct SectionLexicon {
+    /// Lower-case header phrases, longest first, with the corresponding section
+    headers: Vec<(String, ClinicalSection)>,
+}
+
+impl Default for SectionLexicon {
+    /// The lexicon with the headers in `data/section_headers.tsv`
+    fn default() -> Self {
+        let data = include_str!("../data/section_headers.tsv");
+        let mut lexicon = SectionLexicon::empty();
+        for line in data.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() >= 2 {
+                lexicon.add_header(parts[0], ClinicalSection::from_id(parts[1]));
+            }
+        }
+        lexicon
+    }
+}
+
+impl SectionLexicon {
+    /// A lexicon without any headers (no section detection)
+    pub fn empty() -> Self {
+        Self { headers: Vec::new() }
+    }
+
+    /// Add a header phrase (e.g., "Genetic testing") for a section.
+    pub fn add_header(&mut self, phrase: &str, section: ClinicalSection) {
+        let phrase = phrase.trim().to_ascii_lowercase();
+        if phrase.is_empty() {
+            return;
+        }
+        self.headers.retain(|(p, _)| *p != phrase);
+        self.headers.push((phrase, section));
+        // Longest headers first, so that "family history" wins over "history"
+        self.headers.sort_by_key(|(p, _)| std::cmp::Reverse(p.len()));
+    }
+
+    /// Builder-style version of [`SectionLexicon::add_header`]
+    pub fn with_header(mut self, phrase: &str, section: ClinicalSection) -> Self {
+        self.add_header(phrase, section);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Match a header at the start of `text`.
+    ///
+    /// Returns the section and the length (in bytes) of the header including a trailing colon and whitespace.
+    fn match_header(&self, text: &str, line_start: bool) -> Option<(&ClinicalSection, usize)> {
+        let bytes = text.as_bytes();
+        for (phrase, section) in &self.headers {
+            if bytes.len() < phrase.len() || !bytes[..phrase.len()].eq_ignore_ascii_case(phrase.as_bytes()) {
+                continue;
+            }
+            let rest = &text[phrase.len()..];
+            let trimmed = rest.trim_start_matches([' ', '\t']);
+            if let Some(after_colon) = trimmed.strip_prefix(':') {
+                let after_ws = after_colon.trim_start();
+                return Some((section, text.len() - after_ws.len()));
+            }
+            // Without a colon, the header must be on a line of its own
+            if line_start && (trimmed.is_empty() || trimmed.starts_with(['\n', '\r'])) {
+                let after_ws = trimmed.trim_start();
+                return Some((section, text.len() - after_ws.len()));
+            }
+        }
+        None
+    }
+}
+
+/// A contiguous part of a document that belongs to one section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextSection {
+    /// The section, or `None` for text that precedes the first header
+    pub section: Option<ClinicalSection>,
+    /// The coordinates of the header (including the colon), if any
+    pub header: Option<Range<usize>>,
+    /// The coordinates of the text that follows the header
+    pub body: Range<usize>,
+}
+
+/// Which sections of a document should be mined.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SectionFilter {
+    /// Mine the entire document
+    #[default]
+    All,
+    /// Only mine the listed sections
+    Include(Vec<ClinicalSection>),
+    /// Mine everything except the listed sections
+    Exclude(Vec<ClinicalSection>),
+}
+
+impl SectionFilter {
+    pub fn accepts(&self, section: Option<&ClinicalSection>) -> bool {
+        match self {
+            SectionFilter::All => true,
+            SectionFilter::Include(sections) => section.is_some_and(|s| sections.contains(s)),
+            SectionFilter::Exclude(sections) => !section.is_some_and(|s| sections.contains(s)),
+        }
+    }
+}
+
+impl fmt::Display for SectionFilter {
+    /// e.g., `all` or `exclude family_history`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ids = |sections: &[ClinicalSection]| sections.iter().map(ClinicalSection::id).collect::<Vec<_>>().join(",");
+        match self {
+            SectionFilter::All => write!(f, "all"),
+            SectionFilter::Include(sections) => write!(f, "include {}", ids(sections)),
+            SectionFilter::Exclude(sections) => write!(f, "exclude {}", ids(sections)),
+        }
+    }
+}
+
+/// Divide a text into sections. The returned sections cover the entire text.
+pub fn segment_sections(text: &str, lexicon: &SectionLexicon) -> Vec<TextSection> {
+    let mut sections = Vec::new();
+    let mut current = TextSection { section: None, header: None, body: 0..text.len() };
+    if lexicon.is_empty() {
+        sections.push(current);
+        return sections;
+    }
+    let mut line_start = true;
+    let mut sentence_start = true;
+    let mut skip_until = 0;
+    for (i, c) in text.char_indices() {
+        if i >= skip_until && (line_start || sentence_start) && !c.is_whitespace() && !is_bullet(c) {
+            if let Some((section, header_len)) = lexicon.match_header(&text[i..], line_start) {
+                current.body.end = i;
+                if current.header.is_some() || !text[current.body.clone()].trim().is_empty() {
+                    sections.push(current);
+                }
+                current = TextSection {
+                    section: Some(section.clone()),
+                    header: Some(i..i + header_len),
+                    body: i + header_len..text.len(),
+                };
+                skip_until = i + header_len;
+            }
+            line_start = false;
+            sentence_start = false;
+        }
+        if c == '\n' {
+            line_start = true;
+        } else if c == '.' || c == '!' || c == '?' {
+            sentence_start = true;
+        } else if !c.is_whitespace() && !is_bullet(c) {
+            line_start = false;
+            sentence_start = false;
+        }
+    }
+    current.body.end = text.len();
+    sections.push(current);
+    sections
+}
+
+fn is_bullet(c: char) -> bool {
+    c == '-' || c == '*' || c == '#' || c == '\u{2022}'
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NOTE: &str = "Chief complaint: seizures\nHistory of present illness\nThe boy had hypotonia. Family history: deafness in his uncle.\nPlan:\n- EEG";
+
+    #[test]
+    fn test_segment_sections() {
+        let sections = segment_sections(NOTE, &SectionLexicon::default());
+        let found: Vec<(Option<ClinicalSection>, &str)> = sections
+            .iter()
+            .map(|s| (s.section.clone(), &NOTE[s.body.clone()]))
+            .collect();
+        assert_eq!(
+            vec![
+                (Some(ClinicalSection::ChiefComplaint), "seizures\n"),
+                (Some(ClinicalSection::HistoryOfPresentIllness), "The boy had hypotonia. "),
+                (Some(ClinicalSection::FamilyHistory), "deafness in his uncle.\n"),
+                (Some(ClinicalSection::Plan), "- EEG"),
+            ],
+            found
+        );
+        assert_eq!("Chief complaint: ", &NOTE[sections[0].header.clone().unwrap()]);
+    }
+
+    #[test]
+    fn test_header_requires_colon_inside_line() {
+        let text = "Exam showed scoliosis. Plan is to follow up.";
+        let sections = segment_sections(text, &SectionLexicon::default());
+        assert_eq!(1, sections.len());
+        assert_eq!(None, sections[0].section);
+    }
+
+    #[test]
+    fn test_custom_header() {
+        let lexicon = SectionLexicon::empty()
+            .with_header("Genetic testing", ClinicalSection::Custom("genetic_testing".to_string()));
+        let sections = segment_sections("Intro.\nGenetic testing: pending", &lexicon);
+        assert_eq!(2, sections.len());
+        assert_eq!(Some(ClinicalSection::Custom("genetic_testing".to_string())), sections[1].section);
+        assert_eq!(None, sections[0].section);
+    }
+
+    #[test]
+    fn test_section_filter() {
+        let exclude = SectionFilter::Exclude(vec![ClinicalSection::FamilyHistory]);
+        assert!(!exclude.accepts(Some(&ClinicalSection::FamilyHistory)));
+        assert!(exclude.accepts(None));
+        let include = SectionFilter::Include(vec![ClinicalSection::PhysicalExamination]);
+        assert!(include.accepts(Some(&ClinicalSection::PhysicalExamination)));
+        assert!(!include.accepts(None));
+    }
+}
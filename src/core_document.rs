@@ -1,50 +1,98 @@
+use crate::clinical_section::{segment_sections, ClinicalSection, SectionLexicon};
 use crate::simple_sentence::SimpleSentence;
 
 pub struct CoreDocument {
-    original_text: String,
     sentences: Vec<SimpleSentence>,
-    //private final static Set<Character> sentenceEndPunctuation = Set.of('.', '!', '?');
 }
 
 
 
 impl CoreDocument {
-    /// divide original text into sentences with boundaries on period, exclamation or question mark
-    pub fn new(text: &str) -> Self {
+    /// Divide the original text into sections (see [`SectionLexicon`]), and each section into sentences
+    /// with boundaries on period, exclamation or question mark. Section headers are not part of any sentence.
+    ///
+    /// All positions are byte offsets into `text`.
+    pub fn new(text: &str, lexicon: &SectionLexicon) -> Self {
         let mut ssentences = Vec::new();
-
-        let mut start = 0;
-        let chars: Vec<char> = text.chars().collect();
-        for (i, &c) in chars.iter().enumerate() {
-            if c == '.' || c == '!' || c == '?' {
-                // Look ahead to include the period and any following space(s)
-                let mut end = i + 1;
-                while end < chars.len() && chars[end].is_whitespace() {
-                    end += 1;
-                }
-                let sentence: String = chars[start..end].iter().collect();
-                ssentences.push(SimpleSentence::new(sentence.trim_end(), start, end));
-                start = end;
-            }
+        for section in segment_sections(text, lexicon) {
+            split_sentences(text, section.body.clone(), section.section.as_ref(), &mut ssentences);
         }
-            // Add trailing sentence if any
-        if start < chars.len() {
-            let sentence: String = chars[start..].iter().collect();
-            //result.push((start, chars.len(), sentence.trim_end()));
-            ssentences.push(SimpleSentence::new(&sentence.trim_end(), start, chars.len()));
-        }
-    
         CoreDocument {
-            original_text: text.into(),
             sentences: ssentences,
         }
     }
 
-    pub fn get_sentences(&self) -> &[SimpleSentence] {
-        &self.sentences
+    pub fn into_sentences(self) -> Vec<SimpleSentence> {
+        self.sentences
+    }
+}
+
+fn split_sentences(
+    text: &str,
+    range: std::ops::Range<usize>,
+    section: Option<&ClinicalSection>,
+    ssentences: &mut Vec<SimpleSentence>,
+) {
+    let offset = range.start;
+    let body = &text[range];
+    let mut start = 0;
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '.' || c == '!' || c == '?' {
+            // Look ahead to include the period and any following space(s)
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, w)) = chars.peek() {
+                if !w.is_whitespace() {
+                    break;
+                }
+                end = j + w.len_utf8();
+                chars.next();
+            }
+            push_sentence(&body[start..end], offset + start, offset + end, section, ssentences);
+            start = end;
+        }
+    }
+    // Add trailing sentence if any
+    if start < body.len() {
+        push_sentence(&body[start..], offset + start, offset + body.len(), section, ssentences);
+    }
+}
+
+fn push_sentence(
+    sentence: &str,
+    start: usize,
+    end: usize,
+    section: Option<&ClinicalSection>,
+    ssentences: &mut Vec<SimpleSentence>,
+) {
+    if sentence.trim().is_empty() {
+        return;
+    }
+    ssentences.push(SimpleSentence::new(sentence.trim_end(), start, end).with_section(section.cloned()));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sentences_use_byte_offsets() {
+        let text = "Café au lait spots. Scoliosis.";
+        let doc = CoreDocument::new(text, &SectionLexicon::empty());
+        let sentences = doc.into_sentences();
+        assert_eq!(2, sentences.len());
+        let second = &sentences[1];
+        assert_eq!("Scoliosis.", &text[second.get_start_pos()..second.get_start_pos() + 10]);
     }
 
-    pub fn original_text(&self) -> &str {
-        &self.original_text
+    #[test]
+    fn test_sentences_are_tagged_with_section() {
+        let text = "Seizures.\nFamily history: deafness in his uncle.";
+        let doc = CoreDocument::new(text, &SectionLexicon::default());
+        let sentences = doc.into_sentences();
+        assert_eq!(2, sentences.len());
+        assert_eq!(None, sentences[0].get_section());
+        assert_eq!(Some(&ClinicalSection::FamilyHistory), sentences[1].get_section());
+        assert_eq!("deafness in his uncle.", sentences[1].get_sentence());
     }
 }
@@ -0,0 +1,627 @@
+//! BioC input and output
+//!
+//! [BioC](https://bioc.sourceforge.net/) is the exchange format of many biomedical text-mining corpora
+//! and tools (e.g., PubTator). A collection contains documents, which are divided into passages
+//! (e.g., title and abstract). Each passage has an offset within its document, and annotations
+//! refer to the document coordinates.
+//!
+//! Collections can be read and written as BioC XML and as BioC JSON. [`annotate_collection`] mines
+//! every passage (or every sentence of passages that are split into sentences) and adds an annotation
+//! for each hit, with the infons `type` (the ontology prefix, e.g., `HP`), `identifier`, `label` and
+//! `assertion` (`present` or `absent`). [`BioCCollection::set_provenance`] records the versions and the
+//! configuration of the mining as infons of the collection (e.g., `hp_version`).
+//!
+//! Offsets and lengths are counted in Unicode characters, as in the BioC reference implementations.
+
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::term::{MinimalTerm, Synonymous};
+use serde::{Deserialize, Serialize};
+
+use crate::export::char_offset;
+use crate::fenominal::Fenominal;
+use crate::models::fenominal_model::FenominalHit;
+use crate::models::provenance::Provenance;
+use crate::util::error::FenominalError;
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE collection SYSTEM \"BioC.dtd\">\n";
+
+/// A BioC collection of documents
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCCollection {
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub infons: BTreeMap<String, String>,
+    #[serde(default)]
+    pub documents: Vec<BioCDocument>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCDocument {
+    pub id: String,
+    #[serde(default)]
+    pub infons: BTreeMap<String, String>,
+    #[serde(default)]
+    pub passages: Vec<BioCPassage>,
+    #[serde(default)]
+    pub relations: Vec<BioCRelation>,
+}
+
+/// A passage of a document, e.g., the title or the abstract. Passages contain either text or sentences.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCPassage {
+    /// The offset of the passage within the document
+    pub offset: usize,
+    #[serde(default)]
+    pub infons: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub sentences: Vec<BioCSentence>,
+    #[serde(default)]
+    pub annotations: Vec<BioCAnnotation>,
+    #[serde(default)]
+    pub relations: Vec<BioCRelation>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCSentence {
+    /// The offset of the sentence within the document
+    pub offset: usize,
+    #[serde(default)]
+    pub infons: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<BioCAnnotation>,
+    #[serde(default)]
+    pub relations: Vec<BioCRelation>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCAnnotation {
+    pub id: String,
+    #[serde(default)]
+    pub infons: BTreeMap<String, String>,
+    #[serde(default)]
+    pub locations: Vec<BioCLocation>,
+    #[serde(default)]
+    pub text: String,
+}
+
+/// The coordinates of an annotation within the document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCLocation {
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCRelation {
+    pub id: String,
+    #[serde(default)]
+    pub infons: BTreeMap<String, String>,
+    #[serde(default)]
+    pub nodes: Vec<BioCNode>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BioCNode {
+    pub refid: String,
+    #[serde(default)]
+    pub role: String,
+}
+
+impl BioCCollection {
+    /// Read a collection in BioC XML format
+    pub fn from_xml_str(xml: &str) -> Result<Self, FenominalError> {
+        let collection: xml::Collection = quick_xml::de::from_str(xml).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not read BioC XML: {}", e),
+        })?;
+        Ok(collection.into())
+    }
+
+    /// Read a collection in BioC XML format
+    pub fn from_xml_reader<R: BufRead>(reader: R) -> Result<Self, FenominalError> {
+        let collection: xml::Collection = quick_xml::de::from_reader(reader).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not read BioC XML: {}", e),
+        })?;
+        Ok(collection.into())
+    }
+
+    /// Write the collection in BioC XML format
+    pub fn to_xml(&self) -> Result<String, FenominalError> {
+        let mut body = String::new();
+        let mut serializer = quick_xml::se::Serializer::new(&mut body);
+        serializer.indent(' ', 2);
+        xml::Collection::from(self)
+            .serialize(serializer)
+            .map_err(|e| FenominalError::Parsing {
+                reason: format!("could not write BioC XML: {}", e),
+            })?;
+        Ok(format!("{}{}\n", XML_HEADER, body))
+    }
+
+    /// Read a collection in BioC JSON format
+    pub fn from_json_str(json: &str) -> Result<Self, FenominalError> {
+        serde_json::from_str(json).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not read BioC JSON: {}", e),
+        })
+    }
+
+    /// Add the provenance of the annotations to the infons of the collection. The document ID of the
+    /// provenance is not added, because the collection has its own document IDs.
+    pub fn set_provenance(&mut self, provenance: &Provenance) {
+        for (key, value) in provenance.to_pairs() {
+            if key != "document_id" {
+                self.infons.insert(key, value);
+            }
+        }
+    }
+
+    /// Write the collection in BioC JSON format
+    pub fn to_json(&self) -> Result<String, FenominalError> {
+        serde_json::to_string_pretty(self).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not write BioC JSON: {}", e),
+        })
+    }
+}
+
+/// Mine the text of every passage (or of every sentence of passages that are split into sentences)
+/// and add the hits as annotations. Annotation IDs are numbered per document, after the largest numeric ID of
+/// the existing annotations and relations.
+pub fn annotate_collection<O, T>(fenominal: &Fenominal<O, T>, collection: &mut BioCCollection) -> Result<(), FenominalError>
+where
+    O: OntologyTerms<T> + HierarchyWalks,
+    T: MinimalTerm + Synonymous,
+{
+    for document in collection.documents.iter_mut() {
+        let mut next_id = max_numeric_id(document);
+        for passage in document.passages.iter_mut() {
+            if let Some(text) = &passage.text {
+                let hits = fenominal.process(text)?;
+                passage.annotations.extend(to_annotations(text, passage.offset, &hits, &mut next_id));
+            }
+            for sentence in passage.sentences.iter_mut() {
+                if let Some(text) = &sentence.text {
+                    let hits = fenominal.process(text)?;
+                    sentence.annotations.extend(to_annotations(text, sentence.offset, &hits, &mut next_id));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The largest ID of the annotations and relations of a document that is a number (0 if there is none)
+fn max_numeric_id(document: &BioCDocument) -> usize {
+    let passage_ids = document.passages.iter().flat_map(|p| {
+        let sentence_ids = p.sentences.iter().flat_map(|s| {
+            s.annotations.iter().map(|a| &a.id).chain(s.relations.iter().map(|r| &r.id))
+        });
+        p.annotations.iter().map(|a| &a.id).chain(p.relations.iter().map(|r| &r.id)).chain(sentence_ids)
+    });
+    passage_ids
+        .chain(document.relations.iter().map(|r| &r.id))
+        .filter_map(|id| id.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Convert hits (byte coordinates in `text`) to annotations (character coordinates in the document)
+fn to_annotations(text: &str, offset: usize, hits: &[FenominalHit], next_id: &mut usize) -> Vec<BioCAnnotation> {
+    hits.iter()
+        .map(|hit| {
+            *next_id += 1;
+            let mut infons = BTreeMap::new();
+            let prefix = hit.term_id.split(':').next().unwrap_or_default();
+            infons.insert("type".to_string(), hit.ontology.clone().unwrap_or_else(|| prefix.to_string()));
+            infons.insert("identifier".to_string(), hit.term_id.clone());
+            infons.insert("label".to_string(), hit.label.clone());
+            let assertion = if hit.is_observed { "present" } else { "absent" };
+            infons.insert("assertion".to_string(), assertion.to_string());
+            let matched = &text[hit.span.clone()];
+            BioCAnnotation {
+                id: next_id.to_string(),
+                infons,
+                locations: vec![BioCLocation {
+                    offset: offset + char_offset(text, hit.span.start),
+                    length: matched.chars().count(),
+                }],
+                text: matched.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// The layout of BioC XML, where infons are `<infon key="...">` elements and annotation IDs are attributes
+mod xml {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "collection")]
+    pub(super) struct Collection {
+        #[serde(default)]
+        source: String,
+        #[serde(default)]
+        date: String,
+        #[serde(default)]
+        key: String,
+        #[serde(default, rename = "infon")]
+        infons: Vec<Infon>,
+        #[serde(default, rename = "document")]
+        documents: Vec<Document>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Infon {
+        #[serde(rename = "@key")]
+        key: String,
+        #[serde(default, rename = "$text")]
+        value: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Document {
+        id: String,
+        #[serde(default, rename = "infon")]
+        infons: Vec<Infon>,
+        #[serde(default, rename = "passage")]
+        passages: Vec<Passage>,
+        #[serde(default, rename = "relation")]
+        relations: Vec<Relation>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Passage {
+        #[serde(default, rename = "infon")]
+        infons: Vec<Infon>,
+        offset: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+        #[serde(default, rename = "sentence")]
+        sentences: Vec<Sentence>,
+        #[serde(default, rename = "annotation")]
+        annotations: Vec<Annotation>,
+        #[serde(default, rename = "relation")]
+        relations: Vec<Relation>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Sentence {
+        #[serde(default, rename = "infon")]
+        infons: Vec<Infon>,
+        offset: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+        #[serde(default, rename = "annotation")]
+        annotations: Vec<Annotation>,
+        #[serde(default, rename = "relation")]
+        relations: Vec<Relation>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Annotation {
+        #[serde(rename = "@id")]
+        id: String,
+        #[serde(default, rename = "infon")]
+        infons: Vec<Infon>,
+        #[serde(default, rename = "location")]
+        locations: Vec<Location>,
+        #[serde(default)]
+        text: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Location {
+        #[serde(rename = "@offset")]
+        offset: usize,
+        #[serde(rename = "@length")]
+        length: usize,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Relation {
+        #[serde(rename = "@id")]
+        id: String,
+        #[serde(default, rename = "infon")]
+        infons: Vec<Infon>,
+        #[serde(default, rename = "node")]
+        nodes: Vec<Node>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Node {
+        #[serde(rename = "@refid")]
+        refid: String,
+        #[serde(default, rename = "@role")]
+        role: String,
+    }
+
+    fn to_map(infons: Vec<Infon>) -> BTreeMap<String, String> {
+        infons.into_iter().map(|i| (i.key, i.value)).collect()
+    }
+
+    fn from_map(infons: &BTreeMap<String, String>) -> Vec<Infon> {
+        infons
+            .iter()
+            .map(|(key, value)| Infon {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    }
+
+    impl From<Collection> for super::BioCCollection {
+        fn from(c: Collection) -> Self {
+            Self {
+                source: c.source,
+                date: c.date,
+                key: c.key,
+                infons: to_map(c.infons),
+                documents: c.documents.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<&super::BioCCollection> for Collection {
+        fn from(c: &super::BioCCollection) -> Self {
+            Self {
+                source: c.source.clone(),
+                date: c.date.clone(),
+                key: c.key.clone(),
+                infons: from_map(&c.infons),
+                documents: c.documents.iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<Document> for super::BioCDocument {
+        fn from(d: Document) -> Self {
+            Self {
+                id: d.id,
+                infons: to_map(d.infons),
+                passages: d.passages.into_iter().map(Into::into).collect(),
+                relations: d.relations.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<&super::BioCDocument> for Document {
+        fn from(d: &super::BioCDocument) -> Self {
+            Self {
+                id: d.id.clone(),
+                infons: from_map(&d.infons),
+                passages: d.passages.iter().map(Into::into).collect(),
+                relations: d.relations.iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<Passage> for super::BioCPassage {
+        fn from(p: Passage) -> Self {
+            Self {
+                offset: p.offset,
+                infons: to_map(p.infons),
+                text: p.text,
+                sentences: p.sentences.into_iter().map(Into::into).collect(),
+                annotations: p.annotations.into_iter().map(Into::into).collect(),
+                relations: p.relations.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<&super::BioCPassage> for Passage {
+        fn from(p: &super::BioCPassage) -> Self {
+            Self {
+                infons: from_map(&p.infons),
+                offset: p.offset,
+                text: p.text.clone(),
+                sentences: p.sentences.iter().map(Into::into).collect(),
+                annotations: p.annotations.iter().map(Into::into).collect(),
+                relations: p.relations.iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<Sentence> for super::BioCSentence {
+        fn from(s: Sentence) -> Self {
+            Self {
+                offset: s.offset,
+                infons: to_map(s.infons),
+                text: s.text,
+                annotations: s.annotations.into_iter().map(Into::into).collect(),
+                relations: s.relations.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<&super::BioCSentence> for Sentence {
+        fn from(s: &super::BioCSentence) -> Self {
+            Self {
+                infons: from_map(&s.infons),
+                offset: s.offset,
+                text: s.text.clone(),
+                annotations: s.annotations.iter().map(Into::into).collect(),
+                relations: s.relations.iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<Annotation> for super::BioCAnnotation {
+        fn from(a: Annotation) -> Self {
+            Self {
+                id: a.id,
+                infons: to_map(a.infons),
+                locations: a
+                    .locations
+                    .into_iter()
+                    .map(|l| super::BioCLocation {
+                        offset: l.offset,
+                        length: l.length,
+                    })
+                    .collect(),
+                text: a.text,
+            }
+        }
+    }
+
+    impl From<&super::BioCAnnotation> for Annotation {
+        fn from(a: &super::BioCAnnotation) -> Self {
+            Self {
+                id: a.id.clone(),
+                infons: from_map(&a.infons),
+                locations: a
+                    .locations
+                    .iter()
+                    .map(|l| Location {
+                        offset: l.offset,
+                        length: l.length,
+                    })
+                    .collect(),
+                text: a.text.clone(),
+            }
+        }
+    }
+
+    impl From<Relation> for super::BioCRelation {
+        fn from(r: Relation) -> Self {
+            Self {
+                id: r.id,
+                infons: to_map(r.infons),
+                nodes: r
+                    .nodes
+                    .into_iter()
+                    .map(|n| super::BioCNode {
+                        refid: n.refid,
+                        role: n.role,
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl From<&super::BioCRelation> for Relation {
+        fn from(r: &super::BioCRelation) -> Self {
+            Self {
+                id: r.id.clone(),
+                infons: from_map(&r.infons),
+                nodes: r
+                    .nodes
+                    .iter()
+                    .map(|n| Node {
+                        refid: n.refid.clone(),
+                        role: n.role.clone(),
+                    })
+                    .collect(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE collection SYSTEM "BioC.dtd">
+<collection>
+  <source>PubMed</source>
+  <date>20250303</date>
+  <key>collection.key</key>
+  <document>
+    <id>12345</id>
+    <passage>
+      <infon key="type">title</infon>
+      <offset>0</offset>
+      <text>Café-au-lait spots &amp; scoliosis</text>
+    </passage>
+    <passage>
+      <infon key="type">abstract</infon>
+      <offset>32</offset>
+      <text>A boy with seizures.</text>
+      <annotation id="1">
+        <infon key="type">Disease</infon>
+        <location offset="43" length="8"/>
+        <text>seizures</text>
+      </annotation>
+    </passage>
+  </document>
+</collection>
+"#;
+
+    #[test]
+    fn test_read_xml() {
+        let collection = BioCCollection::from_xml_str(XML).unwrap();
+        assert_eq!("PubMed", collection.source);
+        let document = &collection.documents[0];
+        assert_eq!("12345", document.id);
+        assert_eq!(2, document.passages.len());
+        let title = &document.passages[0];
+        assert_eq!(Some("title"), title.infons.get("type").map(String::as_str));
+        assert_eq!(Some("Café-au-lait spots & scoliosis"), title.text.as_deref());
+        let abstract_passage = &document.passages[1];
+        assert_eq!(32, abstract_passage.offset);
+        assert_eq!(
+            vec![BioCLocation { offset: 43, length: 8 }],
+            abstract_passage.annotations[0].locations
+        );
+    }
+
+    #[test]
+    fn test_xml_roundtrip() {
+        let collection = BioCCollection::from_xml_str(XML).unwrap();
+        let xml = collection.to_xml().unwrap();
+        assert!(xml.starts_with(XML_HEADER));
+        assert!(xml.contains(r#"<infon key="type">title</infon>"#));
+        assert!(xml.contains(r#"<location offset="43" length="8"/>"#));
+        assert!(xml.contains("spots &amp; scoliosis"));
+        assert_eq!(collection, BioCCollection::from_xml_str(&xml).unwrap());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let collection = BioCCollection::from_xml_str(XML).unwrap();
+        let json = collection.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!("title", value["documents"][0]["passages"][0]["infons"]["type"]);
+        assert_eq!(43, value["documents"][0]["passages"][1]["annotations"][0]["locations"][0]["offset"]);
+        assert_eq!(collection, BioCCollection::from_json_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_max_numeric_id() {
+        let mut collection = BioCCollection::from_xml_str(XML).unwrap();
+        let document = &mut collection.documents[0];
+        assert_eq!(1, max_numeric_id(document));
+        let mut annotation = document.passages[1].annotations[0].clone();
+        annotation.id = "T9".to_string();
+        document.passages[0].annotations.push(annotation.clone());
+        annotation.id = "7".to_string();
+        document.passages[0].annotations.push(annotation);
+        // IDs are not consecutive: the next ID follows the largest one, not the number of annotations
+        assert_eq!(7, max_numeric_id(document));
+    }
+
+    #[test]
+    fn test_to_annotations_uses_character_offsets() {
+        let text = "Café-au-lait spots and scoliosis";
+        let start = text.find("scoliosis").unwrap();
+        let hit = FenominalHit::new("HP:0002650".to_string(), "Scoliosis", start..start + 9, false);
+        let mut next_id = 1;
+        let annotations = to_annotations(text, 100, &[hit], &mut next_id);
+        assert_eq!("2", annotations[0].id);
+        assert_eq!(BioCLocation { offset: 100 + 23, length: 9 }, annotations[0].locations[0]);
+        assert_eq!(Some("absent"), annotations[0].infons.get("assertion").map(String::as_str));
+        assert_eq!(Some("HP"), annotations[0].infons.get("type").map(String::as_str));
+    }
+}
@@ -0,0 +1,256 @@
+//! brat standoff format
+//!
+//! [brat](https://brat.nlplab.org/standoff.html) stores the annotations of a text file `doc.txt` in
+//! a file `doc.ann`. Each hit is written as a text-bound annotation (`T`) whose type is the ontology
+//! prefix (e.g., `HP`), with a normalization (`N`) to the term and a `Negated` attribute (`A`) for
+//! excluded findings (the fields are separated by tabs):
+//!
+//! ```text
+//! T1    HP 16 24    seizures
+//! N1    Reference T1 HP:0001250    Seizure
+//! T2    HP 39 48    scoliosis
+//! N2    Reference T2 HP:0002650    Scoliosis
+//! A1    Negated T2
+//! ```
+//!
+//! Offsets are counted in Unicode characters of the `.txt` file, as in brat itself. Annotation files
+//! can be read back, e.g., to use curated annotations as a gold standard.
+//!
+//! [`BratDocument::to_ann_with_provenance`] also records the provenance of the hits as annotator notes
+//! (`#`) of the first entity, one note per key, e.g., `#1    AnnotatorNotes T1    hp_version: 2025-03-03`.
+//! brat notes need a target, so a document without entities has no provenance notes.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::export::{byte_offset, char_offset};
+use crate::models::fenominal_model::{FenominalHit, OntologyClass};
+use crate::models::provenance::Provenance;
+use crate::util::error::FenominalError;
+
+/// The attribute of entities that refer to excluded findings
+const NEGATED: &str = "Negated";
+
+/// A text-bound annotation of a brat document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BratEntity {
+    /// The ID of the annotation, e.g., `T1`
+    pub id: String,
+    /// The entity type, e.g., `HP`
+    pub entity_type: String,
+    /// The character ranges of the annotation (more than one for discontinuous annotations)
+    pub spans: Vec<Range<usize>>,
+    pub text: String,
+    /// The term of the normalization of the annotation, if any
+    pub term: Option<OntologyClass>,
+    pub is_negated: bool,
+}
+
+/// The annotations of one text file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BratDocument {
+    pub entities: Vec<BratEntity>,
+}
+
+impl BratDocument {
+    /// One entity per hit; `text` is the text the hits were mined from.
+    pub fn from_hits(text: &str, hits: &[FenominalHit]) -> Self {
+        let entities = hits
+            .iter()
+            .enumerate()
+            .map(|(i, hit)| {
+                let span = char_offset(text, hit.span.start)..char_offset(text, hit.span.end);
+                BratEntity {
+                    id: format!("T{}", i + 1),
+                    entity_type: hit.ontology.clone().unwrap_or_else(|| prefix(&hit.term_id).to_string()),
+                    spans: vec![span],
+                    text: text[hit.span.clone()].to_string(),
+                    term: Some(OntologyClass::new(hit.term_id.clone(), hit.label.clone())),
+                    is_negated: !hit.is_observed,
+                }
+            })
+            .collect();
+        Self { entities }
+    }
+
+    /// Parse the contents of an `.ann` file. Annotations other than entities, normalizations and
+    /// `Negated` attributes (e.g., relations and events) are ignored.
+    pub fn parse(ann: &str) -> Result<Self, FenominalError> {
+        let mut entities: Vec<BratEntity> = Vec::new();
+        let mut normalizations = Vec::new();
+        let mut negated = Vec::new();
+        for (n, line) in ann.lines().enumerate() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let malformed = || FenominalError::Parsing {
+                reason: format!("malformed brat annotation at line {}: {}", n + 1, line),
+            };
+            let args: Vec<&str> = fields.get(1).map_or(Vec::new(), |f| f.split(' ').collect());
+            match line.chars().next() {
+                Some('T') => {
+                    let (entity_type, ranges) = fields.get(1).and_then(|f| f.split_once(' ')).ok_or_else(malformed)?;
+                    let spans = ranges
+                        .split(';')
+                        .map(|r| parse_range(r).ok_or_else(malformed))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    entities.push(BratEntity {
+                        id: fields[0].to_string(),
+                        entity_type: entity_type.to_string(),
+                        spans,
+                        text: fields.get(2).unwrap_or(&"").to_string(),
+                        term: None,
+                        is_negated: false,
+                    });
+                }
+                Some('N') => {
+                    // N1	Reference T1 HP:0001250	Seizure
+                    if args.len() != 3 {
+                        return Err(malformed());
+                    }
+                    let label = fields.get(2).unwrap_or(&"").to_string();
+                    normalizations.push((args[1].to_string(), OntologyClass::new(args[2], label)));
+                }
+                Some('A') | Some('M') if args.first() == Some(&NEGATED) => {
+                    // A1	Negated T2
+                    negated.push(args.get(1).ok_or_else(malformed)?.to_string());
+                }
+                _ => {}
+            }
+        }
+        for entity in entities.iter_mut() {
+            entity.term = normalizations
+                .iter()
+                .find(|(target, _)| *target == entity.id)
+                .map(|(_, term)| term.clone());
+            entity.is_negated = negated.contains(&entity.id);
+        }
+        Ok(Self { entities })
+    }
+
+    /// The contents of the `.ann` file
+    pub fn to_ann(&self) -> String {
+        self.write_ann(None)
+    }
+
+    /// The contents of the `.ann` file as [`BratDocument::to_ann`], followed by the provenance of the
+    /// hits as annotator notes of the first entity
+    pub fn to_ann_with_provenance(&self, provenance: &Provenance) -> String {
+        self.write_ann(Some(provenance))
+    }
+
+    fn write_ann(&self, provenance: Option<&Provenance>) -> String {
+        let mut ann = String::new();
+        let mut attributes = 0;
+        for (i, entity) in self.entities.iter().enumerate() {
+            let ranges: Vec<String> = entity.spans.iter().map(|r| format!("{} {}", r.start, r.end)).collect();
+            // brat does not allow line breaks in the text of an annotation
+            let text = entity.text.replace(['\n', '\r'], " ");
+            let _ = writeln!(ann, "{}\t{} {}\t{}", entity.id, entity.entity_type, ranges.join(";"), text);
+            if let Some(term) = &entity.term {
+                let _ = writeln!(ann, "N{}\tReference {} {}\t{}", i + 1, entity.id, term.id, term.label);
+            }
+            if entity.is_negated {
+                attributes += 1;
+                let _ = writeln!(ann, "A{}\t{} {}", attributes, NEGATED, entity.id);
+            }
+        }
+        if let (Some(provenance), Some(first)) = (provenance, self.entities.first()) {
+            for (i, (key, value)) in provenance.to_pairs().into_iter().enumerate() {
+                let value = value.replace(['\n', '\r'], " ");
+                let _ = writeln!(ann, "#{}\tAnnotatorNotes {}\t{}: {}", i + 1, first.id, key, value);
+            }
+        }
+        ann
+    }
+
+    /// Hits of the normalized entities; `text` is the contents of the `.txt` file. Discontinuous
+    /// annotations are converted to a hit from the start of the first to the end of the last range.
+    pub fn to_hits(&self, text: &str) -> Result<Vec<FenominalHit>, FenominalError> {
+        let mut hits = Vec::new();
+        for entity in &self.entities {
+            let Some(term) = &entity.term else {
+                continue;
+            };
+            let (Some(first), Some(last)) = (entity.spans.first(), entity.spans.last()) else {
+                continue;
+            };
+            let out_of_range = || FenominalError::Parsing {
+                reason: format!("span of brat annotation {} is outside of the text", entity.id),
+            };
+            let start = byte_offset(text, first.start).ok_or_else(out_of_range)?;
+            let end = byte_offset(text, last.end).ok_or_else(out_of_range)?;
+            let mut hit = FenominalHit::new(term.id.clone(), &term.label, start..end, !entity.is_negated);
+            if entity.entity_type != prefix(&term.id) {
+                hit.ontology = Some(entity.entity_type.clone());
+            }
+            hits.push(hit);
+        }
+        hits.sort_by_key(|h| h.span.start);
+        Ok(hits)
+    }
+}
+
+fn prefix(term_id: &str) -> &str {
+    term_id.split(':').next().unwrap_or_default()
+}
+
+fn parse_range(range: &str) -> Option<Range<usize>> {
+    let (start, end) = range.split_once(' ')?;
+    Some(start.parse().ok()?..end.parse().ok()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEXT: &str = "Café owner with seizures. No scoliosis.";
+
+    fn hits() -> Vec<FenominalHit> {
+        let seizures = TEXT.find("seizures").unwrap();
+        let scoliosis = TEXT.find("scoliosis").unwrap();
+        vec![
+            FenominalHit::new("HP:0001250".to_string(), "Seizure", seizures..seizures + 8, true),
+            FenominalHit::new("HP:0002650".to_string(), "Scoliosis", scoliosis..scoliosis + 9, false),
+        ]
+    }
+
+    #[test]
+    fn test_to_ann() {
+        let ann = BratDocument::from_hits(TEXT, &hits()).to_ann();
+        let expected = "T1\tHP 16 24\tseizures\n\
+                        N1\tReference T1 HP:0001250\tSeizure\n\
+                        T2\tHP 29 38\tscoliosis\n\
+                        N2\tReference T2 HP:0002650\tScoliosis\n\
+                        A1\tNegated T2\n";
+        assert_eq!(expected, ann);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let document = BratDocument::from_hits(TEXT, &hits());
+        let parsed = BratDocument::parse(&document.to_ann()).unwrap();
+        assert_eq!(document, parsed);
+        assert_eq!(hits(), parsed.to_hits(TEXT).unwrap());
+    }
+
+    #[test]
+    fn test_parse_ignores_other_annotations() {
+        let ann = "T1\tHP 0 4;16 24\tCafé seizures\n\
+                   T2\tPerson 5 10\towner\n\
+                   R1\tHas Arg1:T2 Arg2:T1\n\
+                   #1\tAnnotatorNotes T1\tcurated\n\
+                   N1\tReference T1 HP:0001250\tSeizure\n";
+        let document = BratDocument::parse(ann).unwrap();
+        assert_eq!(2, document.entities.len());
+        assert_eq!(vec![0..4, 16..24], document.entities[0].spans);
+        let hits = document.to_hits(TEXT).unwrap();
+        assert_eq!(1, hits.len());
+        assert_eq!(0..25, hits[0].span);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(BratDocument::parse("T1\tHP 0 x\tCafé").is_err());
+        let document = BratDocument::parse("T1\tHP 100 108\tseizures\nN1\tReference T1 HP:0001250\tSeizure").unwrap();
+        assert!(document.to_hits(TEXT).is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! Token-level export for training NER models
+//!
+//! Converts the hits of a text to token tags, e.g., to use fenominal as a weak labeler for a neural
+//! named-entity recognizer. The text is split into sentences and tokens as by [`crate::Fenominal::process`]
+//! (tokens are words; digits and punctuation are not tokens), and each token is tagged with the
+//! [`TagScheme::Bio`] or [`TagScheme::Bioes`] scheme. The entity label is the ontology prefix (e.g., `HP`)
+//! or, optionally, the term ID (e.g., `HP:0001250`). Negated mentions are tagged like observed ones.
+//!
+//! Tagged sentences can be written in CoNLL-2003 style (one token per line, sentences separated
+//! by blank lines) or as spaCy-compatible JSONL (one sentence per line with character offsets).
+//! [`to_conll_with_provenance`] and [`to_spacy_jsonl_with_provenance`] also record the provenance of
+//! the hits, as `# key: value` comment lines before the CoNLL document and as the `meta` field of
+//! every spaCy example, respectively.
+//!
+//! Overlapping hits are resolved deterministically: hits are assigned in order of decreasing number
+//! of tokens, then by position and term ID, and a hit that shares a token with an assigned hit is dropped.
+//! Hits whose tokens are not adjacent (e.g., when stop words were skipped) are tagged as a single entity
+//! from their first to their last token.
+
+use std::ops::Range;
+
+use serde_json::json;
+
+use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::term::{MinimalTerm, Synonymous};
+
+use crate::export::char_offset;
+use crate::fenominal::Fenominal;
+use crate::models::fenominal_model::FenominalHit;
+use crate::models::provenance::Provenance;
+use crate::simple_sentence::SimpleSentence;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagScheme {
+    /// B(egin), I(nside), O(utside)
+    #[default]
+    Bio,
+    /// B(egin), I(nside), O(utside), E(nd) and S(ingle)
+    Bioes,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaggingOptions {
+    pub scheme: TagScheme,
+    /// Use the term ID (e.g., `B-HP:0001250`) instead of the ontology prefix (e.g., `B-HP`) as the label
+    pub term_id_labels: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedToken {
+    pub text: String,
+    /// Byte offsets in the document
+    pub span: Range<usize>,
+    /// e.g., `B-HP` or `O`
+    pub tag: String,
+}
+
+/// An entity after the resolution of overlaps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedEntity {
+    /// Byte offsets in the document, from the start of the first to the end of the last token
+    pub span: Range<usize>,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedSentence {
+    pub text: String,
+    /// Byte offset of the sentence in the document
+    pub start: usize,
+    pub tokens: Vec<TaggedToken>,
+    pub entities: Vec<TaggedEntity>,
+}
+
+impl TaggedSentence {
+    /// A spaCy training example, e.g., `{"text": "...", "entities": [[16, 24, "HP"]]}`, with character
+    /// offsets relative to the sentence
+    pub fn to_spacy_json(&self) -> String {
+        self.spacy_example(None).to_string()
+    }
+
+    fn spacy_example(&self, provenance: Option<&Provenance>) -> serde_json::Value {
+        let entities: Vec<_> = self
+            .entities
+            .iter()
+            .map(|e| {
+                let start = char_offset(&self.text, e.span.start - self.start);
+                let end = char_offset(&self.text, e.span.end - self.start);
+                json!([start, end, e.label])
+            })
+            .collect();
+        let mut example = json!({"text": self.text, "entities": entities});
+        if let Some(provenance) = provenance {
+            let meta: serde_json::Map<String, serde_json::Value> =
+                provenance.to_pairs().into_iter().map(|(key, value)| (key, value.into())).collect();
+            example["meta"] = meta.into();
+        }
+        example
+    }
+}
+
+/// Tag the tokens of every sentence of `text` with the hits that were mined from `text` by `fenominal`,
+/// which splits the sentences as in [`Fenominal::process`]
+pub fn tag_sentences<O, T>(fenominal: &Fenominal<O, T>, text: &str, hits: &[FenominalHit], options: TaggingOptions) -> Vec<TaggedSentence>
+where
+    O: OntologyTerms<T> + HierarchyWalks,
+    T: MinimalTerm + Synonymous,
+{
+    tag_simple_sentences(text, &fenominal.split_sentences(text), hits, options)
+}
+
+fn tag_simple_sentences(text: &str, sentences: &[SimpleSentence], hits: &[FenominalHit], options: TaggingOptions) -> Vec<TaggedSentence> {
+    let mut sentences: Vec<TaggedSentence> = sentences
+        .iter()
+        .map(|ss| TaggedSentence {
+            text: ss.get_sentence().to_string(),
+            start: ss.get_start_pos(),
+            tokens: ss
+                .get_tokens()
+                .iter()
+                .map(|t| {
+                    let span = ss.get_start_pos() + t.get_start_pos()..ss.get_start_pos() + t.get_end_pos();
+                    TaggedToken {
+                        text: text[span.clone()].to_string(),
+                        span,
+                        tag: "O".to_string(),
+                    }
+                })
+                .collect(),
+            entities: Vec::new(),
+        })
+        .collect();
+    for sentence in sentences.iter_mut() {
+        tag_sentence(sentence, hits, options);
+    }
+    sentences
+}
+
+fn tag_sentence(sentence: &mut TaggedSentence, hits: &[FenominalHit], options: TaggingOptions) {
+    // the token range of each hit of the sentence
+    let mut candidates: Vec<(Range<usize>, &FenominalHit)> = hits
+        .iter()
+        .filter_map(|hit| {
+            let first = sentence.tokens.iter().position(|t| hit.span.start <= t.span.start && t.span.end <= hit.span.end)?;
+            let last = sentence.tokens.iter().rposition(|t| hit.span.start <= t.span.start && t.span.end <= hit.span.end)?;
+            Some((first..last + 1, hit))
+        })
+        .collect();
+    candidates.sort_by(|(a, h), (b, k)| {
+        b.len()
+            .cmp(&a.len())
+            .then(a.start.cmp(&b.start))
+            .then_with(|| h.term_id.cmp(&k.term_id))
+    });
+    let mut assigned = vec![false; sentence.tokens.len()];
+    let mut entities: Vec<(Range<usize>, String)> = Vec::new();
+    for (range, hit) in candidates {
+        if assigned[range.clone()].iter().any(|&a| a) {
+            continue;
+        }
+        assigned[range.clone()].iter_mut().for_each(|a| *a = true);
+        let label = if options.term_id_labels {
+            hit.term_id.clone()
+        } else {
+            hit.ontology
+                .clone()
+                .unwrap_or_else(|| hit.term_id.split(':').next().unwrap_or_default().to_string())
+        };
+        entities.push((range, label));
+    }
+    entities.sort_by_key(|(range, _)| range.start);
+    for (range, label) in &entities {
+        for i in range.clone() {
+            let prefix = match options.scheme {
+                TagScheme::Bio if i == range.start => "B",
+                TagScheme::Bio => "I",
+                TagScheme::Bioes if range.len() == 1 => "S",
+                TagScheme::Bioes if i == range.start => "B",
+                TagScheme::Bioes if i == range.end - 1 => "E",
+                TagScheme::Bioes => "I",
+            };
+            sentence.tokens[i].tag = format!("{}-{}", prefix, label);
+        }
+    }
+    sentence.entities = entities
+        .into_iter()
+        .map(|(range, label)| TaggedEntity {
+            span: sentence.tokens[range.start].span.start..sentence.tokens[range.end - 1].span.end,
+            label,
+        })
+        .collect();
+}
+
+/// CoNLL-2003 style columns (token, part of speech, chunk, entity tag), separated by spaces.
+/// The part of speech and chunk columns are not known and written as `_`.
+pub fn to_conll(sentences: &[TaggedSentence]) -> String {
+    write_conll(String::new(), sentences)
+}
+
+/// CoNLL-2003 style columns as [`to_conll`], preceded by the provenance of the hits as comment lines,
+/// e.g., `# hp_version: 2025-03-03`
+pub fn to_conll_with_provenance(sentences: &[TaggedSentence], provenance: &Provenance) -> String {
+    let mut conll = String::new();
+    for (key, value) in provenance.to_pairs() {
+        conll.push_str(&format!("# {}: {}\n", key, value.replace(['\n', '\r'], " ")));
+    }
+    write_conll(conll, sentences)
+}
+
+fn write_conll(mut conll: String, sentences: &[TaggedSentence]) -> String {
+    conll.push_str("-DOCSTART- -X- -X- O\n\n");
+    for sentence in sentences.iter().filter(|s| !s.tokens.is_empty()) {
+        for token in &sentence.tokens {
+            conll.push_str(&format!("{} _ _ {}\n", token.text, token.tag));
+        }
+        conll.push('\n');
+    }
+    conll
+}
+
+/// One spaCy training example per sentence (see [`TaggedSentence::to_spacy_json`])
+pub fn to_spacy_jsonl(sentences: &[TaggedSentence]) -> String {
+    sentences.iter().map(|s| s.to_spacy_json() + "\n").collect()
+}
+
+/// One spaCy training example per sentence as [`to_spacy_jsonl`], with the provenance of the hits as
+/// the `meta` field of every example
+pub fn to_spacy_jsonl_with_provenance(sentences: &[TaggedSentence], provenance: &Provenance) -> String {
+    sentences.iter().map(|s| s.spacy_example(Some(provenance)).to_string() + "\n").collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::clinical_section::SectionLexicon;
+    use crate::core_document::CoreDocument;
+
+    const TEXT: &str = "Café owner with focal seizures. No scoliosis.";
+
+    fn tag_sentences(text: &str, hits: &[FenominalHit], options: TaggingOptions) -> Vec<TaggedSentence> {
+        let sentences = CoreDocument::new(text, &SectionLexicon::empty()).into_sentences();
+        tag_simple_sentences(text, &sentences, hits, options)
+    }
+
+    fn hit(term_id: &str, text: &str) -> FenominalHit {
+        let start = TEXT.find(text).unwrap();
+        FenominalHit::new(term_id.to_string(), "label", start..start + text.len(), true)
+    }
+
+    fn tags(sentences: &[TaggedSentence]) -> Vec<Vec<&str>> {
+        sentences.iter().map(|s| s.tokens.iter().map(|t| t.tag.as_str()).collect()).collect()
+    }
+
+    #[rstest]
+    #[case(TagScheme::Bio, vec![vec!["O", "O", "O", "B-HP", "I-HP"], vec!["O", "B-HP"]])]
+    #[case(TagScheme::Bioes, vec![vec!["O", "O", "O", "B-HP", "E-HP"], vec!["O", "S-HP"]])]
+    fn test_tag_schemes(#[case] scheme: TagScheme, #[case] expected: Vec<Vec<&str>>) {
+        let hits = vec![hit("HP:0007359", "focal seizures"), hit("HP:0002650", "scoliosis")];
+        let sentences = tag_sentences(TEXT, &hits, TaggingOptions { scheme, term_id_labels: false });
+        assert_eq!(expected, tags(&sentences));
+    }
+
+    #[rstest]
+    #[case(TagScheme::Bio, vec!["O", "B-HP", "I-HP", "I-HP", "I-HP", "I-HP"])]
+    #[case(TagScheme::Bioes, vec!["O", "B-HP", "I-HP", "I-HP", "I-HP", "E-HP"])]
+    fn test_hit_with_skipped_stop_words(#[case] scheme: TagScheme, #[case] expected: Vec<&str>) {
+        // "abnormality nervous system" is matched without the stop words "of" and "the"
+        let text = "No abnormality of the nervous system.";
+        let hits = vec![FenominalHit::new("HP:0000707".to_string(), "label", 3..36, false)];
+        let sentences = tag_sentences(text, &hits, TaggingOptions { scheme, term_id_labels: false });
+        assert_eq!(vec![expected], tags(&sentences));
+        assert_eq!(vec![3..36], sentences[0].entities.iter().map(|e| e.span.clone()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_overlapping_hits() {
+        // the longer hit wins, regardless of the order of the hits
+        let hits = vec![hit("HP:0001250", "seizures"), hit("HP:0007359", "focal seizures")];
+        let options = TaggingOptions { scheme: TagScheme::Bio, term_id_labels: true };
+        let sentences = tag_sentences(TEXT, &hits, options);
+        assert_eq!(vec!["O", "O", "O", "B-HP:0007359", "I-HP:0007359"], tags(&sentences)[0]);
+        let reversed: Vec<FenominalHit> = hits.into_iter().rev().collect();
+        assert_eq!(sentences, tag_sentences(TEXT, &reversed, options));
+    }
+
+    #[test]
+    fn test_conll() {
+        let sentences = tag_sentences(TEXT, &[hit("HP:0002650", "scoliosis")], TaggingOptions::default());
+        let conll = to_conll(&sentences);
+        assert!(conll.starts_with("-DOCSTART- -X- -X- O\n\nCafé _ _ O\nowner _ _ O\n"));
+        assert!(conll.ends_with("\n\nNo _ _ O\nscoliosis _ _ B-HP\n\n"));
+    }
+
+    #[test]
+    fn test_spacy_jsonl() {
+        let hits = vec![hit("HP:0007359", "focal seizures"), hit("HP:0002650", "scoliosis")];
+        let jsonl = to_spacy_jsonl(&tag_sentences(TEXT, &hits, TaggingOptions::default()));
+        let lines: Vec<serde_json::Value> = jsonl.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(json!({"text": "Café owner with focal seizures.", "entities": [[16, 30, "HP"]]}), lines[0]);
+        assert_eq!(json!({"text": "No scoliosis.", "entities": [[3, 12, "HP"]]}), lines[1]);
+    }
+}
@@ -0,0 +1,444 @@
+//! FHIR R4 export
+//!
+//! Converts the hits of a clinical note to a [FHIR R4](https://hl7.org/fhir/R4/) `Bundle` of type
+//! `collection`. The note itself is a `DocumentReference` (with the text as a base64 attachment),
+//! and each finding of the proband is an `Observation` that is `derivedFrom` the note:
+//!
+//! * `code` is the term (HPO terms use the code system `http://human-phenotype-ontology.org`), with
+//!   the matched text as `code.text`
+//! * `valueBoolean` is `true` for observed and `false` for excluded findings, and `interpretation` is
+//!   `POS` or `NEG` of the HL7 v3 ObservationInterpretation code system
+//! * the extension [`SPAN_EXTENSION_URL`] has the `start` and `end` of the mention (character offsets
+//!   into the note, the end is exclusive)
+//!
+//! Findings of family members are not exported, because they are not observations of the patient.
+//!
+//! [`Bundle::with_provenance`] adds a `Provenance` resource that targets the observations, with fenominal
+//! as the agent, the note as the source entity, and the versions and configuration as the string-valued
+//! sub-extensions of [`PROVENANCE_EXTENSION_URL`] (e.g., `hp_version`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::{base64, char_offset, timestamp};
+use crate::models::fenominal_model::FenominalHit;
+use crate::models::provenance::Provenance;
+
+/// The URL of the extension with the coordinates of a mention in the source note
+pub const SPAN_EXTENSION_URL: &str = "https://github.com/P2GX/fenominal/fhir/StructureDefinition/text-span";
+/// The URL of the extension with the versions and the configuration that produced the observations
+pub const PROVENANCE_EXTENSION_URL: &str = "https://github.com/P2GX/fenominal/fhir/StructureDefinition/mining-configuration";
+/// The FHIR code system of the HPO
+pub const HPO_SYSTEM: &str = "http://human-phenotype-ontology.org";
+const INTERPRETATION_SYSTEM: &str = "http://terminology.hl7.org/CodeSystem/v3-ObservationInterpretation";
+
+/// A `Bundle` of type `collection`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "resourceType", rename = "Bundle")]
+pub struct Bundle {
+    #[serde(rename = "type")]
+    pub bundle_type: String,
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+    pub entry: Vec<BundleEntry>,
+}
+
+impl Bundle {
+    /// The note with the ID `document_id` and the observations of its hits
+    pub fn from_hits(document_id: &str, text: &str, hits: &[FenominalHit]) -> Self {
+        let document_id = resource_id(document_id);
+        let mut entry = vec![BundleEntry {
+            resource: FhirResource::DocumentReference(DocumentReference::new(&document_id, text)),
+        }];
+        entry.extend(
+            hits.iter()
+                .filter(|hit| hit.is_proband())
+                .enumerate()
+                .map(|(i, hit)| BundleEntry {
+                    resource: FhirResource::Observation(Observation::new(&format!("{}-{}", document_id, i + 1), &document_id, text, hit)),
+                }),
+        );
+        Self {
+            bundle_type: "collection".to_string(),
+            timestamp: timestamp(),
+            entry,
+        }
+    }
+
+    /// Add a `Provenance` resource for the observations of the bundle
+    pub fn with_provenance(mut self, provenance: &Provenance) -> Self {
+        let document_id = self
+            .entry
+            .iter()
+            .find_map(|e| match &e.resource {
+                FhirResource::DocumentReference(d) => Some(d.id.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| resource_id(&provenance.document_id));
+        let document = Reference::to(format!("DocumentReference/{}", document_id));
+        let mut target: Vec<Reference> = self.observations().map(|o| Reference::to(format!("Observation/{}", o.id))).collect();
+        if target.is_empty() {
+            // the target is required
+            target.push(document.clone());
+        }
+        let resource = FhirProvenance {
+            id: format!("{}-provenance", document_id),
+            extension: vec![Extension::provenance(provenance)],
+            target,
+            recorded: self.timestamp.clone(),
+            agent: vec![Agent {
+                who: DisplayReference {
+                    display: format!("fenominal {}", provenance.fenominal_version),
+                },
+            }],
+            entity: vec![Entity {
+                role: "source".to_string(),
+                what: document,
+            }],
+        };
+        self.entry.push(BundleEntry {
+            resource: FhirResource::Provenance(resource),
+        });
+        self
+    }
+
+    /// The `Provenance` resource of the bundle, if any
+    pub fn provenance(&self) -> Option<&FhirProvenance> {
+        self.entry.iter().find_map(|e| match &e.resource {
+            FhirResource::Provenance(p) => Some(p),
+            _ => None,
+        })
+    }
+
+    pub fn observations(&self) -> impl Iterator<Item = &Observation> {
+        self.entry.iter().filter_map(|e| match &e.resource {
+            FhirResource::Observation(o) => Some(o),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleEntry {
+    pub resource: FhirResource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "resourceType")]
+pub enum FhirResource {
+    DocumentReference(DocumentReference),
+    Observation(Observation),
+    Provenance(FhirProvenance),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentReference {
+    pub id: String,
+    pub status: String,
+    pub content: Vec<Content>,
+}
+
+impl DocumentReference {
+    fn new(id: &str, text: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            status: "current".to_string(),
+            content: vec![Content {
+                attachment: Attachment {
+                    content_type: "text/plain; charset=utf-8".to_string(),
+                    data: base64(text.as_bytes()),
+                },
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Content {
+    pub attachment: Attachment,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub content_type: String,
+    /// The base64-encoded text
+    pub data: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Observation {
+    pub id: String,
+    pub extension: Vec<Extension>,
+    pub status: String,
+    pub code: CodeableConcept,
+    pub value_boolean: bool,
+    pub interpretation: Vec<CodeableConcept>,
+    pub derived_from: Vec<Reference>,
+}
+
+impl Observation {
+    fn new(id: &str, document_id: &str, text: &str, hit: &FenominalHit) -> Self {
+        let (code, display) = if hit.is_observed { ("POS", "Positive") } else { ("NEG", "Negative") };
+        Self {
+            id: id.to_string(),
+            extension: vec![Extension::span(char_offset(text, hit.span.start), char_offset(text, hit.span.end))],
+            status: "final".to_string(),
+            code: CodeableConcept {
+                coding: vec![Coding::term(&hit.term_id, &hit.label)],
+                text: Some(text[hit.span.clone()].to_string()),
+            },
+            value_boolean: hit.is_observed,
+            interpretation: vec![CodeableConcept {
+                coding: vec![Coding {
+                    system: INTERPRETATION_SYSTEM.to_string(),
+                    code: code.to_string(),
+                    display: display.to_string(),
+                }],
+                text: None,
+            }],
+            derived_from: vec![Reference::to(format!("DocumentReference/{}", document_id))],
+        }
+    }
+
+    /// The character offsets of the mention (see [`SPAN_EXTENSION_URL`])
+    pub fn span(&self) -> Option<std::ops::Range<usize>> {
+        let extension = self.extension.iter().find(|e| e.url == SPAN_EXTENSION_URL)?;
+        let value = |url: &str| extension.extension.iter().find(|e| e.url == url).and_then(|e| e.value_integer);
+        Some(value("start")?..value("end")?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extension {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extension: Vec<Extension>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_integer: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_string: Option<String>,
+}
+
+impl Extension {
+    fn span(start: usize, end: usize) -> Self {
+        let integer = |url: &str, value: usize| Extension {
+            url: url.to_string(),
+            extension: Vec::new(),
+            value_integer: Some(value),
+            value_string: None,
+        };
+        Self {
+            url: SPAN_EXTENSION_URL.to_string(),
+            extension: vec![integer("start", start), integer("end", end)],
+            value_integer: None,
+            value_string: None,
+        }
+    }
+
+    fn provenance(provenance: &Provenance) -> Self {
+        let extension = provenance
+            .to_pairs()
+            .into_iter()
+            .map(|(key, value)| Extension {
+                url: key,
+                extension: Vec::new(),
+                value_integer: None,
+                value_string: Some(value),
+            })
+            .collect();
+        Self {
+            url: PROVENANCE_EXTENSION_URL.to_string(),
+            extension,
+            value_integer: None,
+            value_string: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeableConcept {
+    pub coding: Vec<Coding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coding {
+    pub system: String,
+    pub code: String,
+    pub display: String,
+}
+
+impl Coding {
+    /// The coding of an ontology term, e.g., `HP:0001250`. Terms of ontologies other than the HPO use
+    /// the OBO PURL of the ontology as the code system.
+    fn term(term_id: &str, label: &str) -> Self {
+        let prefix = term_id.split(':').next().unwrap_or_default();
+        let system = if prefix == "HP" {
+            HPO_SYSTEM.to_string()
+        } else {
+            format!("http://purl.obolibrary.org/obo/{}.owl", prefix.to_lowercase())
+        };
+        Self {
+            system,
+            code: term_id.to_string(),
+            display: label.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reference {
+    pub reference: String,
+}
+
+impl Reference {
+    fn to(reference: String) -> Self {
+        Self { reference }
+    }
+}
+
+/// A reference to something that is not a resource of the bundle, by its name only
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayReference {
+    pub display: String,
+}
+
+/// A `Provenance` resource
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FhirProvenance {
+    pub id: String,
+    pub extension: Vec<Extension>,
+    pub target: Vec<Reference>,
+    /// RFC 3339 timestamp
+    pub recorded: String,
+    pub agent: Vec<Agent>,
+    pub entity: Vec<Entity>,
+}
+
+impl FhirProvenance {
+    /// The value of a sub-extension of [`PROVENANCE_EXTENSION_URL`], e.g., `hp_version`
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.extension
+            .iter()
+            .filter(|e| e.url == PROVENANCE_EXTENSION_URL)
+            .flat_map(|e| e.extension.iter())
+            .find(|e| e.url == key)
+            .and_then(|e| e.value_string.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Agent {
+    pub who: DisplayReference,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entity {
+    pub role: String,
+    pub what: Reference,
+}
+
+/// FHIR IDs consist of up to 64 letters, digits, `-` and `.`; other characters are replaced by `-`.
+fn resource_id(id: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '-' })
+        .take(64)
+        .collect();
+    if id.is_empty() { "document".to_string() } else { id }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::models::fenominal_model::Experiencer;
+
+    const TEXT: &str = "Zoë has ptosis. Her mother has seizures. No scoliosis.";
+
+    fn find(s: &str) -> std::ops::Range<usize> {
+        TEXT.find(s).unwrap()..TEXT.find(s).unwrap() + s.len()
+    }
+
+    fn hits() -> Vec<FenominalHit> {
+        let mut seizures = FenominalHit::new("HP:0001250".to_string(), "Seizure", find("seizures"), true);
+        seizures.experiencer = Experiencer::FamilyMember { relation: Some("mother".to_string()) };
+        vec![
+            FenominalHit::new("HP:0000508".to_string(), "Ptosis", find("ptosis"), true),
+            seizures,
+            FenominalHit::new("HP:0002650".to_string(), "Scoliosis", find("scoliosis"), false),
+        ]
+    }
+
+    fn codes(bundle: &Bundle) -> Vec<&str> {
+        bundle.observations().map(|o| o.code.coding[0].code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_bundle_json() {
+        let bundle = Bundle::from_hits("note 1", TEXT, &hits());
+        let value: Value = serde_json::to_value(&bundle).unwrap();
+        assert_eq!("Bundle", value["resourceType"]);
+        assert_eq!("collection", value["type"]);
+        let entries = value["entry"].as_array().unwrap();
+        assert_eq!(3, entries.len());
+        assert_eq!("DocumentReference", entries[0]["resource"]["resourceType"]);
+        assert_eq!("note-1", entries[0]["resource"]["id"]);
+        assert_eq!(
+            json!({
+                "resourceType": "Observation",
+                "id": "note-1-2",
+                "extension": [{
+                    "url": SPAN_EXTENSION_URL,
+                    "extension": [{"url": "start", "valueInteger": 44}, {"url": "end", "valueInteger": 53}]
+                }],
+                "status": "final",
+                "code": {
+                    "coding": [{"system": HPO_SYSTEM, "code": "HP:0002650", "display": "Scoliosis"}],
+                    "text": "scoliosis"
+                },
+                "valueBoolean": false,
+                "interpretation": [{
+                    "coding": [{"system": INTERPRETATION_SYSTEM, "code": "NEG", "display": "Negative"}]
+                }],
+                "derivedFrom": [{"reference": "DocumentReference/note-1"}]
+            }),
+            entries[2]["resource"]
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let bundle = Bundle::from_hits("note-1", TEXT, &hits());
+        let json = serde_json::to_string(&bundle).unwrap();
+        let parsed: Bundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(bundle, parsed);
+        let spans: Vec<_> = parsed.observations().filter_map(Observation::span).collect();
+        assert_eq!(vec![8..14, 44..53], spans);
+    }
+
+    #[test]
+    fn test_family_member_findings() {
+        assert_eq!(vec!["HP:0000508", "HP:0002650"], codes(&Bundle::from_hits("note-1", TEXT, &hits())));
+        // findings of relatives whose relation is unknown (e.g., in the family history) and of other persons
+        let mut hits = hits();
+        hits[0].experiencer = Experiencer::FamilyMember { relation: None };
+        hits[2].experiencer = Experiencer::Other;
+        let bundle = Bundle::from_hits("note-1", TEXT, &hits);
+        assert_eq!(1, bundle.entry.len());
+        assert!(codes(&bundle).is_empty());
+    }
+
+    #[rstest]
+    #[case("note-1", "note-1")]
+    #[case("Smith, J. (2025)", "Smith--J.--2025-")]
+    #[case("", "document")]
+    fn test_resource_id(#[case] id: &str, #[case] expected: &str) {
+        assert_eq!(expected, resource_id(id));
+    }
+}
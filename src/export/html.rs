@@ -0,0 +1,200 @@
+//! HTML report
+//!
+//! Renders mined sentences (see [`crate::Fenominal::mine_sentences`]) as a standalone HTML page that
+//! can be opened offline: the styles are inline and there are no scripts or external assets.
+//! Hits are highlighted in the text, colored by their status (observed, excluded, or a finding of
+//! another person), and show the term ID, label and matched text in a tooltip. A sidebar lists the
+//! unique terms with the number of mentions. The [`Provenance`] of the hits can be shown in a footer.
+
+use std::fmt::Write;
+
+use crate::models::fenominal_model::{FenominalHit, FenominalSegment, FenominalSentence, OntologyClass};
+use crate::models::provenance::Provenance;
+
+const STYLE: &str = "\
+body { margin: 0; font-family: sans-serif; color: #222; display: flex; }
+main { flex: 3; padding: 1em 2em; line-height: 1.7; }
+aside { flex: 1; padding: 1em; background: #f6f6f6; border-left: 1px solid #ddd; min-height: 100vh; }
+aside ul { list-style: none; padding: 0; }
+aside li { margin: 0.3em 0; padding-left: 0.4em; border-left: 4px solid; }
+mark { padding: 0 0.15em; border-radius: 3px; }
+.observed { background: #c8f0c8; border-color: #2e8b2e; }
+.excluded { background: #f8c8c8; border-color: #c03030; text-decoration: line-through; }
+.other { background: #d0dcf8; border-color: #3050c0; }
+aside li.observed, aside li.excluded, aside li.other { background: none; text-decoration: none; }
+.id { color: #666; font-size: 0.85em; }
+.legend mark { margin-right: 0.5em; }
+footer { margin-top: 2em; color: #666; font-size: 0.85em; }
+footer dt { float: left; clear: left; width: 12em; }
+";
+
+/// A standalone HTML page with the given title (escaped) and the sentences in order
+pub fn render_html(title: &str, sentences: &[FenominalSentence]) -> String {
+    render(title, sentences, None)
+}
+
+/// A standalone HTML page as [`render_html`], with a footer that lists the provenance of the hits
+pub fn render_html_with_provenance(title: &str, sentences: &[FenominalSentence], provenance: &Provenance) -> String {
+    render(title, sentences, Some(provenance))
+}
+
+fn render(title: &str, sentences: &[FenominalSentence], provenance: Option<&Provenance>) -> String {
+    let mut html = String::new();
+    let title = escape_html(title);
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<main>\n<h1>{}</h1>\n",
+        title, STYLE, title
+    );
+    html.push_str("<p class=\"legend\"><mark class=\"observed\">observed</mark><mark class=\"excluded\">excluded</mark><mark class=\"other\">family member or other person</mark></p>\n<p>");
+    let mut section = sentences.first().and_then(|s| s.section.as_ref());
+    for (i, sentence) in sentences.iter().enumerate() {
+        if sentence.section.as_ref() != section {
+            section = sentence.section.as_ref();
+            html.push_str("</p>\n<p>");
+        } else if i > 0 {
+            html.push(' ');
+        }
+        render_sentence(&mut html, sentence);
+    }
+    html.push_str("</p>\n");
+    if let Some(provenance) = provenance {
+        render_provenance(&mut html, provenance);
+    }
+    html.push_str("</main>\n");
+    render_sidebar(&mut html, sentences);
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_sentence(html: &mut String, sentence: &FenominalSentence) {
+    for segment in sentence.segments() {
+        match segment {
+            FenominalSegment::Text(text) => html.push_str(&escape_html(&text.text)),
+            FenominalSegment::Hit(segment) => {
+                let hit = &segment.hit;
+                let _ = write!(
+                    html,
+                    "<mark class=\"{}\" title=\"{}\">{}</mark>",
+                    status(hit),
+                    escape_html(&format!("{} {}\nmatched: {}", hit.term_id, hit.label, segment.text)),
+                    escape_html(&segment.text)
+                );
+            }
+        }
+    }
+}
+
+fn render_provenance(html: &mut String, provenance: &Provenance) {
+    html.push_str("<footer>\n<h2>Provenance</h2>\n<dl>\n");
+    for (key, value) in provenance.to_pairs() {
+        let _ = writeln!(html, "<dt>{}</dt><dd>{}</dd>", escape_html(&key), escape_html(&value));
+    }
+    html.push_str("</dl>\n</footer>\n");
+}
+
+/// The unique terms, in order of first mention
+fn render_sidebar(html: &mut String, sentences: &[FenominalSentence]) {
+    let mut terms: Vec<(OntologyClass, &'static str, usize)> = Vec::new();
+    for hit in sentences.iter().flat_map(FenominalSentence::hit_iter) {
+        match terms.iter_mut().find(|(term, _, _)| term.id == hit.term_id) {
+            Some((_, term_status, count)) => {
+                *count += 1;
+                // a term is shown as observed if any mention of the proband is observed
+                if status(hit) == "observed" {
+                    *term_status = "observed";
+                }
+            }
+            None => terms.push((OntologyClass::new(hit.term_id.clone(), hit.label.clone()), status(hit), 1)),
+        }
+    }
+    let _ = writeln!(html, "<aside>\n<h2>Terms ({})</h2>\n<ul>", terms.len());
+    for (term, term_status, count) in &terms {
+        let _ = writeln!(
+            html,
+            "<li class=\"{}\">{} <span class=\"id\">{}</span> ({})</li>",
+            term_status,
+            escape_html(&term.label),
+            escape_html(&term.id),
+            count
+        );
+    }
+    html.push_str("</ul>\n</aside>\n");
+}
+
+fn status(hit: &FenominalHit) -> &'static str {
+    if !hit.is_proband() {
+        "other"
+    } else if hit.is_observed {
+        "observed"
+    } else {
+        "excluded"
+    }
+}
+
+/// Escape text for use in HTML elements and quoted attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::hpo::text_to_annotation::fenominal_hits_to_sentence;
+    use crate::models::fenominal_model::Experiencer;
+
+    #[rstest]
+    #[case("a < b & c > d", "a &lt; b &amp; c &gt; d")]
+    #[case("\"quoted\" 'single'", "&quot;quoted&quot; &#39;single&#39;")]
+    #[case("<script>alert(1)</script>", "&lt;script&gt;alert(1)&lt;/script&gt;")]
+    #[case("Café", "Café")]
+    fn test_escape_html(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(expected, escape_html(text));
+    }
+
+    fn sentences() -> Vec<FenominalSentence> {
+        let first = "Seizures <since> age 3 & no scoliosis.";
+        let seizure = FenominalHit::new("HP:0001250".to_string(), "Seizure", 0..8, true);
+        let scoliosis = FenominalHit::new("HP:0002650".to_string(), "Scoliosis", 28..37, false);
+        let second = "His mother had seizures.";
+        let mut relative = FenominalHit::new("HP:0001250".to_string(), "Seizure", 54..62, true);
+        relative.experiencer = Experiencer::FamilyMember { relation: Some("mother".to_string()) };
+        vec![
+            fenominal_hits_to_sentence(first, 0, &[seizure, scoliosis]).unwrap(),
+            fenominal_hits_to_sentence(second, 39, &[relative]).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_render_hits() {
+        let html = render_html("Note <1>", &sentences());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Note &lt;1&gt;</title>"));
+        assert!(html.contains("<mark class=\"observed\" title=\"HP:0001250 Seizure&#10;matched: Seizures\">Seizures</mark> &lt;since&gt; age 3 &amp; no "));
+        assert!(html.contains("<mark class=\"excluded\" title=\"HP:0002650 Scoliosis&#10;matched: scoliosis\">scoliosis</mark>."));
+        assert!(html.contains("His mother had <mark class=\"other\""));
+        assert!(!html.contains("<script") && !html.contains("href=") && !html.contains("src="));
+    }
+
+    #[test]
+    fn test_render_sidebar() {
+        let html = render_html("Note", &sentences());
+        assert!(html.contains("<h2>Terms (2)</h2>"));
+        assert!(html.contains("<li class=\"observed\">Seizure <span class=\"id\">HP:0001250</span> (2)</li>"));
+        assert!(html.contains("<li class=\"excluded\">Scoliosis <span class=\"id\">HP:0002650</span> (1)</li>"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Serializers of mining results to the formats of other tools.
+
+#[cfg(feature = "bioc")]
+pub mod bioc;
+pub mod brat;
+pub mod conll;
+pub mod fhir;
+pub mod html;
+pub mod phenopacket;
+pub mod pubannotation;
+pub mod table;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The current time as an RFC 3339 timestamp in UTC, e.g., `2025-03-03T10:15:30Z`
+pub(crate) fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format_timestamp(seconds)
+}
+
+/// The number of characters before the byte offset `byte` of `text`
+pub(crate) fn char_offset(text: &str, byte: usize) -> usize {
+    text[..byte].chars().count()
+}
+
+/// The byte offset of the character offset `char` of `text` (`None` if `text` is shorter)
+pub(crate) fn byte_offset(text: &str, char: usize) -> Option<usize> {
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .nth(char)
+}
+
+/// Standard base64 encoding (RFC 4648) with padding
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Format seconds since the Unix epoch (the civil calendar conversion of H. Hinnant's `civil_from_days`)
+fn format_timestamp(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let time = seconds % 86_400;
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, "1970-01-01T00:00:00Z")]
+    #[case(951_782_400, "2000-02-29T00:00:00Z")]
+    #[case(1_740_996_930, "2025-03-03T10:15:30Z")]
+    fn test_format_timestamp(#[case] seconds: u64, #[case] expected: &str) {
+        assert_eq!(expected, format_timestamp(seconds));
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(3, 3)]
+    #[case(5, 4)]
+    #[case(9, 8)]
+    fn test_offsets(#[case] byte: usize, #[case] char: usize) {
+        let text = "Café owner";
+        assert_eq!(char, char_offset(text, byte));
+        assert_eq!(Some(byte), byte_offset(text, char));
+    }
+
+    #[rstest]
+    #[case("", "")]
+    #[case("f", "Zg==")]
+    #[case("fo", "Zm8=")]
+    #[case("foo", "Zm9v")]
+    #[case("foobar", "Zm9vYmFy")]
+    #[case("Café", "Q2Fmw6k=")]
+    fn test_base64(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, base64(input.as_bytes()));
+    }
+
+    #[test]
+    fn test_byte_offset_out_of_range() {
+        assert_eq!(Some(5), byte_offset("Café", 4));
+        assert_eq!(None, byte_offset("Café", 5));
+    }
+}
@@ -0,0 +1,248 @@
+//! Phenopacket export
+//!
+//! Converts mining results to the `phenotypicFeatures` of a
+//! [GA4GH Phenopacket v2](https://phenopacket-schema.readthedocs.io/en/latest/phenopacket.html).
+//! Mentions are aggregated by term first (see [`PhenotypeSummary`]), so that each term is reported once
+//! with its final observation status. The `metaData` lists the HPO release that was used for mining,
+//! and, with [`Phenopacket::with_provenance`], an external reference to the mined document whose
+//! description records the version and configuration of fenominal.
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::timestamp;
+use crate::models::fenominal_model::{FenominalHit, OnsetValue, OntologyClass};
+use crate::models::phenotype_summary::PhenotypeSummary;
+use crate::models::provenance::Provenance;
+
+/// The version of the Phenopacket schema
+pub const PHENOPACKET_SCHEMA_VERSION: &str = "2.0";
+
+/// A phenopacket with the phenotypic features of a single document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Phenopacket {
+    pub id: String,
+    pub phenotypic_features: Vec<PhenotypicFeature>,
+    pub meta_data: MetaData,
+}
+
+impl Phenopacket {
+    /// Create a phenopacket from the mentions of a document; the HPO version is, e.g., `2025-03-03`.
+    pub fn from_hits(id: impl Into<String>, hits: &[FenominalHit], hpo_version: &str) -> Self {
+        Self::from_summary(id, &PhenotypeSummary::from_hits(hits), hpo_version)
+    }
+
+    /// Create a phenopacket from a document summary (one feature per term).
+    pub fn from_summary(id: impl Into<String>, summary: &PhenotypeSummary, hpo_version: &str) -> Self {
+        Self {
+            id: id.into(),
+            phenotypic_features: phenotypic_features(summary),
+            meta_data: MetaData::new(hpo_version),
+        }
+    }
+
+    /// Record the provenance of the features: the HPO release of the provenance replaces the one
+    /// of the resources, and the document is added as an external reference.
+    pub fn with_provenance(mut self, provenance: &Provenance) -> Self {
+        if let Some(version) = provenance.ontology_version("HP") {
+            for resource in self.meta_data.resources.iter_mut().filter(|r| r.namespace_prefix == "HP") {
+                resource.version = version.to_string();
+            }
+        }
+        let description: Vec<String> = provenance.to_pairs().iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        self.meta_data.external_references.push(ExternalReference {
+            id: provenance.document_id.clone(),
+            reference: None,
+            description: description.join("; "),
+        });
+        self
+    }
+}
+
+/// A `PhenotypicFeature` element
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhenotypicFeature {
+    #[serde(rename = "type")]
+    pub feature_type: OntologyClass,
+    /// Only serialized for excluded features, as in the phenopacket JSON produced by the reference implementation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub excluded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onset: Option<TimeElement>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<OntologyClass>,
+}
+
+/// A `TimeElement`; only the forms that are found by fenominal are supported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeElement {
+    Age(Age),
+    OntologyClass(OntologyClass),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Age {
+    pub iso8601duration: String,
+}
+
+impl From<&OnsetValue> for TimeElement {
+    fn from(onset: &OnsetValue) -> Self {
+        match onset {
+            OnsetValue::Age { iso8601duration } => TimeElement::Age(Age {
+                iso8601duration: iso8601duration.clone(),
+            }),
+            OnsetValue::OntologyClass(term) => TimeElement::OntologyClass(term.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaData {
+    /// RFC 3339 timestamp
+    pub created: String,
+    pub created_by: String,
+    pub resources: Vec<Resource>,
+    pub phenopacket_schema_version: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_references: Vec<ExternalReference>,
+}
+
+impl MetaData {
+    pub fn new(hpo_version: &str) -> Self {
+        Self {
+            created: timestamp(),
+            created_by: format!("fenominal {}", env!("CARGO_PKG_VERSION")),
+            resources: vec![Resource::hpo(hpo_version)],
+            phenopacket_schema_version: PHENOPACKET_SCHEMA_VERSION.to_string(),
+            external_references: Vec::new(),
+        }
+    }
+}
+
+/// An ontology that is used in a phenopacket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub version: String,
+    pub namespace_prefix: String,
+    pub iri_prefix: String,
+}
+
+impl Resource {
+    /// The Human Phenotype Ontology resource of the given release, e.g., `2025-03-03`
+    pub fn hpo(version: &str) -> Self {
+        Self {
+            id: "hp".to_string(),
+            name: "human phenotype ontology".to_string(),
+            url: "http://purl.obolibrary.org/obo/hp.owl".to_string(),
+            version: version.to_string(),
+            namespace_prefix: "HP".to_string(),
+            iri_prefix: "http://purl.obolibrary.org/obo/HP_".to_string(),
+        }
+    }
+}
+
+/// A reference to a document that the phenopacket is based on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalReference {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    pub description: String,
+}
+
+/// One feature per HPO term of the summary, in order of first mention. Terms of other ontologies are skipped.
+pub fn phenotypic_features(summary: &PhenotypeSummary) -> Vec<PhenotypicFeature> {
+    summary
+        .iter()
+        .filter(|entry| entry.term.id.starts_with("HP:"))
+        .map(|entry| PhenotypicFeature {
+            feature_type: entry.term.clone(),
+            excluded: !entry.is_observed,
+            onset: entry.onset.as_ref().map(|o| TimeElement::from(&o.value)),
+            modifiers: entry.modifiers.iter().map(|m| m.term.clone()).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::models::fenominal_model::{Modifier, Onset};
+
+    fn hits() -> Vec<FenominalHit> {
+        let mut seizure = FenominalHit::new("HP:0001250".to_string(), "Seizure", 10..18, true);
+        seizure.onset = Some(Onset {
+            value: OnsetValue::Age { iso8601duration: "P3Y".to_string() },
+            span: 19..36,
+        });
+        let mut ptosis = FenominalHit::new("HP:0000508".to_string(), "Ptosis", 40..46, true);
+        ptosis.modifiers.push(Modifier {
+            term: OntologyClass::new("HP:0012834", "Right"),
+            span: 50..55,
+        });
+        let mut hypotonia = FenominalHit::new("HP:0001252".to_string(), "Hypotonia", 60..69, false);
+        hypotonia.onset = Some(Onset {
+            value: OnsetValue::OntologyClass(OntologyClass::new("HP:0003623", "Neonatal onset")),
+            span: 56..59,
+        });
+        let disease = FenominalHit::new("MONDO:0007947".to_string(), "Marfan syndrome", 70..85, true);
+        vec![seizure, ptosis, hypotonia, disease]
+    }
+
+    #[test]
+    fn test_phenotypic_features_json() {
+        let phenopacket = Phenopacket::from_hits("doc-1", &hits(), "2025-03-03");
+        let value: Value = serde_json::to_value(&phenopacket).unwrap();
+        assert_eq!(
+            json!([
+                {
+                    "type": {"id": "HP:0001250", "label": "Seizure"},
+                    "onset": {"age": {"iso8601duration": "P3Y"}}
+                },
+                {
+                    "type": {"id": "HP:0000508", "label": "Ptosis"},
+                    "modifiers": [{"id": "HP:0012834", "label": "Right"}]
+                },
+                {
+                    "type": {"id": "HP:0001252", "label": "Hypotonia"},
+                    "excluded": true,
+                    "onset": {"ontologyClass": {"id": "HP:0003623", "label": "Neonatal onset"}}
+                }
+            ]),
+            value["phenotypicFeatures"]
+        );
+        assert_eq!("doc-1", value["id"]);
+    }
+
+    #[test]
+    fn test_meta_data_json() {
+        let phenopacket = Phenopacket::from_hits("doc-1", &hits(), "2025-03-03");
+        let value: Value = serde_json::to_value(&phenopacket).unwrap();
+        let meta_data = &value["metaData"];
+        assert_eq!("2.0", meta_data["phenopacketSchemaVersion"]);
+        assert!(meta_data["created"].as_str().unwrap().ends_with('Z'));
+        assert!(meta_data["createdBy"].as_str().unwrap().starts_with("fenominal"));
+        assert_eq!(
+            json!([{
+                "id": "hp",
+                "name": "human phenotype ontology",
+                "url": "http://purl.obolibrary.org/obo/hp.owl",
+                "version": "2025-03-03",
+                "namespacePrefix": "HP",
+                "iriPrefix": "http://purl.obolibrary.org/obo/HP_"
+            }]),
+            meta_data["resources"]
+        );
+        let roundtrip: Phenopacket = serde_json::from_value(value).unwrap();
+        assert_eq!(phenopacket, roundtrip);
+    }
+}
@@ -0,0 +1,211 @@
+//! PubAnnotation JSON
+//!
+//! [PubAnnotation](https://www.pubannotation.org/docs/annotation-format/) stores the annotations of a
+//! text as `denotations` (spans with an object) and `attributes` of denotations. Each hit is a denotation
+//! whose `obj` is the IRI of the term (e.g., `http://purl.obolibrary.org/obo/HP_0001250`), and negated
+//! hits have the attribute `{"pred": "negation", "obj": true}`. Spans are character offsets.
+//!
+//! Documents can be created from the hits of a text (see [`crate::Fenominal::process`]) or from the
+//! sentences of [`crate::Fenominal::mine_sentences`], either as one document or as one document per sentence.
+//! The [`Provenance`] of the hits is an additional top-level `provenance` object, which is ignored by
+//! PubAnnotation itself.
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::char_offset;
+use crate::models::fenominal_model::{FenominalHit, FenominalSentence};
+use crate::models::provenance::Provenance;
+
+const OBO_PURL: &str = "http://purl.obolibrary.org/obo/";
+/// The predicate of the attribute of negated denotations
+pub const NEGATION: &str = "negation";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PubAnnotation {
+    pub text: String,
+    /// e.g., `PubMed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sourcedb: Option<String>,
+    /// The ID of the document in the source database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sourceid: Option<String>,
+    /// The ID of the division (e.g., the sentence) of the document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub divid: Option<usize>,
+    #[serde(default)]
+    pub denotations: Vec<Denotation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Attribute>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Denotation {
+    pub id: String,
+    pub span: Span,
+    pub obj: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub begin: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribute {
+    pub id: String,
+    pub subj: String,
+    pub pred: String,
+    pub obj: serde_json::Value,
+}
+
+impl PubAnnotation {
+    /// The annotations of `text` with the hits that were mined from it
+    pub fn from_hits(text: &str, hits: &[FenominalHit]) -> Self {
+        let mut annotation = Self {
+            text: text.to_string(),
+            ..Default::default()
+        };
+        annotation.add_hits(text, 0, hits);
+        annotation
+    }
+
+    /// One document with the text and the hits of all sentences
+    pub fn from_sentences(sentences: &[FenominalSentence]) -> Self {
+        let mut text = String::new();
+        for sentence in sentences {
+            // the sentences of mine_sentences are separated by a single space
+            while text.len() < sentence.start {
+                text.push(' ');
+            }
+            text.push_str(&sentence.original_text);
+        }
+        let hits: Vec<FenominalHit> = sentences.iter().flat_map(FenominalSentence::hit_iter).cloned().collect();
+        Self::from_hits(&text, &hits)
+    }
+
+    /// One document per sentence, with the index of the sentence as the division ID
+    pub fn sentence_batch(sentences: &[FenominalSentence]) -> Vec<Self> {
+        sentences
+            .iter()
+            .enumerate()
+            .map(|(i, sentence)| {
+                let mut annotation = Self {
+                    text: sentence.original_text.clone(),
+                    divid: Some(i),
+                    ..Default::default()
+                };
+                annotation.add_hits(&sentence.original_text, sentence.start, &sentence.hits());
+                annotation
+            })
+            .collect()
+    }
+
+    /// Set the source of the document, e.g., `PubMed` and the PMID
+    pub fn with_source(mut self, sourcedb: impl Into<String>, sourceid: impl Into<String>) -> Self {
+        self.sourcedb = Some(sourcedb.into());
+        self.sourceid = Some(sourceid.into());
+        self
+    }
+
+    /// Add the provenance of the hits
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// `offset` is the byte offset of `text` in the coordinates of the hits
+    fn add_hits(&mut self, text: &str, offset: usize, hits: &[FenominalHit]) {
+        for hit in hits {
+            let id = format!("T{}", self.denotations.len() + 1);
+            if !hit.is_observed {
+                self.attributes.push(Attribute {
+                    id: format!("A{}", self.attributes.len() + 1),
+                    subj: id.clone(),
+                    pred: NEGATION.to_string(),
+                    obj: serde_json::Value::Bool(true),
+                });
+            }
+            self.denotations.push(Denotation {
+                id,
+                span: Span {
+                    begin: char_offset(text, hit.span.start - offset),
+                    end: char_offset(text, hit.span.end - offset),
+                },
+                obj: term_iri(&hit.term_id),
+            });
+        }
+    }
+}
+
+/// The OBO PURL of a term, e.g., `http://purl.obolibrary.org/obo/HP_0001250` for `HP:0001250`
+pub fn term_iri(term_id: &str) -> String {
+    format!("{}{}", OBO_PURL, term_id.replacen(':', "_", 1))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::hpo::text_to_annotation::fenominal_hits_to_sentence;
+
+    const TEXT: &str = "Café au lait spots and seizures. No scoliosis or kyphosis.";
+
+    fn hits() -> Vec<FenominalHit> {
+        vec![
+            FenominalHit::new("HP:0000957".to_string(), "Cafe-au-lait spot", 0..19, true),
+            FenominalHit::new("HP:0001250".to_string(), "Seizure", 24..32, true),
+            FenominalHit::new("HP:0002650".to_string(), "Scoliosis", 37..46, false),
+            FenominalHit::new("HP:0002808".to_string(), "Kyphosis", 50..58, false),
+        ]
+    }
+
+    #[test]
+    fn test_from_hits() {
+        let annotation = PubAnnotation::from_hits(TEXT, &hits()).with_source("PubMed", "123");
+        let value: Value = serde_json::to_value(&annotation).unwrap();
+        assert_eq!(
+            json!({
+                "text": TEXT,
+                "sourcedb": "PubMed",
+                "sourceid": "123",
+                "denotations": [
+                    {"id": "T1", "span": {"begin": 0, "end": 18}, "obj": "http://purl.obolibrary.org/obo/HP_0000957"},
+                    {"id": "T2", "span": {"begin": 23, "end": 31}, "obj": "http://purl.obolibrary.org/obo/HP_0001250"},
+                    {"id": "T3", "span": {"begin": 36, "end": 45}, "obj": "http://purl.obolibrary.org/obo/HP_0002650"},
+                    {"id": "T4", "span": {"begin": 49, "end": 57}, "obj": "http://purl.obolibrary.org/obo/HP_0002808"}
+                ],
+                "attributes": [
+                    {"id": "A1", "subj": "T3", "pred": "negation", "obj": true},
+                    {"id": "A2", "subj": "T4", "pred": "negation", "obj": true}
+                ]
+            }),
+            value
+        );
+        let parsed: PubAnnotation = serde_json::from_value(value).unwrap();
+        assert_eq!(annotation, parsed);
+    }
+
+    #[test]
+    fn test_from_sentences() {
+        let hits = hits();
+        let sentences = vec![
+            fenominal_hits_to_sentence("Café au lait spots and seizures.", 0, &hits[..2]).unwrap(),
+            fenominal_hits_to_sentence("No scoliosis or kyphosis.", 34, &hits[2..]).unwrap(),
+        ];
+        let document = PubAnnotation::from_sentences(&sentences);
+        assert_eq!(PubAnnotation::from_hits(TEXT, &hits), document);
+        let batch = PubAnnotation::sentence_batch(&sentences);
+        assert_eq!(2, batch.len());
+        assert!(batch[0].attributes.is_empty());
+        assert_eq!(Some(1), batch[1].divid);
+        // the denotations and their negations are numbered per sentence
+        let spans: Vec<&Span> = batch[1].denotations.iter().map(|d| &d.span).collect();
+        assert_eq!(vec![&Span { begin: 3, end: 12 }, &Span { begin: 16, end: 24 }], spans);
+        let negated: Vec<(&str, &str)> = batch[1].attributes.iter().map(|a| (a.id.as_str(), a.subj.as_str())).collect();
+        assert_eq!(vec![("A1", "T1"), ("A2", "T2")], negated);
+    }
+}
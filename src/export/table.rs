@@ -0,0 +1,267 @@
+//! Tabular output
+//!
+//! Writes hits as flat tables (TSV or CSV) with one row per hit, and document summaries
+//! (see [`PhenotypeSummary`]) with one row per term. Both tables start with a header line.
+//!
+//! Fields that contain the delimiter, a double quote or a line break are enclosed in double quotes,
+//! with double quotes doubled (RFC 4180). The same rule is used for TSV, so that any field can be
+//! read back, e.g., with Python's `csv` module or R's `read.delim`.
+//!
+//! The [`Provenance`] of the hits can be written before the header as comment lines (`# key: value`),
+//! which are skipped with `comment='#'` in pandas or `comment.char = "#"` in R.
+
+use std::io::Write;
+
+use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::term::{MinimalTerm, Synonymous};
+
+use crate::fenominal::Fenominal;
+use crate::models::fenominal_model::FenominalHit;
+use crate::models::phenotype_summary::PhenotypeSummary;
+use crate::models::provenance::Provenance;
+use crate::simple_sentence::SimpleSentence;
+use crate::util::error::FenominalError;
+
+const HIT_COLUMNS: [&str; 8] = ["document_id", "sentence_index", "start", "end", "matched_text", "term_id", "label", "assertion"];
+const SUMMARY_COLUMNS: [&str; 8] = [
+    "document_id",
+    "term_id",
+    "label",
+    "assertion",
+    "mention_count",
+    "first_position",
+    "last_position",
+    "conflicting",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Tsv,
+    Csv,
+}
+
+impl TableFormat {
+    fn delimiter(self) -> char {
+        match self {
+            TableFormat::Tsv => '\t',
+            TableFormat::Csv => ',',
+        }
+    }
+}
+
+/// One row of the hit table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitRow {
+    pub document_id: String,
+    /// The index of the sentence (0-based) that contains the hit
+    pub sentence_index: usize,
+    /// Byte offsets of the hit in the document
+    pub start: usize,
+    pub end: usize,
+    pub matched_text: String,
+    pub term_id: String,
+    pub label: String,
+    /// `present` or `absent`
+    pub assertion: String,
+}
+
+impl HitRow {
+    /// The rows of the hits of a document; `text` is the text the hits were mined from by `fenominal`,
+    /// which splits the sentences as in [`Fenominal::process`].
+    pub fn from_hits<O, T>(fenominal: &Fenominal<O, T>, document_id: &str, text: &str, hits: &[FenominalHit]) -> Vec<HitRow>
+    where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm + Synonymous,
+    {
+        Self::from_sentences(document_id, text, &fenominal.split_sentences(text), hits)
+    }
+
+    fn from_sentences(document_id: &str, text: &str, sentences: &[SimpleSentence], hits: &[FenominalHit]) -> Vec<HitRow> {
+        let starts: Vec<usize> = sentences.iter().map(|s| s.get_start_pos()).collect();
+        hits.iter()
+            .map(|hit| HitRow {
+                document_id: document_id.to_string(),
+                sentence_index: starts.partition_point(|&s| s <= hit.span.start).saturating_sub(1),
+                start: hit.span.start,
+                end: hit.span.end,
+                matched_text: text[hit.span.clone()].to_string(),
+                term_id: hit.term_id.clone(),
+                label: hit.label.clone(),
+                assertion: assertion(hit.is_observed).to_string(),
+            })
+            .collect()
+    }
+
+    fn fields(&self) -> [String; 8] {
+        [
+            self.document_id.clone(),
+            self.sentence_index.to_string(),
+            self.start.to_string(),
+            self.end.to_string(),
+            self.matched_text.clone(),
+            self.term_id.clone(),
+            self.label.clone(),
+            self.assertion.clone(),
+        ]
+    }
+}
+
+/// One row of the summary table, i.e., one term of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryRow {
+    pub document_id: String,
+    pub term_id: String,
+    pub label: String,
+    /// The final status of the term (`present` or `absent`)
+    pub assertion: String,
+    pub mention_count: usize,
+    pub first_position: usize,
+    pub last_position: usize,
+    /// Were there both observed and excluded mentions?
+    pub conflicting: bool,
+}
+
+impl SummaryRow {
+    pub fn from_summary(document_id: &str, summary: &PhenotypeSummary) -> Vec<SummaryRow> {
+        summary
+            .iter()
+            .map(|entry| SummaryRow {
+                document_id: document_id.to_string(),
+                term_id: entry.term.id.clone(),
+                label: entry.term.label.clone(),
+                assertion: assertion(entry.is_observed).to_string(),
+                mention_count: entry.mention_count,
+                first_position: entry.first_position,
+                last_position: entry.last_position,
+                conflicting: entry.is_conflicting,
+            })
+            .collect()
+    }
+
+    fn fields(&self) -> [String; 8] {
+        [
+            self.document_id.clone(),
+            self.term_id.clone(),
+            self.label.clone(),
+            self.assertion.clone(),
+            self.mention_count.to_string(),
+            self.first_position.to_string(),
+            self.last_position.to_string(),
+            self.conflicting.to_string(),
+        ]
+    }
+}
+
+/// Write the header and one line per hit
+pub fn write_hits<W: Write>(writer: &mut W, format: TableFormat, rows: &[HitRow]) -> Result<(), FenominalError> {
+    write_line(writer, format, &HIT_COLUMNS)?;
+    for row in rows {
+        write_line(writer, format, &row.fields())?;
+    }
+    Ok(())
+}
+
+/// Write the header and one line per term
+pub fn write_summary<W: Write>(writer: &mut W, format: TableFormat, rows: &[SummaryRow]) -> Result<(), FenominalError> {
+    write_line(writer, format, &SUMMARY_COLUMNS)?;
+    for row in rows {
+        write_line(writer, format, &row.fields())?;
+    }
+    Ok(())
+}
+
+/// Write the provenance as comment lines, e.g., `# hp_version: 2025-03-03`
+pub fn write_provenance<W: Write>(writer: &mut W, provenance: &Provenance) -> Result<(), FenominalError> {
+    for (key, value) in provenance.to_pairs() {
+        // a line break would end the comment
+        writeln!(writer, "# {}: {}", key, value.replace(['\n', '\r'], " "))?;
+    }
+    Ok(())
+}
+
+fn assertion(is_observed: bool) -> &'static str {
+    if is_observed { "present" } else { "absent" }
+}
+
+fn write_line<W: Write, S: AsRef<str>>(writer: &mut W, format: TableFormat, fields: &[S]) -> Result<(), FenominalError> {
+    let delimiter = format.delimiter();
+    let line: Vec<String> = fields.iter().map(|f| quote(f.as_ref(), delimiter)).collect();
+    writeln!(writer, "{}", line.join(&delimiter.to_string()))?;
+    Ok(())
+}
+
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::clinical_section::SectionLexicon;
+    use crate::core_document::CoreDocument;
+
+    use super::*;
+
+    #[rstest]
+    #[case("seizures", ',', "seizures")]
+    #[case("seizures, focal", ',', "\"seizures, focal\"")]
+    #[case("seizures, focal", '\t', "seizures, focal")]
+    #[case("a\tb", '\t', "\"a\tb\"")]
+    #[case("the \"worst\" headache", '\t', "\"the \"\"worst\"\" headache\"")]
+    #[case("two\nlines", ',', "\"two\nlines\"")]
+    fn test_quote(#[case] field: &str, #[case] delimiter: char, #[case] expected: &str) {
+        assert_eq!(expected, quote(field, delimiter));
+    }
+
+    fn rows() -> Vec<HitRow> {
+        let text = "Mild intellectual disability. No scoliosis.";
+        let hits = vec![
+            FenominalHit::new("HP:0001256".to_string(), "Intellectual disability, mild", 0..28, true),
+            FenominalHit::new("HP:0002650".to_string(), "Scoliosis", 33..42, false),
+        ];
+        let sentences = CoreDocument::new(text, &SectionLexicon::empty()).into_sentences();
+        HitRow::from_sentences("note,1", text, &sentences, &hits)
+    }
+
+    #[test]
+    fn test_hit_rows() {
+        let rows = rows();
+        assert_eq!(vec![0, 1], rows.iter().map(|r| r.sentence_index).collect::<Vec<_>>());
+        assert_eq!("Mild intellectual disability", rows[0].matched_text);
+        assert_eq!("absent", rows[1].assertion);
+    }
+
+    #[test]
+    fn test_write_hits() {
+        let mut tsv = Vec::new();
+        write_hits(&mut tsv, TableFormat::Tsv, &rows()).unwrap();
+        let expected = "document_id\tsentence_index\tstart\tend\tmatched_text\tterm_id\tlabel\tassertion\n\
+                        note,1\t0\t0\t28\tMild intellectual disability\tHP:0001256\tIntellectual disability, mild\tpresent\n\
+                        note,1\t1\t33\t42\tscoliosis\tHP:0002650\tScoliosis\tabsent\n";
+        assert_eq!(expected, String::from_utf8(tsv).unwrap());
+        let mut csv = Vec::new();
+        write_hits(&mut csv, TableFormat::Csv, &rows()).unwrap();
+        // the document ID and the label contain the delimiter
+        let expected = "\n\"note,1\",0,0,28,Mild intellectual disability,HP:0001256,\"Intellectual disability, mild\",present\n";
+        assert!(String::from_utf8(csv).unwrap().contains(expected));
+    }
+
+    #[test]
+    fn test_write_summary() {
+        let hits = vec![
+            FenominalHit::new("HP:0001250".to_string(), "Seizure", 17..25, false),
+            FenominalHit::new("HP:0001250".to_string(), "Seizure", 40..48, true),
+        ];
+        let rows = SummaryRow::from_summary("note-1", &PhenotypeSummary::from_hits(&hits));
+        let mut csv = Vec::new();
+        write_summary(&mut csv, TableFormat::Csv, &rows).unwrap();
+        let expected = "document_id,term_id,label,assertion,mention_count,first_position,last_position,conflicting\n\
+                        note-1,HP:0001250,Seizure,present,2,17,40,true\n";
+        assert_eq!(expected, String::from_utf8(csv).unwrap());
+    }
+}
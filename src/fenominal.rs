@@ -1,27 +1,53 @@
 
 use std::sync::Arc;
+use std::time::Instant;
 
 
+use crate::clinical_section::{segment_sections, ClinicalSection, SectionFilter, SectionLexicon};
 use crate::core_document::CoreDocument;
+use crate::builder::FenominalBuilder;
+use crate::hpo::branches::default_roots;
+use crate::hpo::mining_dictionary::MiningDictionary;
+use crate::hpo::obsolete::TermIdResolver;
 use crate::hpo::sentence_mapper::SentenceMapper;
 use crate::hpo::text_to_annotation::fenominal_hits_to_sentence;
-use crate::models::fenominal_model::{FenominalHit, FenominalSentence};
+use crate::models::batch::{BatchResult, DocumentResult};
+use crate::models::fenominal_model::{Experiencer, FenominalHit, FenominalSegment, FenominalSentence};
+use crate::models::organ_system_summary::OrganSystemSummary;
+use crate::models::phenotype_summary::{ConflictPolicy, PhenotypeSummary};
+use crate::models::offset_unit::OffsetUnit;
+use crate::models::provenance::{Configuration, MatchMode, MiningResult, OntologyVersion, Provenance};
+use crate::options::FenominalOptions;
 use crate::simple_sentence::SimpleSentence;
+use crate::structured_form::parse_form_lines;
 use crate::util::error::FenominalError;
+use crate::util::negex::NegEx;
 use crate::{sanitize, sentence_split};
-use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::ontology::{HierarchyWalks, MetadataAware, OntologyTerms};
 use ontolius::term::{MinimalTerm, Synonymous};
+use ontolius::TermId;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
-#[cfg(feature = "serde")]
-
-
-
+/// With the `rayon` feature, [`Fenominal::process_batch`] takes this many documents per thread at a time
+#[cfg(feature = "rayon")]
+const BATCH_CHUNK_DOCUMENTS_PER_THREAD: usize = 16;
 
 /// Fenominal text mining.
+///
+/// By default, fenominal mines HPO terms. Terms of other ontologies in OBO Graphs format (e.g., diseases
+/// from MONDO or treatments from MAxO) can be mined in the same pass with [`FenominalBuilder::ontology`].
+/// All other settings are made with the [`FenominalBuilder`], which validates them; a `Fenominal` cannot
+/// be reconfigured once it is built.
 pub struct Fenominal<O, T> where
         O: OntologyTerms<T> + HierarchyWalks,
         T: MinimalTerm + Synonymous  {
-    sentence_mapper: SentenceMapper<O,T>,
+    /// One mapper per ontology, in order of priority
+    sentence_mappers: Vec<SentenceMapper<O,T>>,
+    section_lexicon: SectionLexicon,
+    options: FenominalOptions,
+    /// Replaces outdated term IDs of the hits
+    id_resolver: Option<TermIdResolver>,
 }
 
 impl<O, T> Fenominal<O, T> 
@@ -31,52 +57,411 @@ impl<O, T> Fenominal<O, T>
     {
 
     pub fn new(hpo: Arc<O>)-> Self {
-        let hpo_arc = Arc::clone(&hpo);
+        Self::from_roots(hpo, &default_roots())
+    }
+
+    /// Create a [`FenominalBuilder`] to configure the options of text mining.
+    pub fn builder(ontology: Arc<O>) -> FenominalBuilder<O, T> {
+        FenominalBuilder::new(ontology)
+    }
+
+    /// Mine the descendants of the given root terms, e.g., Phenotypic abnormality HP:0000118 and
+    /// Mode of inheritance HP:0000005 (see [`crate::branches`]). Each hit reports the root of its branch.
+    ///
+    /// The ontology does not need to be the HPO; for instance, MONDO can be mined from the root
+    /// disease MONDO:0000001. The HPO-specific features (normal findings, onset and modifiers)
+    /// are only available for HPO terms.
+    pub fn from_roots(ontology: Arc<O>, roots: &[TermId]) -> Self {
+        let options = FenominalOptions {
+            roots: roots.iter().map(TermId::to_string).collect(),
+            ..Default::default()
+        };
         Self {
-            sentence_mapper: SentenceMapper::new(hpo_arc),
+            sentence_mappers: vec![SentenceMapper::new(ontology, roots)],
+            section_lexicon: SectionLexicon::empty(),
+            options,
+            id_resolver: None,
+        }
+    }
+
+    /// Create fenominal from the mappers of validated options (see [`FenominalBuilder::build`]).
+    pub(crate) fn from_options(
+        sentence_mappers: Vec<SentenceMapper<O, T>>,
+        section_lexicon: SectionLexicon,
+        options: FenominalOptions,
+        id_resolver: Option<TermIdResolver>,
+    ) -> Self {
+        Self {
+            sentence_mappers,
+            section_lexicon: section_lexicon_for(section_lexicon, &options.section_filter),
+            options,
+            id_resolver,
+        }
+    }
+
+    /// Mine the terms of a precompiled dictionary with the default options, without loading the ontology
+    /// (see [`MiningDictionary`] and [`FenominalBuilder::from_dictionary`]).
+    pub fn from_dictionary(dictionary: MiningDictionary) -> Result<Self, FenominalError> {
+        FenominalBuilder::from_dictionary(dictionary).build()
+    }
+
+    /// The settings that affect the hits (see [`Provenance`])
+    pub fn configuration(&self) -> Configuration {
+        Configuration {
+            match_mode: MatchMode::TokenSet,
+            negation_lexicon_hash: NegEx::lexicon_hash(),
+            branches: self
+                .sentence_mappers
+                .iter()
+                .flat_map(|m| m.roots().iter().map(TermId::to_string))
+                .collect(),
+            branch_filter: self.options.branch_filter.clone(),
+            section_filter: self.options.section_filter.clone(),
+            proband_only: self.options.proband_only,
+            structured_forms: self.options.structured_forms,
+            remove_redundant: self.options.remove_redundant,
+            omittable_labels: self.options.omittable_labels.clone(),
+            min_synonym_length: self.options.min_synonym_length,
+            stopwords: self.options.stopwords.clone(),
+            negation_window: self.options.negation_window,
+        }
+    }
+
+    /// The options of text mining
+    pub fn options(&self) -> &FenominalOptions {
+        &self.options
+    }
+
+    /// Flag the hits that are redundant according to the hierarchy of any of the mined ontologies
+    fn find_redundant_hits(&self, hits: &[FenominalHit]) -> Vec<bool> {
+        let mut redundant = vec![false; hits.len()];
+        for sentence_mapper in &self.sentence_mappers {
+            for (flag, is_redundant) in redundant.iter_mut().zip(sentence_mapper.find_redundant_hits(hits)) {
+                *flag |= is_redundant;
+            }
+        }
+        redundant
+    }
+
+    fn map_sentence(&self, ss: &SimpleSentence) -> Result<Vec<FenominalHit>, FenominalError> {
+        if !self.options.section_filter.accepts(ss.get_section()) {
+            return Ok(Vec::new());
+        }
+        let mut hits = Vec::new();
+        for sentence_mapper in &self.sentence_mappers {
+            hits.extend(sentence_mapper.map_sentence(ss)?);
+        }
+        if self.sentence_mappers.len() > 1 {
+            hits = remove_overlapping_hits(hits);
+        }
+        for hit in hits.iter_mut() {
+            hit.section = ss.get_section().cloned();
+            // Findings listed in the family history refer to relatives even if no relation is mentioned
+            if hit.section == Some(ClinicalSection::FamilyHistory) && hit.is_proband() {
+                hit.experiencer = Experiencer::FamilyMember { relation: None };
+            }
+        }
+        if self.options.proband_only {
+            hits.retain(FenominalHit::is_proband);
+        }
+        if let Some(branches) = &self.options.branch_filter {
+            hits.retain(|h| branches.iter().any(|root| h.is_in_branch(root)));
+        }
+        self.remap_outdated_hits(&mut hits);
+        Ok(hits)
+    }
+
+    /// Replace outdated term IDs of the hits by the current terms (if an ID resolver was set) and
+    /// classify the replaced hits anew. A current term that is not in the mined branches has no
+    /// branch and no categories.
+    fn remap_outdated_hits(&self, hits: &mut [FenominalHit]) {
+        let Some(id_resolver) = &self.id_resolver else {
+            return;
+        };
+        let mined_ids: Vec<String> = hits.iter().map(|h| h.term_id.clone()).collect();
+        id_resolver.remap_hits(hits);
+        for (hit, mined_id) in hits.iter_mut().zip(mined_ids) {
+            if hit.term_id != mined_id && !self.sentence_mappers.iter().any(|m| m.classify_hit(hit)) {
+                hit.branch = None;
+                hit.categories.clear();
+            }
         }
     }
 
     pub fn map_text(&self, text: &str) -> Result<Vec<FenominalHit>, FenominalError> {
-        let core_document = CoreDocument::new(text);
-        let sentences = core_document.get_sentences();
         let mut mapped_parts: Vec<FenominalHit> = Vec::new();
-        for ss in sentences {
-            let sentence_parts = self.sentence_mapper.map_sentence(ss)?;
+        let mut free_text = text.to_string();
+        if self.options.structured_forms {
+            let form_hits = self.map_form_lines(text, &mut free_text)?;
+            mapped_parts.extend(form_hits);
+        }
+        for ss in self.split_sentences(&free_text) {
+            let sentence_parts = self.map_sentence(&ss)?;
             mapped_parts.extend(sentence_parts);
         }
+        mapped_parts.sort_by_key(|h| h.span.start);
+        if self.options.remove_redundant {
+            let redundant = self.find_redundant_hits(&mapped_parts);
+            mapped_parts = mapped_parts
+                .into_iter()
+                .zip(redundant)
+                .filter_map(|(hit, is_redundant)| (!is_redundant).then_some(hit))
+                .collect();
+        }
         Ok(mapped_parts)
     }
 
+    /// Split a text into sentences as [`Fenominal::map_text`] does, with the section lexicon of this fenominal
+    pub(crate) fn split_sentences(&self, text: &str) -> Vec<SimpleSentence> {
+        CoreDocument::new(text, &self.section_lexicon).into_sentences()
+    }
+
+    /// Map the keys of structured form lines. Lines with at least one hit are blanked out in `free_text`
+    /// (keeping all byte offsets) so that they are not mined a second time as free text.
+    fn map_form_lines(&self, text: &str, free_text: &mut String) -> Result<Vec<FenominalHit>, FenominalError> {
+        let sections = segment_sections(text, &self.section_lexicon);
+        let mut form_hits = Vec::new();
+        for form_line in parse_form_lines(text) {
+            let section = sections
+                .iter()
+                .find(|s| s.body.contains(&form_line.line.start))
+                .and_then(|s| s.section.clone());
+            let mut line_hits = Vec::new();
+            for field in &form_line.fields {
+                let key = &text[field.key.clone()];
+                let ss = SimpleSentence::new(key, field.key.start, field.key.end).with_section(section.clone());
+                for mut hit in self.map_sentence(&ss)? {
+                    hit.is_observed = field.is_observed;
+                    line_hits.push(hit);
+                }
+            }
+            if !line_hits.is_empty() {
+                let line = form_line.line.clone();
+                free_text.replace_range(line.clone(), &" ".repeat(line.len()));
+                form_hits.extend(line_hits);
+            }
+        }
+        Ok(form_hits)
+    }
+
+    /// Mine the text. The spans of the hits are byte offsets into `text`, so that `&text[hit.span.clone()]`
+    /// is the matched text (earlier releases counted the start of a sentence in characters, which shifted
+    /// the spans of hits after non-ASCII text). See [`OffsetUnit`] to convert them, e.g., to character offsets.
     pub fn process(
         &self, 
         text: &str) -> Result<Vec<FenominalHit>, FenominalError> {
         self.map_text(text)
     }
 
-    fn mine_sentence(&self, sentence: &str,  start: usize) -> Result<FenominalSentence, FenominalError> {
+    /// Mine the text and aggregate the findings of the proband by term, resolving contradictory
+    /// mentions with the given policy (see [`PhenotypeSummary`]).
+    pub fn summarize(&self, text: &str, policy: ConflictPolicy) -> Result<PhenotypeSummary, FenominalError> {
+        let hits = self.map_text(text)?;
+        Ok(PhenotypeSummary::from_hits_with_policy(&hits, policy))
+    }
+
+    /// Mine the text and group the findings of the proband by organ system (see [`OrganSystemSummary`]).
+    pub fn summarize_organ_systems(&self, text: &str) -> Result<OrganSystemSummary, FenominalError> {
+        let hits = self.map_text(text)?;
+        Ok(OrganSystemSummary::from_hits(&hits))
+    }
+
+    /// Mine a batch of documents given as (document ID, text) pairs, e.g., the notes of a cohort. With the
+    /// `rayon` feature, the documents are mined in parallel on the global rayon thread pool. The results are
+    /// returned in the order of the input. The documents are taken from `documents` as they are mined (a
+    /// few per thread at a time), so the input can be a lazy iterator, e.g., over the files of a directory.
+    /// A document that cannot be mined is reported with its error and does not abort the batch.
+    pub fn process_batch<I, D, S>(&self, documents: I) -> BatchResult
+    where
+        I: IntoIterator<Item = (D, S)>,
+        D: Into<String> + Send,
+        S: AsRef<str> + Send,
+        O: Send + Sync,
+        T: Send + Sync,
+    {
+        let start = Instant::now();
+        let mine = |(document_id, text): (D, S)| self.mine_batch_document(document_id.into(), text.as_ref());
+        let mut results = Vec::new();
+        #[cfg(feature = "rayon")]
+        let threads = {
+            // take the documents in chunks, so that only the texts of one chunk are held at a time
+            let threads = rayon::current_num_threads();
+            let mut documents = documents.into_iter();
+            loop {
+                let chunk: Vec<(D, S)> = documents.by_ref().take(threads * BATCH_CHUNK_DOCUMENTS_PER_THREAD).collect();
+                if chunk.is_empty() {
+                    break;
+                }
+                results.par_extend(chunk.into_par_iter().map(mine));
+            }
+            threads
+        };
+        #[cfg(not(feature = "rayon"))]
+        let threads = {
+            results.extend(documents.into_iter().map(mine));
+            1
+        };
+        BatchResult::new(results, start.elapsed(), threads)
+    }
+
+    /// Mine a document of a batch
+    fn mine_batch_document(&self, document_id: String, text: &str) -> DocumentResult {
+        DocumentResult {
+            document_id,
+            text_length: text.len(),
+            hits: self.map_text(text),
+        }
+    }
+
+    fn mine_sentence(&self, sentence: &str,  start: usize, section: Option<&ClinicalSection>) -> Result<FenominalSentence, FenominalError> {
         let sentence_end = start + sentence.len() - 1;
-        let ss = SimpleSentence::new(sentence, start, sentence_end);
-        let hits =  self.sentence_mapper.map_sentence(&ss)?;
-        fenominal_hits_to_sentence(sentence,start,  &hits)
-          
+        let ss = SimpleSentence::new(sentence, start, sentence_end).with_section(section.cloned());
+        let hits =  self.map_sentence(&ss)?;
+        let mut fsent = fenominal_hits_to_sentence(sentence,start,  &hits)?;
+        fsent.section = section.cloned();
+        Ok(fsent)
     }
 
+    /// Mine the sanitized text sentence by sentence. If section detection is on (see
+    /// [`FenominalBuilder::section_lexicon`]), section headers are returned as sentences without hits,
+    /// so that the sentences represent the entire (sanitized) text, and sentences of sections that
+    /// are rejected by the section filter are not returned.
     pub fn mine_sentences(&self, text: &str) -> Result<Vec<FenominalSentence>, FenominalError> {
+        let mut fenom_sent_list = if self.section_lexicon.is_empty() {
+            self.mine_text_sentences(text)?
+        } else {
+            self.mine_section_sentences(text)?
+        };
+        if self.options.remove_redundant {
+            self.remove_redundant_sentence_hits(&mut fenom_sent_list)?;
+        }
+        Ok(fenom_sent_list)
+    }
+
+    fn mine_text_sentences(&self, text: &str) -> Result<Vec<FenominalSentence>, FenominalError> {
         let sanitized_text = sanitize(text);
         let sentences = sentence_split(&sanitized_text);
-        let mut start = 0 as usize;
+        let mut start = 0_usize;
         let mut fenom_sent_list = Vec::with_capacity(sentences.len());
-        for (i, s) in sentences.into_iter().enumerate() {
-            let fsent = self.mine_sentence(&s, start)?;
-            start += fsent.text_length() +1;
+        for s in sentences {
+            let fsent = self.mine_sentence(&s, start, None)?;
+            start += fsent.text_length() + 1;
             fenom_sent_list.push(fsent);
         }
         Ok(fenom_sent_list)
     }
 
-}    
+    fn mine_section_sentences(&self, text: &str) -> Result<Vec<FenominalSentence>, FenominalError> {
+        let mut start = 0_usize;
+        let mut fenom_sent_list = Vec::new();
+        for text_section in segment_sections(text, &self.section_lexicon) {
+            let section = text_section.section.as_ref();
+            let accepted = self.options.section_filter.accepts(section);
+            if let Some(header) = &text_section.header {
+                let header_text = sanitize(&text[header.clone()]);
+                if !header_text.is_empty() {
+                    let segments = vec![FenominalSegment::plain_text(header_text.as_str(), start)];
+                    let mut fsent = FenominalSentence::new(start, header_text.as_str(), segments);
+                    fsent.section = section.cloned();
+                    start += fsent.text_length() + 1;
+                    if accepted {
+                        fenom_sent_list.push(fsent);
+                    }
+                }
+            }
+            let sanitized_text = sanitize(&text[text_section.body.clone()]);
+            for s in sentence_split(&sanitized_text) {
+                if accepted {
+                    let fsent = self.mine_sentence(&s, start, section)?;
+                    fenom_sent_list.push(fsent);
+                }
+                start += s.len() + 1;
+            }
+        }
+        Ok(fenom_sent_list)
+    }
 
+    /// Redundancy is determined for the entire text; sentences that lose hits are segmented anew.
+    fn remove_redundant_sentence_hits(&self, sentences: &mut [FenominalSentence]) -> Result<(), FenominalError> {
+        let hits: Vec<FenominalHit> = sentences.iter().flat_map(FenominalSentence::hit_iter).cloned().collect();
+        let mut redundant = self.find_redundant_hits(&hits).into_iter();
+        for fsent in sentences.iter_mut() {
+            let sentence_hits = fsent.hits();
+            let kept: Vec<FenominalHit> = sentence_hits
+                .iter()
+                .zip(redundant.by_ref())
+                .filter(|(_, is_redundant)| !is_redundant)
+                .map(|(hit, _)| hit.clone())
+                .collect();
+            if kept.len() < sentence_hits.len() {
+                let section = fsent.section.take();
+                *fsent = fenominal_hits_to_sentence(&fsent.original_text, fsent.start, &kept)?;
+                fsent.section = section;
+            }
+        }
+        Ok(())
+    }
+
+}
+
+impl<O, T> Fenominal<O, T>
+    where
+    O: OntologyTerms<T> + HierarchyWalks + MetadataAware,
+    T: MinimalTerm + Synonymous
+    {
+
+    /// The provenance of the hits of a document: the versions of fenominal and of the mined ontologies
+    /// (from the metadata of the loaded graphs or of the precompiled dictionary) and the configuration.
+    pub fn provenance(&self, document_id: &str) -> Provenance {
+        let mut ontologies: Vec<OntologyVersion> = Vec::new();
+        for mapper in &self.sentence_mappers {
+            let prefix = mapper.roots().first().map_or_else(String::new, |r| r.prefix().to_string());
+            if !ontologies.iter().any(|o| o.prefix == prefix) {
+                ontologies.push(OntologyVersion {
+                    prefix,
+                    version: mapper
+                        .ontology()
+                        .map_or_else(|| mapper.dictionary_version().to_string(), |o| o.version().to_string()),
+                });
+            }
+        }
+        Provenance {
+            document_id: document_id.to_string(),
+            fenominal_version: env!("CARGO_PKG_VERSION").to_string(),
+            ontologies,
+            configuration: self.configuration(),
+        }
+    }
 
+    /// Mine the text and wrap the hits in their provenance
+    pub fn mine_document(&self, document_id: &str, text: &str) -> Result<MiningResult, FenominalError> {
+        Ok(MiningResult {
+            provenance: self.provenance(document_id),
+            offset_unit: OffsetUnit::Bytes,
+            hits: self.map_text(text)?,
+        })
+    }
+}
 
+/// Section detection is switched on by a section filter, with the default lexicon unless a lexicon was set
+fn section_lexicon_for(lexicon: SectionLexicon, filter: &SectionFilter) -> SectionLexicon {
+    if lexicon.is_empty() && *filter != SectionFilter::All {
+        SectionLexicon::default()
+    } else {
+        lexicon
+    }
+}
 
+/// Keep the longest of overlapping hits (earlier hits win ties), sorted by position
+fn remove_overlapping_hits(mut hits: Vec<FenominalHit>) -> Vec<FenominalHit> {
+    // stable sort: hits of the same length keep the order of the ontologies
+    hits.sort_by_key(|h| std::cmp::Reverse(h.span.len()));
+    let mut kept: Vec<FenominalHit> = Vec::with_capacity(hits.len());
+    for hit in hits {
+        if kept.iter().all(|k| k.span.end <= hit.span.start || hit.span.end <= k.span.start) {
+            kept.push(hit);
+        }
+    }
+    kept.sort_by_key(|h| h.span.start);
+    kept
+}
@@ -0,0 +1,22 @@
+//! Root terms of the HPO branches that can be mined.
+//!
+//! By default, fenominal only mines the descendants of Phenotypic abnormality HP:0000118. Other branches
+//! (e.g., Mode of inheritance) can be added with [`crate::Fenominal::from_roots`]; each hit then reports
+//! the root of the branch it came from.
+
+use once_cell::sync::Lazy;
+use ontolius::TermId;
+
+pub use ontolius::common::hpo::{CLINICAL_MODIFIER, PHENOTYPIC_ABNORMALITY};
+
+/// Mode of inheritance HP:0000005
+pub static MODE_OF_INHERITANCE: Lazy<TermId> = Lazy::new(|| "HP:0000005".parse().unwrap());
+/// Past medical history HP:0032443
+pub static PAST_MEDICAL_HISTORY: Lazy<TermId> = Lazy::new(|| "HP:0032443".parse().unwrap());
+/// Frequency HP:0040279
+pub static FREQUENCY: Lazy<TermId> = Lazy::new(|| "HP:0040279".parse().unwrap());
+
+/// The branches that are mined by default (Phenotypic abnormality)
+pub fn default_roots() -> Vec<TermId> {
+    vec![PHENOTYPIC_ABNORMALITY.clone()]
+}
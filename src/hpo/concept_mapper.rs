@@ -1,49 +1,47 @@
 use std::collections::{HashMap, HashSet};
-use super::{hpo_concept::HpoConcept};
+use super::{concept::Concept};
 
-pub struct HpoConceptMapper {
+pub struct ConceptMapper {
+    #[allow(dead_code)]
     n_words: usize,
-    component_token_to_concept_map: HashMap<String, Vec<HpoConcept>>,
+    component_token_to_concept_map: HashMap<String, Vec<Concept>>,
 }
 
-impl HpoConceptMapper {
+impl ConceptMapper {
     pub fn new(n: usize) -> Self {
-        let map: HashMap<String, Vec<HpoConcept>> = HashMap::new();
-        HpoConceptMapper {
+        let map: HashMap<String, Vec<Concept>> = HashMap::new();
+        ConceptMapper {
             n_words: n,
             component_token_to_concept_map: map,
         }
     }
 
-    pub fn get_match(&self, words: &[&str]) -> std::option::Option<HpoConcept> {
+    pub fn get_match(&self, words: &[&str]) -> std::option::Option<Concept> {
         let token_set: HashSet<String> = words.iter().map(|&s| s.to_string()).collect();
         for token in &token_set {
-            let concept_list = self.component_token_to_concept_map.get(token);
-            match concept_list {
-                Some(clist) => {
-                    for cpt in clist {
-                        if cpt.non_stop_set_equal(&token_set) {
-                            // We have a match!
-                            return Some(cpt.clone());
-                        }
+            if let Some(clist) = self.component_token_to_concept_map.get(token) {
+                for cpt in clist {
+                    if cpt.non_stop_set_equal(&token_set) {
+                        // We have a match!
+                        return Some(cpt.clone());
                     }
                 }
-                _ => {}
             }
         }
         None // if we get here, we have not matched anything
     }
 
-    pub fn add_concept(&mut self, concept: &HpoConcept) {
+    pub fn add_concept(&mut self, concept: &Concept) {
         for token in concept.get_non_stop_words() {
             // insert a default value (empty vector) if the key is not present, then add the concept to the list
             self.component_token_to_concept_map
                 .entry(token.clone())
-                .or_insert(Vec::new())
+                .or_default()
                 .push(concept.clone());
         }
     }
 
+    #[allow(dead_code)]
     pub fn n_words(&self) -> usize {
         self.n_words
     }
@@ -0,0 +1,92 @@
+use std::collections::{HashMap, HashSet};
+
+use ontolius::TermId;
+
+
+use super::{concept::Concept, concept_mapper::ConceptMapper};
+
+pub struct DefaultTermMapper {
+    wordcount_to_matcher: HashMap<usize, ConceptMapper>,
+    /// The number of (non-stop) words of the longest label or synonym
+    max_token_count: usize,
+}
+
+impl DefaultTermMapper {
+
+    /// Create a DefaultTermMapper from text_to_tid_map
+    ///
+    /// # Arguments
+    ///
+    /// * `text_to_term_id` - An iterator with mapping from text to corresponding term ID.
+    /// * `stopwords` - The (lower-case) words that are ignored.
+    ///
+    /// # Returns
+    ///
+    /// A DefaultTermMapper object that is ready to use for text mining.
+    pub fn from_map<'a, I>(text_to_term_id: I, stopwords: &HashSet<String>) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a TermId)>,
+    {
+        let mut wc_map: HashMap<usize, ConceptMapper> = HashMap::new();
+        for (key, value) in text_to_term_id {
+            let concept = Concept::with_stopwords(key, value.clone(), stopwords);
+            let n_tokens = concept.word_count();
+            if n_tokens == 0 {
+                continue;
+            }
+            wc_map
+                .entry(n_tokens)
+                .or_insert_with(|| ConceptMapper::new(n_tokens))
+                .add_concept(&concept);
+        }
+        let max_token_count = wc_map.keys().copied().max().unwrap_or(0);
+        DefaultTermMapper {
+            wordcount_to_matcher: wc_map,
+            max_token_count,
+        }
+    }
+
+    /// The number of words of the longest label or synonym (i.e., the largest window that needs to be checked)
+    pub fn max_token_count(&self) -> usize {
+        self.max_token_count
+    }
+
+    /// Search for an HPO term that matches an input string
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - A listt of tokens (words) representing the input string
+    ///
+    /// # Returns
+    ///
+    /// An HpoConceptHit or None
+    pub fn get_match(&self, tokens: &[&str]) -> Option<Concept> {
+        if tokens.len() > self.max_token_count || tokens.is_empty() {
+            None
+        } else {
+            let matcher = self.wordcount_to_matcher.get(&tokens.len())?;
+            matcher.get_match(tokens)
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_max_token_count_is_derived_from_labels() {
+        let long_label = "one two three four five six seven eight nine ten eleven twelve thirteen fourteen fifteen sixteen";
+        let short_id = TermId::from_str("MONDO:0007947").unwrap();
+        let long_id = TermId::from_str("MONDO:0000001").unwrap();
+        let mapper = DefaultTermMapper::from_map([("marfan syndrome", &short_id), (long_label, &long_id)], &HashSet::new());
+        assert_eq!(16, mapper.max_token_count());
+        let tokens: Vec<&str> = long_label.split(' ').collect();
+        let hit = mapper.get_match(&tokens).expect("long label should match");
+        assert_eq!(&long_id, hit.get_term_id());
+        assert!(mapper.get_match(&["marfan", "syndrome"]).is_some());
+    }
+}
@@ -1,12 +1,44 @@
-use std::{collections::{HashMap, HashSet}, sync::Arc};
+use std::collections::{HashMap, HashSet};
 
 use ontolius::{
-    common::hpo::PHENOTYPIC_ABNORMALITY,
     ontology::{HierarchyWalks, OntologyTerms},
     term::{MinimalTerm, Synonymous},
     TermId,
 };
 
+use crate::options::{FenominalOptions, DEFAULT_MIN_SYNONYM_LENGTH, DEFAULT_OMITTABLE_LABELS};
+
+/// The labels and synonyms that are not mined
+#[derive(Debug, Clone)]
+pub struct LabelFilter {
+    /// Lower-case labels that are common false-positive results, because they occur in other contexts
+    omittable_labels: HashSet<String>,
+    min_length: usize,
+}
+
+impl Default for LabelFilter {
+    fn default() -> Self {
+        Self {
+            omittable_labels: DEFAULT_OMITTABLE_LABELS.iter().map(|s| s.to_string()).collect(),
+            min_length: DEFAULT_MIN_SYNONYM_LENGTH,
+        }
+    }
+}
+
+impl LabelFilter {
+    pub fn from_options(options: &FenominalOptions) -> Self {
+        Self {
+            omittable_labels: options.omittable_label_set(),
+            min_length: options.min_synonym_length,
+        }
+    }
+
+    /// Should the lower-case label or synonym be mined?
+    fn accepts(&self, text: &str) -> bool {
+        text.len() >= self.min_length && !self.omittable_labels.contains(text)
+    }
+}
+
 
 
 // /// Load the HPO
@@ -31,34 +63,61 @@ use ontolius::{
 //     }
 // }
 
-pub fn get_text_to_hpo_term_map<O, T>(hpo: Arc<O>) -> HashMap<String, TermId>
+/// Map the lower-case labels and synonyms of the descendants of the root terms (e.g., Phenotypic abnormality
+/// and Mode of inheritance) to their term IDs. If a text is shared by terms of several branches, the term of
+/// the branch whose root comes first wins.
+pub fn get_text_to_term_map_for_roots<O, T>(hpo: &O, roots: &[TermId], filter: &LabelFilter) -> HashMap<String, TermId>
 where
     O: OntologyTerms<T> + HierarchyWalks,
     T: MinimalTerm + Synonymous,
 {
     let mut text_to_tid_map = HashMap::new();
-    // These are commmon false-positive results related to HPO synonyms that occur in other contexts
-    let omittable_labels: HashSet<String> = ["negative", "weakness"]
-        .iter()
-        .map(|s| s.to_ascii_lowercase())
-        .collect();
-    let min_synonym_length = 4;
+    for root in roots {
+        for (text, tid) in get_text_to_term_map(hpo, root, filter) {
+            text_to_tid_map.entry(text).or_insert(tid);
+        }
+    }
+    text_to_tid_map
+}
+
+/// Map the descendants of the root terms to the root of their branch. Terms that descend from
+/// several roots are assigned to the root that comes first.
+pub fn get_term_to_branch_map<O>(hpo: &O, roots: &[TermId]) -> HashMap<TermId, TermId>
+where
+    O: HierarchyWalks,
+{
+    let mut term_to_branch = HashMap::new();
+    for root in roots {
+        for tid in hpo.iter_descendant_ids(root) {
+            term_to_branch.entry(tid.clone()).or_insert_with(|| root.clone());
+        }
+    }
+    term_to_branch
+}
 
+/// Map the lower-case labels and synonyms of all descendants of `root` to their term IDs
+/// (e.g., of the descendants of Onset HP:0003674).
+pub fn get_text_to_term_map<O, T>(hpo: &O, root: &TermId, filter: &LabelFilter) -> HashMap<String, TermId>
+where
+    O: OntologyTerms<T> + HierarchyWalks,
+    T: MinimalTerm + Synonymous,
+{
+    let mut text_to_tid_map = HashMap::new();
     for term in hpo
-        .iter_descendant_ids(&PHENOTYPIC_ABNORMALITY)
+        .iter_descendant_ids(root)
         .flat_map(|term_id| hpo.term_by_id(term_id))
     {
         let term_id = term.identifier();
         let term_label_lc = term.name().to_ascii_lowercase();
-        if omittable_labels.contains(&term_label_lc) || term_label_lc.len() < min_synonym_length {
+        if !filter.accepts(&term_label_lc) {
             continue;
         }
         text_to_tid_map.insert(term_label_lc, term_id.clone());
         for synonym in term.synonyms() {
-            if omittable_labels.contains(&synonym.name) || synonym.name.len() < min_synonym_length {
-                continue;
+            let synonym_lc = synonym.name.to_ascii_lowercase();
+            if filter.accepts(&synonym_lc) {
+                text_to_tid_map.insert(synonym_lc, term_id.clone());
             }
-            text_to_tid_map.insert(synonym.name.to_ascii_lowercase(), term_id.clone());
         }
     }
 
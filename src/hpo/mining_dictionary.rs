@@ -0,0 +1,439 @@
+//! MiningDictionary
+//!
+//! The labels and synonyms of the mined terms, compiled from the ontology and stored in a compact binary
+//! file. Loading the dictionary is much faster than parsing the ontology and walking its hierarchy, so a
+//! [`crate::Fenominal`] can be created from the dictionary alone (see [`crate::Fenominal::from_dictionary`]).
+//! Without the ontology, the features that need the hierarchy or other branches of the HPO are not
+//! available: onset, modifiers, organ systems and redundancy removal.
+//!
+//! The file records the version of its format, the prefix and release of the ontology and an FNV-1a
+//! checksum of its contents. Files of another format version or with a wrong checksum are rejected.
+//!
+//! ```text
+//! magic      "FENODICT"
+//! format     u32
+//! checksum   u64 (FNV-1a of the payload)
+//! payload    ontology prefix and release, roots, label filter, terms, labels and synonyms
+//! ```
+//!
+//! Numbers are little-endian; strings are stored as their length (u32) followed by their UTF-8 bytes.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use ontolius::ontology::{HierarchyWalks, MetadataAware, OntologyTerms};
+use ontolius::term::{MinimalTerm, Synonymous};
+use ontolius::TermId;
+
+use crate::builder::check_roots;
+use crate::hpo::hpo_loader::{get_term_to_branch_map, get_text_to_term_map_for_roots, LabelFilter};
+use crate::models::fenominal_model::OntologyClass;
+use crate::models::provenance::OntologyVersion;
+use crate::options::FenominalOptions;
+use crate::util::error::FenominalError;
+use crate::util::fnv::fnv1a_64;
+
+const MAGIC: &[u8; 8] = b"FENODICT";
+/// Increment if the layout of the payload changes
+pub const DICTIONARY_FORMAT_VERSION: u32 = 1;
+
+/// A minable term and the index of the root of its branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DictionaryTerm {
+    id: TermId,
+    label: String,
+    branch: usize,
+}
+
+/// The compiled labels and synonyms of the descendants of some roots of an ontology
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiningDictionary {
+    ontology: OntologyVersion,
+    /// The roots of the mined branches with their labels
+    roots: Vec<(TermId, String)>,
+    /// The label filter the dictionary was compiled with
+    omittable_labels: Vec<String>,
+    min_synonym_length: usize,
+    /// Sorted by term ID
+    terms: Vec<DictionaryTerm>,
+    /// The lower-case labels and synonyms with the index of their term, sorted by text
+    texts: Vec<(String, usize)>,
+}
+
+impl MiningDictionary {
+    /// Compile the dictionary of the roots of the options, using their label filter (see [`FenominalOptions`]).
+    /// The stop words and the other options are applied when mining and may be changed later.
+    pub fn compile<O, T>(ontology: &O, options: &FenominalOptions) -> Result<Self, FenominalError>
+    where
+        O: OntologyTerms<T> + HierarchyWalks + MetadataAware,
+        T: MinimalTerm + Synonymous,
+    {
+        options.validate()?;
+        let roots = options.root_ids()?;
+        check_roots(ontology, &roots)?;
+        let mut dictionary = Self::from_ontology(ontology, &roots, options);
+        dictionary.ontology.version = ontology.version().to_string();
+        Ok(dictionary)
+    }
+
+    /// Compile the dictionary without the release of the ontology (for mappers that keep the ontology)
+    pub(crate) fn from_ontology<O, T>(ontology: &O, roots: &[TermId], options: &FenominalOptions) -> Self
+    where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm + Synonymous,
+    {
+        let text_to_term = get_text_to_term_map_for_roots(ontology, roots, &LabelFilter::from_options(options));
+        let mut terms: Vec<DictionaryTerm> = get_term_to_branch_map(ontology, roots)
+            .into_iter()
+            .filter_map(|(id, root)| {
+                let label = ontology.term_by_id(&id)?.name().to_string();
+                let branch = roots.iter().position(|r| *r == root)?;
+                Some(DictionaryTerm { id, label, branch })
+            })
+            .collect();
+        terms.sort_by(|a, b| a.id.cmp(&b.id));
+        let term_index: HashMap<&TermId, usize> = terms.iter().enumerate().map(|(i, t)| (&t.id, i)).collect();
+        let mut texts: Vec<(String, usize)> = text_to_term
+            .iter()
+            .filter_map(|(text, tid)| Some((text.clone(), *term_index.get(tid)?)))
+            .collect();
+        texts.sort();
+        let mut omittable_labels: Vec<String> = options.omittable_label_set().into_iter().collect();
+        omittable_labels.sort();
+        Self {
+            ontology: OntologyVersion {
+                prefix: roots.first().map_or_else(String::new, |r| r.prefix().to_string()),
+                version: String::new(),
+            },
+            roots: roots
+                .iter()
+                .map(|r| {
+                    let label = ontology.term_by_id(r).map(|t| t.name().to_string()).unwrap_or_default();
+                    (r.clone(), label)
+                })
+                .collect(),
+            omittable_labels,
+            min_synonym_length: options.min_synonym_length,
+            terms,
+            texts,
+        }
+    }
+
+    /// The prefix and release of the ontology the dictionary was compiled from
+    pub fn ontology_version(&self) -> &OntologyVersion {
+        &self.ontology
+    }
+
+    /// The roots of the mined branches
+    pub fn root_ids(&self) -> Vec<TermId> {
+        self.roots.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// The lower-case labels and synonyms that were not compiled
+    pub fn omittable_labels(&self) -> &[String] {
+        &self.omittable_labels
+    }
+
+    /// Labels and synonyms shorter than this were not compiled
+    pub fn min_synonym_length(&self) -> usize {
+        self.min_synonym_length
+    }
+
+    /// The number of labels and synonyms
+    pub fn len(&self) -> usize {
+        self.texts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.texts.is_empty()
+    }
+
+    /// The checksum of the contents, e.g., `fnv1a64:9c3f...`
+    pub fn checksum(&self) -> String {
+        format!("fnv1a64:{:016x}", fnv1a_64(&self.payload()))
+    }
+
+    /// Check that the options use the roots and the label filter the dictionary was compiled with
+    pub(crate) fn check_options(&self, options: &FenominalOptions) -> Result<(), FenominalError> {
+        let differs = |option: &str| FenominalError::invalid_option(option, "differs from the precompiled dictionary");
+        if options.root_ids()? != self.root_ids() {
+            return Err(differs("roots"));
+        }
+        if options.omittable_label_set() != self.omittable_labels.iter().cloned().collect::<HashSet<_>>() {
+            return Err(differs("omittable_labels"));
+        }
+        if options.min_synonym_length != self.min_synonym_length {
+            return Err(differs("min_synonym_length"));
+        }
+        Ok(())
+    }
+
+    /// Map the lower-case labels and synonyms to their term IDs
+    pub(crate) fn text_to_term_map(&self) -> HashMap<String, TermId> {
+        self.texts
+            .iter()
+            .map(|(text, idx)| (text.clone(), self.terms[*idx].id.clone()))
+            .collect()
+    }
+
+    /// Map the terms to their labels
+    pub(crate) fn term_labels(&self) -> HashMap<TermId, String> {
+        self.terms.iter().map(|t| (t.id.clone(), t.label.clone())).collect()
+    }
+
+    /// Map the terms to the root of their branch
+    pub(crate) fn term_to_branch(&self) -> HashMap<TermId, OntologyClass> {
+        let roots: Vec<OntologyClass> = self
+            .roots
+            .iter()
+            .map(|(id, label)| OntologyClass::new(id.to_string(), label.as_str()))
+            .collect();
+        self.terms
+            .iter()
+            .map(|t| (t.id.clone(), roots[t.branch].clone()))
+            .collect()
+    }
+
+    /// Serialize the dictionary
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let payload = self.payload();
+        let mut bytes = Vec::with_capacity(payload.len() + 20);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&DICTIONARY_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&fnv1a_64(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        bytes
+    }
+
+    /// Deserialize a dictionary, checking its format version and checksum
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FenominalError> {
+        let mut decoder = Decoder { bytes };
+        if decoder.take(MAGIC.len())? != MAGIC {
+            return Err(FenominalError::invalid_dictionary("not a mining dictionary"));
+        }
+        let format = decoder.u32()?;
+        if format != DICTIONARY_FORMAT_VERSION as usize {
+            return Err(FenominalError::invalid_dictionary(format!(
+                "unsupported format version {} (expected {})",
+                format, DICTIONARY_FORMAT_VERSION
+            )));
+        }
+        let checksum = decoder.u64()?;
+        if fnv1a_64(decoder.bytes) != checksum {
+            return Err(FenominalError::invalid_dictionary("checksum mismatch"));
+        }
+        Self::decode_payload(&mut decoder)
+    }
+
+    /// Write the dictionary to a file
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> Result<(), FenominalError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Read a dictionary from a file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FenominalError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.string(&self.ontology.prefix);
+        encoder.string(&self.ontology.version);
+        encoder.len(self.roots.len());
+        for (id, label) in &self.roots {
+            encoder.string(&id.to_string());
+            encoder.string(label);
+        }
+        encoder.len(self.omittable_labels.len());
+        for label in &self.omittable_labels {
+            encoder.string(label);
+        }
+        encoder.len(self.min_synonym_length);
+        encoder.len(self.terms.len());
+        for term in &self.terms {
+            encoder.string(&term.id.to_string());
+            encoder.string(&term.label);
+            encoder.len(term.branch);
+        }
+        encoder.len(self.texts.len());
+        for (text, idx) in &self.texts {
+            encoder.string(text);
+            encoder.len(*idx);
+        }
+        encoder.bytes
+    }
+
+    fn decode_payload(decoder: &mut Decoder) -> Result<Self, FenominalError> {
+        let ontology = OntologyVersion {
+            prefix: decoder.string()?,
+            version: decoder.string()?,
+        };
+        let roots = (0..decoder.u32()?)
+            .map(|_| Ok((decoder.term_id()?, decoder.string()?)))
+            .collect::<Result<Vec<_>, FenominalError>>()?;
+        let omittable_labels = (0..decoder.u32()?)
+            .map(|_| decoder.string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let min_synonym_length = decoder.u32()?;
+        let terms = (0..decoder.u32()?)
+            .map(|_| {
+                let term = DictionaryTerm {
+                    id: decoder.term_id()?,
+                    label: decoder.string()?,
+                    branch: decoder.u32()?,
+                };
+                if term.branch < roots.len() {
+                    Ok(term)
+                } else {
+                    Err(FenominalError::invalid_dictionary(format!("invalid branch of {}", term.id)))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let texts = (0..decoder.u32()?)
+            .map(|_| {
+                let text = decoder.string()?;
+                match decoder.u32()? {
+                    idx if idx < terms.len() => Ok((text, idx)),
+                    _ => Err(FenominalError::invalid_dictionary(format!("invalid term of '{}'", text))),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !decoder.bytes.is_empty() {
+            return Err(FenominalError::invalid_dictionary("trailing bytes"));
+        }
+        Ok(Self {
+            ontology,
+            roots,
+            omittable_labels,
+            min_synonym_length,
+            terms,
+            texts,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn len(&mut self, n: usize) {
+        self.bytes.extend_from_slice(&(n as u32).to_le_bytes());
+    }
+
+    fn string(&mut self, s: &str) {
+        self.len(s.len());
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], FenominalError> {
+        if self.bytes.len() < n {
+            return Err(FenominalError::invalid_dictionary("unexpected end of file"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<usize, FenominalError> {
+        let bytes = self.take(4)?.try_into().expect("4 bytes");
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn u64(&mut self) -> Result<u64, FenominalError> {
+        let bytes = self.take(8)?.try_into().expect("8 bytes");
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn string(&mut self) -> Result<String, FenominalError> {
+        let n = self.u32()?;
+        String::from_utf8(self.take(n)?.to_vec()).map_err(|_| FenominalError::invalid_dictionary("invalid UTF-8"))
+    }
+
+    fn term_id(&mut self) -> Result<TermId, FenominalError> {
+        let id = self.string()?;
+        id.parse()
+            .map_err(|_| FenominalError::invalid_dictionary(format!("'{}' is not a term ID", id)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    #[fixture]
+    fn dictionary() -> MiningDictionary {
+        let seizure: TermId = "HP:0001250".parse().unwrap();
+        let scoliosis: TermId = "HP:0002650".parse().unwrap();
+        MiningDictionary {
+            ontology: OntologyVersion {
+                prefix: "HP".to_string(),
+                version: "2025-03-03".to_string(),
+            },
+            roots: vec![("HP:0000118".parse().unwrap(), "Phenotypic abnormality".to_string())],
+            omittable_labels: vec!["negative".to_string(), "weakness".to_string()],
+            min_synonym_length: 4,
+            terms: vec![
+                DictionaryTerm { id: seizure, label: "Seizure".to_string(), branch: 0 },
+                DictionaryTerm { id: scoliosis, label: "Scoliosis".to_string(), branch: 0 },
+            ],
+            texts: vec![
+                ("epileptic seizure".to_string(), 0),
+                ("scoliosis".to_string(), 1),
+                ("seizure".to_string(), 0),
+            ],
+        }
+    }
+
+    #[rstest]
+    fn test_roundtrip(dictionary: MiningDictionary) {
+        let bytes = dictionary.to_bytes();
+        assert_eq!(MAGIC, &bytes[..8]);
+        let loaded = MiningDictionary::from_bytes(&bytes).unwrap();
+        assert_eq!(dictionary, loaded);
+        assert_eq!(dictionary.checksum(), loaded.checksum());
+        assert_eq!(3, loaded.len());
+        let text_to_term = loaded.text_to_term_map();
+        assert_eq!("HP:0001250", text_to_term["epileptic seizure"].to_string());
+        let branch = &loaded.term_to_branch()[&"HP:0002650".parse::<TermId>().unwrap()];
+        assert_eq!("Phenotypic abnormality", branch.label);
+    }
+
+    #[rstest]
+    #[case::magic(0, "not a mining dictionary")]
+    #[case::format_version(8, "unsupported format version")]
+    #[case::checksum(12, "checksum mismatch")]
+    #[case::payload(40, "checksum mismatch")]
+    fn test_corrupted(dictionary: MiningDictionary, #[case] position: usize, #[case] expected: &str) {
+        let mut bytes = dictionary.to_bytes();
+        bytes[position] ^= 0xff;
+        let error = MiningDictionary::from_bytes(&bytes).unwrap_err().to_string();
+        assert!(error.contains(expected), "{}", error);
+    }
+
+    #[rstest]
+    fn test_truncated(dictionary: MiningDictionary) {
+        let bytes = dictionary.to_bytes();
+        assert!(MiningDictionary::from_bytes(&bytes[..10]).is_err());
+        assert!(MiningDictionary::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[rstest]
+    fn test_check_options(dictionary: MiningDictionary) {
+        assert!(dictionary.check_options(&FenominalOptions::default()).is_ok());
+        let options = FenominalOptions { min_synonym_length: 3, ..Default::default() };
+        assert!(dictionary.check_options(&options).is_err());
+        let options = FenominalOptions { roots: vec!["HP:0000005".to_string()], ..Default::default() };
+        assert!(dictionary.check_options(&options).is_err());
+    }
+}
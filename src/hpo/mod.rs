@@ -1,8 +1,17 @@
-pub mod default_hpo_mapper;
-pub mod hpo_concept;
-pub mod hpo_concept_mapper;
+pub mod branches;
+pub mod concept;
+pub mod concept_mapper;
+pub mod default_term_mapper;
 pub mod hpo_loader;
+pub mod mining_dictionary;
+pub mod modifiers;
+pub mod normal_findings;
+pub mod obsolete;
+pub mod onset;
+pub mod organ_systems;
+#[allow(dead_code)]
 mod partition;
+pub mod redundancy;
 pub mod sentence_mapper;
 pub mod text_to_annotation;
 
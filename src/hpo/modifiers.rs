@@ -0,0 +1,248 @@
+//! ModifierExtractor
+//!
+//! Clinicians qualify findings with their severity ("severe intellectual disability"),
+//! laterality ("left-sided ptosis") and frequency ("occasional seizures"). The modifiers are taken from
+//! the subtrees Severity HP:0012824 and Laterality HP:0012831 of Clinical modifier HP:0012823, from
+//! Frequency HP:0040279, and from the phrases in `data/modifier_phrases.tsv` ("bilaterally", "on the left").
+//!
+//! A modifier qualifies the hit that directly follows it (an adjective, possibly after other modifiers),
+//! or else the closest hit within a few tokens ("ptosis on the left", "seizures occur occasionally").
+//! If the HPO has a pre-coordinated term for the modified finding (e.g., Bilateral ptosis HP:0001488 for
+//! "ptosis, bilateral"), the hit is replaced by the more specific term.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use ontolius::{
+    ontology::{HierarchyWalks, OntologyTerms},
+    term::{MinimalTerm, Synonymous},
+    TermId,
+};
+
+use crate::hpo::branches::FREQUENCY;
+use crate::hpo::hpo_loader::{get_text_to_term_map, LabelFilter};
+use crate::models::fenominal_model::{FenominalHit, Modifier, OntologyClass};
+use crate::simple_sentence::SimpleSentence;
+use crate::util::text_util::normalize_phrase;
+
+/// Severity HP:0012824
+pub static SEVERITY: Lazy<TermId> = Lazy::new(|| "HP:0012824".parse().unwrap());
+/// Laterality HP:0012831
+pub static LATERALITY: Lazy<TermId> = Lazy::new(|| "HP:0012831".parse().unwrap());
+/// Excluded HP:0040285 (a frequency of 0%) is expressed by negation, not by a modifier
+static EXCLUDED_FREQUENCY: Lazy<TermId> = Lazy::new(|| "HP:0040285".parse().unwrap());
+
+/// Maximum number of tokens between a modifier and the hit that follows it ("occasionally has seizures")
+const MAX_FORWARD_GAP: usize = 2;
+/// Maximum number of tokens between a hit and a modifier that follows it ("seizures that occur occasionally")
+const MAX_BACKWARD_GAP: usize = 3;
+
+pub struct ModifierExtractor {
+    /// Lower-case modifier phrases (tokens separated by a single space)
+    phrases: HashMap<String, OntologyClass>,
+    max_phrase_token_count: usize,
+    /// (modifier, finding) -> pre-coordinated term, e.g., (Bilateral, Ptosis) -> Bilateral ptosis
+    precoordinated: HashMap<(String, String), OntologyClass>,
+}
+
+impl ModifierExtractor {
+    /// Create the extractor from the HPO and the label/synonym map of the Phenotypic abnormality subontology
+    pub fn new<O, T>(hpo: &O, text_to_term: &HashMap<String, TermId>) -> Self
+    where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm + Synonymous,
+    {
+        let mut phrases = HashMap::new();
+        for root in [&*SEVERITY, &*LATERALITY, &*FREQUENCY] {
+            for (text, tid) in get_text_to_term_map(hpo, root, &LabelFilter::default()) {
+                // "Very rare (<4-1%)" is not written in clinical text
+                if tid == *EXCLUDED_FREQUENCY || text.chars().any(|c| c.is_ascii_digit()) {
+                    continue;
+                }
+                add_phrase(&mut phrases, hpo, &text, &tid);
+            }
+        }
+        let data = include_str!("../../data/modifier_phrases.tsv");
+        for line in data.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 2 {
+                continue;
+            }
+            match parts[1].parse::<TermId>() {
+                Ok(tid) if is_modifier(hpo, &tid) => add_phrase(&mut phrases, hpo, parts[0], &tid),
+                _ => eprintln!("Skipping {} in modifier phrases: not a modifier term", parts[1]),
+            }
+        }
+        let max_phrase_token_count = phrases.keys().map(|k| k.split(' ').count()).max().unwrap_or(0);
+        let precoordinated = find_precoordinated_terms(hpo, text_to_term, &phrases);
+        Self {
+            phrases,
+            max_phrase_token_count,
+            precoordinated,
+        }
+    }
+
+    /// Find modifiers in the sentence and attach them to the hits they qualify. Hits for which
+    /// the HPO has a pre-coordinated term with one of their modifiers are replaced by that term.
+    pub fn annotate(&self, sentence: &SimpleSentence, hits: &mut [FenominalHit]) {
+        if hits.is_empty() {
+            return;
+        }
+        let offset = sentence.get_start_pos();
+        let tokens = sentence.get_tokens();
+        // index of the hit that covers each token
+        let token_hit: Vec<Option<usize>> = tokens
+            .iter()
+            .map(|t| hits.iter().position(|h| h.span.contains(&(offset + t.get_start_pos()))))
+            .collect();
+        let found = self.find(sentence, &token_hit);
+        let is_modifier_token = |i: usize| found.iter().any(|(range, _)| range.contains(&i));
+        for (range, term) in &found {
+            // an adjective: skip other modifiers and look for the next hit
+            let mut forward = None;
+            let mut gap = 0;
+            for (i, hit_idx) in token_hit.iter().enumerate().skip(range.end) {
+                if let Some(h) = hit_idx {
+                    forward = Some((*h, gap));
+                    break;
+                }
+                if !is_modifier_token(i) {
+                    gap += 1;
+                    if gap > MAX_FORWARD_GAP {
+                        break;
+                    }
+                }
+            }
+            let mut backward = None;
+            let mut gap = 0;
+            for i in (0..range.start).rev() {
+                if let Some(h) = token_hit[i] {
+                    backward = Some((h, gap));
+                    break;
+                }
+                if !is_modifier_token(i) {
+                    gap += 1;
+                    if gap > MAX_BACKWARD_GAP {
+                        break;
+                    }
+                }
+            }
+            let target = match (forward, backward) {
+                (Some((h, 0)), _) => Some(h),
+                (Some((f, fgap)), Some((b, bgap))) => Some(if fgap < bgap { f } else { b }),
+                (Some((h, _)), None) | (None, Some((h, _))) => Some(h),
+                (None, None) => None,
+            };
+            if let Some(h) = target {
+                let span = offset + tokens[range.start].get_start_pos()..offset + tokens[range.end - 1].get_end_pos();
+                hits[h].modifiers.push(Modifier {
+                    term: term.clone(),
+                    span,
+                });
+            }
+        }
+        for hit in hits.iter_mut() {
+            self.precoordinate(hit);
+        }
+    }
+
+    /// Replace the hit by a pre-coordinated term (e.g., Ptosis + Bilateral -> Bilateral ptosis)
+    fn precoordinate(&self, hit: &mut FenominalHit) {
+        let Some(idx) = hit
+            .modifiers
+            .iter()
+            .position(|m| self.precoordinated.contains_key(&(m.term.id.clone(), hit.term_id.clone())))
+        else {
+            return;
+        };
+        let modifier = hit.modifiers.remove(idx);
+        let term = &self.precoordinated[&(modifier.term.id, hit.term_id.clone())];
+        hit.term_id = term.id.clone();
+        hit.label = term.label.clone();
+        hit.span = hit.span.start.min(modifier.span.start)..hit.span.end.max(modifier.span.end);
+    }
+
+    /// Modifier phrases (token ranges) that are not part of a hit
+    fn find(&self, sentence: &SimpleSentence, token_hit: &[Option<usize>]) -> Vec<(Range<usize>, OntologyClass)> {
+        let tokens = sentence.get_tokens();
+        let mut found = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let mut matched = false;
+            for n in (1..=self.max_phrase_token_count.min(tokens.len() - i)).rev() {
+                if token_hit[i..i + n].iter().any(Option::is_some) {
+                    continue;
+                }
+                let chunk: Vec<&str> = tokens[i..i + n].iter().map(|t| t.get_lc_original_token()).collect();
+                if let Some(term) = self.phrases.get(&chunk.join(" ")) {
+                    found.push((i..i + n, term.clone()));
+                    i += n;
+                    matched = true;
+                    break;
+                }
+            }
+            if !matched {
+                i += 1;
+            }
+        }
+        found
+    }
+}
+
+fn is_modifier<O>(hpo: &O, tid: &TermId) -> bool
+where
+    O: HierarchyWalks,
+{
+    hpo.iter_ancestor_ids(tid)
+        .any(|a| *a == *SEVERITY || *a == *LATERALITY || *a == *FREQUENCY)
+}
+
+fn add_phrase<O, T>(phrases: &mut HashMap<String, OntologyClass>, hpo: &O, text: &str, tid: &TermId)
+where
+    O: OntologyTerms<T>,
+    T: MinimalTerm,
+{
+    let Some(term) = hpo.term_by_id(tid) else {
+        return;
+    };
+    let key = normalize_phrase(text);
+    if !key.is_empty() {
+        phrases.insert(key, OntologyClass::new(term.identifier().to_string(), term.name()));
+    }
+}
+
+/// Find labels and synonyms of the form "{modifier} {finding}" or "{finding}, {modifier}"
+/// (e.g., "bilateral ptosis", "intellectual disability, severe") that denote a subclass of the finding.
+fn find_precoordinated_terms<O, T>(
+    hpo: &O,
+    text_to_term: &HashMap<String, TermId>,
+    phrases: &HashMap<String, OntologyClass>,
+) -> HashMap<(String, String), OntologyClass>
+where
+    O: OntologyTerms<T> + HierarchyWalks,
+    T: MinimalTerm,
+{
+    let mut precoordinated = HashMap::new();
+    for (text, tid) in text_to_term {
+        for (phrase, modifier) in phrases {
+            let finding = text
+                .strip_prefix(phrase.as_str())
+                .and_then(|rest| rest.strip_prefix(' '))
+                .or_else(|| text.strip_suffix(phrase.as_str()).and_then(|rest| rest.strip_suffix(", ")));
+            let Some(finding_tid) = finding.and_then(|f| text_to_term.get(f)) else {
+                continue;
+            };
+            if !hpo.iter_ancestor_ids(tid).any(|a| a == finding_tid) {
+                continue;
+            }
+            if let Some(term) = hpo.term_by_id(tid) {
+                precoordinated.insert(
+                    (modifier.id.clone(), finding_tid.to_string()),
+                    OntologyClass::new(tid.to_string(), term.name()),
+                );
+            }
+        }
+    }
+    precoordinated
+}
@@ -0,0 +1,230 @@
+//! NormalFindingRules
+//!
+//! Clinical notes frequently report normal examination results, e.g., "normal hearing",
+//! "hearing was normal", or "echocardiogram normal". None of these phrases correspond to an HPO label,
+//! but each of them excludes an HPO abnormality (Hearing abnormality HP:0000364, Abnormal heart
+//! morphology HP:0001627, ...).
+//!
+//! The phrase that is qualified as normal is resolved in two steps:
+//! 1. An explicit table (`data/normal_findings.tsv`) for phrases whose abnormality cannot be derived
+//!    from the phrase itself (e.g., "head circumference" -> Abnormality of skull size).
+//! 2. Label templates such as "abnormal {phrase}" or "abnormality of the {phrase}" that are looked up
+//!    among the labels and synonyms of the Phenotypic abnormality subontology.
+//!
+//! Entries of the explicit table are only used if the HPO hierarchy confirms that they denote
+//! a phenotypic abnormality.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use ontolius::{
+    common::hpo::PHENOTYPIC_ABNORMALITY,
+    ontology::{HierarchyWalks, OntologyTerms},
+    term::MinimalTerm,
+    TermId,
+};
+
+/// Maximum number of tokens of the phrase that is qualified as normal, e.g., "brain mri"
+const MAX_PHRASE_TOKEN_COUNT: usize = 3;
+
+/// Templates to derive the label of the abnormality from the phrase qualified as normal
+const LABEL_TEMPLATES: [&str; 6] = [
+    "abnormal {}",
+    "{} abnormality",
+    "abnormality of {}",
+    "abnormality of the {}",
+    "abnormal {} morphology",
+    "abnormal {} physiology",
+];
+
+/// Verbs that may separate the phrase from a trailing "normal", e.g., "hearing was normal"
+const COPULAS: [&str; 8] = ["is", "was", "are", "were", "appeared", "appears", "remained", "seemed"];
+
+/// Words that reverse the meaning of "normal", e.g., "not normal"
+const REVERSALS: [&str; 3] = ["not", "never", "nor"];
+
+pub struct NormalFindingRules {
+    explicit_phrases: HashMap<String, TermId>,
+    /// Phrases that fill one of the [`LABEL_TEMPLATES`] to form a label or synonym, e.g., "hearing" for "abnormal hearing"
+    template_phrases: HashMap<String, TermId>,
+}
+
+impl NormalFindingRules {
+    /// Create the rules from the HPO and the label/synonym map of the Phenotypic abnormality subontology
+    pub fn new<O, T>(hpo: &O, text_to_term: &HashMap<String, TermId>) -> Self
+    where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm,
+    {
+        // Use the primary id (in case the table lists an alt_id) and check that it is a phenotypic abnormality
+        Self::with_resolver(text_to_term, |term_id| match hpo.term_by_id(term_id) {
+            Some(term) if hpo.iter_ancestor_ids(term.identifier()).any(|a| *a == PHENOTYPIC_ABNORMALITY) => {
+                Some(term.identifier().clone())
+            }
+            _ => None,
+        })
+    }
+
+    /// Create the rules without the HPO. `resolve` maps the term IDs of the table of normal findings
+    /// to the IDs of phenotypic abnormalities (or `None` if the term is not a phenotypic abnormality).
+    pub fn with_resolver(text_to_term: &HashMap<String, TermId>, resolve: impl Fn(&TermId) -> Option<TermId>) -> Self {
+        let data = include_str!("../../data/normal_findings.tsv");
+        let mut explicit_phrases = HashMap::new();
+        for line in data.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 2 {
+                continue;
+            }
+            let Ok(term_id) = parts[1].parse::<TermId>() else {
+                eprintln!("Could not parse term id {} in normal findings", parts[1]);
+                continue;
+            };
+            match resolve(&term_id) {
+                Some(tid) => {
+                    explicit_phrases.insert(parts[0].to_lowercase(), tid);
+                }
+                None => eprintln!("Skipping {} in normal findings: not a phenotypic abnormality", term_id),
+            }
+        }
+        Self {
+            explicit_phrases,
+            template_phrases: template_phrases(text_to_term),
+        }
+    }
+
+    /// Get the term ID of the abnormality that is excluded if `phrase` is said to be normal
+    pub fn lookup(&self, phrase: &str) -> Option<&TermId> {
+        self.explicit_phrases
+            .get(phrase)
+            .or_else(|| self.template_phrases.get(phrase))
+    }
+
+    /// Find "normal X" and "X (was) normal" expressions in a sentence.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The lower-case tokens of the sentence
+    /// * `token_used` - Tokens that are already part of another hit and may not be used again
+    ///
+    /// # Returns
+    ///
+    /// The token range of each expression (including the word "normal") with the term ID of the excluded abnormality
+    pub fn find(&self, tokens: &[&str], token_used: &[bool]) -> Vec<(Range<usize>, TermId)> {
+        let mut findings = Vec::new();
+        for (i, &word) in tokens.iter().enumerate() {
+            if word != "normal" || token_used[i] {
+                continue;
+            }
+            if i > 0 && REVERSALS.contains(&tokens[i - 1]) {
+                continue;
+            }
+            if let Some(finding) = self.find_after(tokens, token_used, i).or_else(|| self.find_before(tokens, token_used, i)) {
+                findings.push(finding);
+            }
+        }
+        findings
+    }
+
+    /// "normal hearing"
+    fn find_after(&self, tokens: &[&str], token_used: &[bool], normal_idx: usize) -> Option<(Range<usize>, TermId)> {
+        let start = normal_idx + 1;
+        for n in (1..=MAX_PHRASE_TOKEN_COUNT).rev() {
+            let end = start + n;
+            if end > tokens.len() || token_used[start..end].iter().any(|&used| used) {
+                continue;
+            }
+            if let Some(tid) = self.lookup(&tokens[start..end].join(" ")) {
+                return Some((normal_idx..end, tid.clone()));
+            }
+        }
+        None
+    }
+
+    /// "hearing was normal", "echocardiogram normal", "head circumference within normal limits"
+    fn find_before(&self, tokens: &[&str], token_used: &[bool], normal_idx: usize) -> Option<(Range<usize>, TermId)> {
+        let mut end = normal_idx;
+        let mut last = normal_idx + 1;
+        if end > 0 && tokens[end - 1] == "within" && tokens.get(last) == Some(&"limits") {
+            end -= 1;
+            last += 1;
+        }
+        while end > 0 && COPULAS.contains(&tokens[end - 1]) {
+            end -= 1;
+        }
+        for n in (1..=MAX_PHRASE_TOKEN_COUNT).rev() {
+            if n > end {
+                continue;
+            }
+            let start = end - n;
+            if token_used[start..end].iter().any(|&used| used) {
+                continue;
+            }
+            if let Some(tid) = self.lookup(&tokens[start..end].join(" ")) {
+                return Some((start..last, tid.clone()));
+            }
+        }
+        None
+    }
+}
+
+/// Extract the phrases that fill a label template from the labels and synonyms. If a phrase fills several
+/// templates, the term of the first template wins.
+fn template_phrases(text_to_term: &HashMap<String, TermId>) -> HashMap<String, TermId> {
+    let mut phrases: HashMap<String, (usize, TermId)> = HashMap::new();
+    for (text, tid) in text_to_term {
+        for (rank, template) in LABEL_TEMPLATES.iter().enumerate() {
+            let (prefix, suffix) = template.split_once("{}").unwrap();
+            let Some(phrase) = text.strip_prefix(prefix).and_then(|t| t.strip_suffix(suffix)) else {
+                continue;
+            };
+            if phrase.is_empty() || phrase.split(' ').count() > MAX_PHRASE_TOKEN_COUNT {
+                continue;
+            }
+            match phrases.get(phrase) {
+                Some((r, _)) if *r <= rank => {}
+                _ => {
+                    phrases.insert(phrase.to_string(), (rank, tid.clone()));
+                }
+            }
+        }
+    }
+    phrases.into_iter().map(|(phrase, (_, tid))| (phrase, tid)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    #[fixture]
+    fn rules() -> NormalFindingRules {
+        let mut text_to_term = HashMap::new();
+        text_to_term.insert("abnormal hearing".to_string(), TermId::from_str("HP:0000364").unwrap());
+        text_to_term.insert("abnormal heart morphology".to_string(), TermId::from_str("HP:0001627").unwrap());
+        let mut explicit_phrases = HashMap::new();
+        explicit_phrases.insert("head circumference".to_string(), TermId::from_str("HP:0000240").unwrap());
+        NormalFindingRules { explicit_phrases, template_phrases: template_phrases(&text_to_term) }
+    }
+
+    #[rstest]
+    #[case("normal hearing", Some((0..2, "HP:0000364")))]
+    #[case("hearing was normal", Some((0..3, "HP:0000364")))]
+    #[case("his head circumference is within normal limits", Some((1..7, "HP:0000240")))]
+    #[case("the heart was normal", Some((1..4, "HP:0001627")))]
+    #[case("normal heart", Some((0..2, "HP:0001627")))]
+    #[case("hearing was not normal", None)]
+    #[case("normal saline", None)]
+    fn test_find(rules: NormalFindingRules, #[case] sentence: &str, #[case] expected: Option<(Range<usize>, &str)>) {
+        let tokens: Vec<&str> = sentence.split_whitespace().collect();
+        let token_used = vec![false; tokens.len()];
+        let findings = rules.find(&tokens, &token_used);
+        let expected: Vec<(Range<usize>, TermId)> = expected
+            .into_iter()
+            .map(|(range, tid)| (range, TermId::from_str(tid).unwrap()))
+            .collect();
+        assert_eq!(expected, findings);
+    }
+}
@@ -0,0 +1,320 @@
+//! TermIdResolver
+//!
+//! Annotations that were stored with an older HPO release may use IDs that are no longer primary:
+//! alternative IDs of merged terms (`alt_id`) and IDs of obsolete terms, which usually point to
+//! their replacement (`replaced_by`, IAO:0100001) or to terms to consider instead.
+//!
+//! Ontolius resolves alternative IDs, but drops obsolete terms when loading an ontology. The resolver
+//! therefore reads the obsolete terms directly from the OBO Graphs JSON file
+//! (see [`TermIdResolver::from_obographs`]).
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::io::Read;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use ontolius::ontology::OntologyTerms;
+use ontolius::term::MinimalTerm;
+
+use crate::models::fenominal_model::{FenominalHit, OntologyClass};
+#[cfg(feature = "serde")]
+use crate::util::error::FenominalError;
+
+/// replaced_by
+#[cfg(feature = "serde")]
+const REPLACED_BY: &str = "http://purl.obolibrary.org/obo/IAO_0100001";
+#[cfg(feature = "serde")]
+const CONSIDER: &str = "http://www.geneontology.org/formats/oboInOwl#consider";
+#[cfg(feature = "serde")]
+const ALTERNATIVE_ID: &str = "http://www.geneontology.org/formats/oboInOwl#hasAlternativeId";
+/// Maximum length of a chain of replacements (an obsolete term replaced by an obsolete term ...)
+const MAX_REPLACEMENTS: usize = 10;
+
+/// The status of a term ID in the current release of the ontology
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum TermIdStatus {
+    /// The primary ID of a current term
+    Current,
+    /// An alternative ID of a current term, e.g., HP:0001275 for Seizure HP:0001250
+    AlternativeId { primary: String },
+    /// The ID of an obsolete term, with its replacement (if any) and terms to consider instead
+    Obsolete {
+        replaced_by: Option<String>,
+        consider: Vec<String>,
+    },
+    /// The ID is not part of the ontology
+    Unknown,
+}
+
+/// A term ID that is not the primary ID of a current term
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutdatedTermId {
+    pub id: String,
+    pub status: TermIdStatus,
+    /// The current term that replaces the ID, if there is one
+    pub current: Option<OntologyClass>,
+}
+
+impl fmt::Display for OutdatedTermId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match &self.status {
+            TermIdStatus::Current => "current",
+            TermIdStatus::AlternativeId { .. } => "alternative ID",
+            TermIdStatus::Obsolete { .. } => "obsolete",
+            TermIdStatus::Unknown => "unknown",
+        };
+        match &self.current {
+            Some(term) => write!(f, "{} ({}) -> {}", self.id, status, term),
+            None => write!(f, "{} ({})", self.id, status),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ObsoleteTerm {
+    replaced_by: Option<String>,
+    consider: Vec<String>,
+}
+
+/// Resolves alternative and obsolete term IDs to current terms.
+#[derive(Debug, Clone, Default)]
+pub struct TermIdResolver {
+    /// Primary ID of the current terms -> label
+    labels: HashMap<String, String>,
+    /// Alternative ID -> primary ID
+    alt_ids: HashMap<String, String>,
+    obsolete: HashMap<String, ObsoleteTerm>,
+}
+
+impl TermIdResolver {
+    /// Create a resolver for the current terms and alternative IDs of an ontology. Obsolete terms are not
+    /// available from ontolius; use [`TermIdResolver::from_obographs`] to resolve them.
+    pub fn from_ontology<O, T>(ontology: &O) -> Self
+    where
+        O: OntologyTerms<T>,
+        T: MinimalTerm,
+    {
+        let mut resolver = Self::default();
+        for term in ontology.iter_terms() {
+            let id = term.identifier().to_string();
+            for alt_id in term.iter_alt_term_ids() {
+                resolver.alt_ids.insert(alt_id.to_string(), id.clone());
+            }
+            resolver.labels.insert(id, term.name().to_string());
+        }
+        resolver
+    }
+
+    /// Read the current terms, alternative IDs and obsolete terms from an OBO Graphs JSON file (e.g., `hp.json`).
+    #[cfg(feature = "serde")]
+    pub fn from_obographs<R: Read>(reader: R) -> Result<Self, FenominalError> {
+        let document: GraphDocument = serde_json::from_reader(reader).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not read OBO Graphs JSON: {}", e),
+        })?;
+        let mut resolver = Self::default();
+        for node in document.graphs.into_iter().flat_map(|g| g.nodes) {
+            if node.node_type.as_deref().is_some_and(|t| t != "CLASS") {
+                continue;
+            }
+            let (Some(id), Some(label)) = (iri_to_curie(&node.id), node.lbl) else {
+                continue;
+            };
+            let meta = node.meta.unwrap_or_default();
+            let values = |pred: &str| -> Vec<String> {
+                meta.basic_property_values
+                    .iter()
+                    .filter(|v| v.pred == pred)
+                    .filter_map(|v| iri_to_curie(&v.val))
+                    .collect()
+            };
+            if meta.deprecated {
+                let obsolete = ObsoleteTerm {
+                    replaced_by: values(REPLACED_BY).into_iter().next(),
+                    consider: values(CONSIDER),
+                };
+                resolver.obsolete.insert(id, obsolete);
+            } else {
+                for alt_id in values(ALTERNATIVE_ID) {
+                    resolver.alt_ids.insert(alt_id, id.clone());
+                }
+                resolver.labels.insert(id, label);
+            }
+        }
+        Ok(resolver)
+    }
+
+    /// The status of a term ID such as `HP:0001250`. The ID of an obsolete term that was merged into
+    /// another term is usually also an alternative ID of that term; it is reported as obsolete.
+    pub fn status(&self, id: &str) -> TermIdStatus {
+        if self.labels.contains_key(id) {
+            TermIdStatus::Current
+        } else if let Some(obsolete) = self.obsolete.get(id) {
+            TermIdStatus::Obsolete {
+                replaced_by: obsolete.replaced_by.clone(),
+                consider: obsolete.consider.clone(),
+            }
+        } else if let Some(primary) = self.alt_ids.get(id) {
+            TermIdStatus::AlternativeId { primary: primary.clone() }
+        } else {
+            TermIdStatus::Unknown
+        }
+    }
+
+    /// The current term of an ID, following alternative IDs and `replaced_by` links.
+    /// Obsolete terms without replacement are not resolved (their `consider` terms are only suggestions).
+    pub fn resolve(&self, id: &str) -> Option<OntologyClass> {
+        let mut id = id;
+        for _ in 0..MAX_REPLACEMENTS {
+            if let Some(label) = self.labels.get(id) {
+                return Some(OntologyClass::new(id, label.as_str()));
+            }
+            id = match self.alt_ids.get(id) {
+                Some(primary) => primary,
+                None => self.obsolete.get(id)?.replaced_by.as_ref()?,
+            };
+        }
+        None
+    }
+
+    /// Report the IDs that are not primary IDs of current terms, e.g., of a stored annotation set.
+    /// Each outdated ID is reported once.
+    pub fn find_outdated_ids<'a>(&self, ids: impl IntoIterator<Item = &'a str>) -> Vec<OutdatedTermId> {
+        let mut seen = HashSet::new();
+        ids.into_iter()
+            .filter(|id| seen.insert(*id))
+            .filter_map(|id| match self.status(id) {
+                TermIdStatus::Current => None,
+                status => Some(OutdatedTermId {
+                    id: id.to_string(),
+                    status,
+                    current: self.resolve(id),
+                }),
+            })
+            .collect()
+    }
+
+    /// Replace outdated term IDs (and their labels) of the hits by the current terms.
+    /// Returns the outdated IDs, including those that could not be replaced.
+    pub fn remap_hits(&self, hits: &mut [FenominalHit]) -> Vec<OutdatedTermId> {
+        let outdated = self.find_outdated_ids(hits.iter().map(|h| h.term_id.as_str()).collect::<Vec<_>>());
+        for hit in hits.iter_mut() {
+            let current = outdated
+                .iter()
+                .find(|o| o.id == hit.term_id)
+                .and_then(|o| o.current.as_ref());
+            if let Some(term) = current {
+                hit.term_id = term.id.clone();
+                hit.label = term.label.clone();
+            }
+        }
+        outdated
+    }
+}
+
+/// `http://purl.obolibrary.org/obo/HP_0001250` -> `HP:0001250`; CURIEs are returned as they are.
+fn iri_to_curie(iri: &str) -> Option<String> {
+    let local = iri.rsplit(['/', '#']).next()?;
+    if local.contains(':') {
+        return Some(local.to_string());
+    }
+    let (prefix, id) = local.split_once('_')?;
+    let valid = !prefix.is_empty() && !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+    valid.then(|| format!("{}:{}", prefix, id))
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GraphDocument {
+    graphs: Vec<Graph>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct Graph {
+    #[serde(default)]
+    nodes: Vec<Node>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct Node {
+    id: String,
+    lbl: Option<String>,
+    #[serde(rename = "type")]
+    node_type: Option<String>,
+    meta: Option<Meta>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize, Default)]
+struct Meta {
+    #[serde(default)]
+    deprecated: bool,
+    #[serde(default, rename = "basicPropertyValues")]
+    basic_property_values: Vec<PropertyValue>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct PropertyValue {
+    pred: String,
+    val: String,
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("http://purl.obolibrary.org/obo/HP_0001250", Some("HP:0001250"))]
+    #[case("HP:0008665", Some("HP:0008665"))]
+    #[case("http://purl.obolibrary.org/obo/hp#obsolete_synonym", None)]
+    fn test_iri_to_curie(#[case] iri: &str, #[case] expected: Option<&str>) {
+        assert_eq!(expected.map(String::from), iri_to_curie(iri));
+    }
+
+    fn resolver() -> TermIdResolver {
+        let json = r#"{"graphs":[{"nodes":[
+            {"id":"http://purl.obolibrary.org/obo/HP_0001250","lbl":"Seizure","type":"CLASS",
+             "meta":{"basicPropertyValues":[{"pred":"http://www.geneontology.org/formats/oboInOwl#hasAlternativeId","val":"HP:0001275"}]}},
+            {"id":"http://purl.obolibrary.org/obo/HP_0000001","lbl":"obsolete A","type":"CLASS",
+             "meta":{"deprecated":true,"basicPropertyValues":[{"pred":"http://purl.obolibrary.org/obo/IAO_0100001","val":"HP:0000002"}]}},
+            {"id":"http://purl.obolibrary.org/obo/HP_0000002","lbl":"obsolete B","type":"CLASS",
+             "meta":{"deprecated":true,"basicPropertyValues":[{"pred":"http://purl.obolibrary.org/obo/IAO_0100001","val":"HP:0001275"}]}},
+            {"id":"http://purl.obolibrary.org/obo/HP_0000003","lbl":"obsolete C","type":"CLASS",
+             "meta":{"deprecated":true,"basicPropertyValues":[{"pred":"http://www.geneontology.org/formats/oboInOwl#consider","val":"HP:0001250"}]}}
+        ]}]}"#;
+        TermIdResolver::from_obographs(json.as_bytes()).unwrap()
+    }
+
+    #[rstest]
+    #[case("HP:0001250", Some("HP:0001250"))]
+    #[case("HP:0001275", Some("HP:0001250"))]
+    #[case("HP:0000001", Some("HP:0001250"))]
+    #[case("HP:0000003", None)]
+    #[case("HP:9999999", None)]
+    fn test_resolve(#[case] id: &str, #[case] expected: Option<&str>) {
+        assert_eq!(expected, resolver().resolve(id).as_ref().map(|t| t.id.as_str()));
+    }
+
+    #[test]
+    fn test_status() {
+        let resolver = resolver();
+        assert_eq!(TermIdStatus::Current, resolver.status("HP:0001250"));
+        assert_eq!(
+            TermIdStatus::Obsolete {
+                replaced_by: None,
+                consider: vec!["HP:0001250".to_string()]
+            },
+            resolver.status("HP:0000003")
+        );
+        assert_eq!(TermIdStatus::Unknown, resolver.status("HP:9999999"));
+    }
+}
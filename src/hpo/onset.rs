@@ -0,0 +1,259 @@
+//! OnsetExtractor
+//!
+//! Finds age-of-onset expressions in a sentence and attaches them to the nearby hits.
+//! Two kinds of expression are recognized:
+//! 1. Ages such as "since age 3 years" or "at 5 months of age", which are normalized to
+//!    ISO 8601 durations (`P3Y`, `P5M`).
+//! 2. Onset terms, i.e., the labels and synonyms of the descendants of Onset HP:0003674
+//!    ("onset in infancy"), their adjectival forms ("neonatal hypotonia", "congenital heart defect")
+//!    and the phrases in `data/onset_phrases.tsv` ("since birth", "in adolescence").
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use ontolius::{
+    ontology::{HierarchyWalks, OntologyTerms},
+    term::{MinimalTerm, Synonymous},
+    TermId,
+};
+use regex::Regex;
+
+use crate::hpo::hpo_loader::{get_text_to_term_map, LabelFilter};
+use crate::models::fenominal_model::{FenominalHit, Onset, OnsetValue, OntologyClass};
+use crate::simple_sentence::SimpleSentence;
+use crate::util::text_util::normalize_phrase;
+
+/// Onset HP:0003674
+pub static ONSET: Lazy<TermId> = Lazy::new(|| "HP:0003674".parse().unwrap());
+
+/// An age that follows a cue word, e.g., "since age 3 years", "at the age of 2 months", "from 18 months of age"
+static AGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:since|from|at|by|around|after|beginning at|starting at)\s+(?:the\s+)?(?P<age>age\s+(?:of\s+)?)?(?P<n>\d+(?:\.\d+)?)(?:\s*-?\s*(?P<unit>years?|yrs?|y|months?|mos?|weeks?|wks?|days?)\b)?(?:\s+(?:of\s+age|old))?",
+    )
+    .unwrap()
+});
+
+/// Maximum distance (in bytes) between an onset expression and the hit it qualifies
+const MAX_ONSET_DISTANCE: usize = 50;
+
+#[derive(Debug, Clone)]
+struct OnsetPhrase {
+    term: OntologyClass,
+    /// Adjectival forms ("neonatal") only qualify a hit that directly follows them
+    adjectival: bool,
+}
+
+pub struct OnsetExtractor {
+    /// Lower-case phrases (tokens separated by a single space)
+    phrases: HashMap<String, OnsetPhrase>,
+    max_phrase_token_count: usize,
+}
+
+impl OnsetExtractor {
+    pub fn new<O, T>(hpo: &O) -> Self
+    where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm + Synonymous,
+    {
+        let mut phrases = HashMap::new();
+        for (text, tid) in get_text_to_term_map(hpo, &ONSET, &LabelFilter::default()) {
+            // "Signs and symptoms begin before 15 years of age" cannot be matched by tokens
+            if text.chars().any(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            add_phrase(&mut phrases, hpo, &text, &tid, false);
+        }
+        let data = include_str!("../../data/onset_phrases.tsv");
+        for line in data.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 2 {
+                continue;
+            }
+            match parts[1].parse::<TermId>() {
+                Ok(tid) if hpo.iter_ancestor_ids(&tid).any(|a| *a == *ONSET) => add_phrase(&mut phrases, hpo, parts[0], &tid, false),
+                _ => eprintln!("Skipping {} in onset phrases: not an onset term", parts[1]),
+            }
+        }
+        // "Neonatal onset" -> "neonatal"
+        let adjectives: Vec<(String, TermId)> = phrases
+            .iter()
+            .filter_map(|(phrase, p)| {
+                phrase
+                    .strip_suffix(" onset")
+                    .map(|stem| (stem.to_string(), p.term.id.parse::<TermId>().unwrap()))
+            })
+            .collect();
+        for (stem, tid) in adjectives {
+            add_phrase(&mut phrases, hpo, &stem, &tid, true);
+        }
+        let max_phrase_token_count = phrases.keys().map(|k| k.split(' ').count()).max().unwrap_or(0);
+        Self {
+            phrases,
+            max_phrase_token_count,
+        }
+    }
+
+    /// Find onset expressions in the sentence and attach each one to the closest hit.
+    ///
+    /// Adjectival onset terms only qualify the hit that starts with or directly follows them.
+    /// Hits that already have an onset are not changed.
+    pub fn annotate(&self, sentence: &SimpleSentence, hits: &mut [FenominalHit]) {
+        if hits.is_empty() {
+            return;
+        }
+        let offset = sentence.get_start_pos();
+        let text = sentence.get_sentence();
+        for (onset, adjectival) in self.find(sentence) {
+            let hit_idx = if adjectival {
+                hits.iter().position(|h| {
+                    h.span.start == onset.span.start
+                        || (h.span.start >= onset.span.end
+                            && text[onset.span.end - offset..h.span.start - offset]
+                                .chars()
+                                .all(|c| c.is_whitespace() || c == '-'))
+                })
+            } else {
+                hits.iter()
+                    .enumerate()
+                    .map(|(i, h)| (i, distance(&h.span, &onset.span)))
+                    .filter(|(_, d)| *d <= MAX_ONSET_DISTANCE)
+                    .min_by_key(|(_, d)| *d)
+                    .map(|(i, _)| i)
+            };
+            if let Some(hit) = hit_idx.map(|i| &mut hits[i]) {
+                if hit.onset.is_none() {
+                    hit.onset = Some(onset);
+                }
+            }
+        }
+    }
+
+    /// Onset expressions of the sentence (with absolute coordinates), and whether they are adjectival
+    fn find(&self, sentence: &SimpleSentence) -> Vec<(Onset, bool)> {
+        let offset = sentence.get_start_pos();
+        let mut onsets = Vec::new();
+        for caps in AGE_PATTERN.captures_iter(sentence.get_sentence()) {
+            let unit = caps.name("unit").map(|m| m.as_str().to_ascii_lowercase());
+            // "at 3" is not an age, but "at age 3" and "at 3 months" are
+            if unit.is_none() && caps.name("age").is_none() {
+                continue;
+            }
+            let Some(duration) = iso8601_duration(&caps["n"], unit.as_deref().unwrap_or("years")) else {
+                continue;
+            };
+            let span = caps.get(0).unwrap().range();
+            onsets.push((
+                Onset {
+                    value: OnsetValue::Age { iso8601duration: duration },
+                    span: offset + span.start..offset + span.end,
+                },
+                false,
+            ));
+        }
+        let tokens = sentence.get_tokens();
+        let mut i = 0;
+        while i < tokens.len() {
+            let mut matched = false;
+            for n in (1..=self.max_phrase_token_count.min(tokens.len() - i)).rev() {
+                let chunk: Vec<&str> = tokens[i..i + n].iter().map(|t| t.get_lc_original_token()).collect();
+                if let Some(phrase) = self.phrases.get(&chunk.join(" ")) {
+                    let span = offset + tokens[i].get_start_pos()..offset + tokens[i + n - 1].get_end_pos();
+                    onsets.push((
+                        Onset {
+                            value: OnsetValue::OntologyClass(phrase.term.clone()),
+                            span,
+                        },
+                        phrase.adjectival,
+                    ));
+                    i += n;
+                    matched = true;
+                    break;
+                }
+            }
+            if !matched {
+                i += 1;
+            }
+        }
+        onsets
+    }
+}
+
+/// Add an onset phrase; adjectival forms do not replace phrases of the HPO or of the phrase table
+fn add_phrase<O, T>(phrases: &mut HashMap<String, OnsetPhrase>, hpo: &O, text: &str, tid: &TermId, adjectival: bool)
+where
+    O: OntologyTerms<T>,
+    T: MinimalTerm,
+{
+    let Some(term) = hpo.term_by_id(tid) else {
+        return;
+    };
+    let key = normalize_phrase(text);
+    if key.is_empty() || (adjectival && phrases.contains_key(&key)) {
+        return;
+    }
+    let term = OntologyClass::new(term.identifier().to_string(), term.name());
+    phrases.insert(key, OnsetPhrase { term, adjectival });
+}
+
+/// Number of bytes between two spans (0 if they overlap)
+fn distance(a: &Range<usize>, b: &Range<usize>) -> usize {
+    b.start.saturating_sub(a.end).max(a.start.saturating_sub(b.end))
+}
+
+/// Convert an age to an ISO 8601 duration, e.g., ("3", "years") -> `P3Y`, ("1.5", "years") -> `P1Y6M`
+fn iso8601_duration(number: &str, unit: &str) -> Option<String> {
+    let value: f64 = number.parse().ok()?;
+    let designator = match unit.chars().next()? {
+        'y' => 'Y',
+        'm' => 'M',
+        'w' => 'W',
+        'd' => 'D',
+        _ => return None,
+    };
+    if value.fract() == 0.0 {
+        return Some(format!("P{}{}", value as u64, designator));
+    }
+    if designator == 'Y' {
+        let months = (value * 12.0).round() as u64;
+        return Some(match (months / 12, months % 12) {
+            (0, m) => format!("P{}M", m),
+            (y, 0) => format!("P{}Y", y),
+            (y, m) => format!("P{}Y{}M", y, m),
+        });
+    }
+    Some(format!("P{}{}", number, designator))
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("3", "years", "P3Y")]
+    #[case("18", "months", "P18M")]
+    #[case("1.5", "y", "P1Y6M")]
+    #[case("0.5", "years", "P6M")]
+    #[case("2", "wks", "P2W")]
+    #[case("10", "days", "P10D")]
+    fn test_iso8601_duration(#[case] number: &str, #[case] unit: &str, #[case] expected: &str) {
+        assert_eq!(Some(expected.to_string()), iso8601_duration(number, unit));
+    }
+
+    #[rstest]
+    #[case("Seizures since age 3 years.", Some("since age 3 years"))]
+    #[case("Seizures began at the age of 2 months.", Some("at the age of 2 months"))]
+    #[case("Hypotonia from 18 months of age.", Some("from 18 months of age"))]
+    #[case("He was seen at 3 pm.", None)]
+    #[case("A 3-year-old boy.", None)]
+    fn test_age_pattern(#[case] sentence: &str, #[case] expected: Option<&str>) {
+        let found = AGE_PATTERN
+            .captures(sentence)
+            .filter(|c| c.name("unit").is_some() || c.name("age").is_some())
+            .map(|c| c.get(0).unwrap().as_str());
+        assert_eq!(expected, found);
+    }
+}
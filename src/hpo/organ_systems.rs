@@ -0,0 +1,63 @@
+//! OrganSystems
+//!
+//! The children of Phenotypic abnormality HP:0000118 correspond to organ systems, e.g.,
+//! Abnormality of the nervous system HP:0000707 or Abnormality of the cardiovascular system HP:0001626.
+//! A term may belong to several organ systems; for instance, Macrocephaly HP:0000256 is an abnormality of
+//! the head or neck and of the musculoskeletal system. The categories of each term are
+//! computed once from the hierarchy and cached, because the same terms are found again and again
+//! in a batch of documents.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::term::MinimalTerm;
+use ontolius::TermId;
+
+use crate::hpo::branches::PHENOTYPIC_ABNORMALITY;
+use crate::models::fenominal_model::OntologyClass;
+
+pub struct OrganSystems {
+    /// The children of Phenotypic abnormality
+    categories: HashMap<TermId, OntologyClass>,
+    cache: RwLock<HashMap<TermId, Vec<OntologyClass>>>,
+}
+
+impl OrganSystems {
+    pub fn new<O, T>(hpo: &O) -> Self
+    where
+        O: OntologyTerms<T> + HierarchyWalks,
+        T: MinimalTerm,
+    {
+        let categories = hpo
+            .iter_child_ids(&PHENOTYPIC_ABNORMALITY)
+            .filter_map(|tid| {
+                let term = hpo.term_by_id(tid)?;
+                Some((tid.clone(), OntologyClass::new(tid.to_string(), term.name())))
+            })
+            .collect();
+        Self {
+            categories,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The organ systems of a term, sorted by ID. Terms outside of Phenotypic abnormality have no organ system.
+    pub fn get_categories<O>(&self, hpo: &O, tid: &TermId) -> Vec<OntologyClass>
+    where
+        O: HierarchyWalks,
+    {
+        if let Some(categories) = self.cache.read().unwrap().get(tid) {
+            return categories.clone();
+        }
+        let mut categories: Vec<OntologyClass> = std::iter::once(tid)
+            .chain(hpo.iter_ancestor_ids(tid))
+            .filter_map(|a| self.categories.get(a))
+            .cloned()
+            .collect();
+        categories.sort_by(|a, b| a.id.cmp(&b.id));
+        categories.dedup();
+        self.cache.write().unwrap().insert(tid.clone(), categories.clone());
+        categories
+    }
+}
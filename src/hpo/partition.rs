@@ -26,7 +26,7 @@ impl<'a, T> Partition<'a, T> {
     pub fn new(original: &'a [T], chunk_size: usize) -> Self {
         Self {
             original_list: original,
-            chunk_size: chunk_size,
+            chunk_size,
         }
     }
 
@@ -0,0 +1,67 @@
+//! Hierarchy-aware redundancy removal
+//!
+//! A note often mentions a finding both in general and in specific terms ("seizures ...
+//! generalized tonic-clonic seizures"). Because of the true path rule, the specific term implies
+//! the general one, and downstream tools (e.g., phenopackets) expect only the most specific terms.
+//!
+//! The following hits are redundant:
+//! 1. An observed hit whose term is an ancestor of the term of another observed hit.
+//! 2. An excluded hit whose term is a descendant of the term of another excluded hit
+//!    (excluding a finding excludes all of its subclasses).
+//!
+//! Hits of different experiencers (e.g., the proband and the mother) are never compared.
+//! Observed and excluded hits do not make each other redundant. If an excluded term conflicts with an
+//! observed descendant or with an observed mention of the same term ("no seizures ... absence seizures"),
+//! both hits are kept, and the excluded term does not make its excluded descendants redundant.
+
+use std::collections::{HashMap, HashSet};
+
+use ontolius::ontology::HierarchyWalks;
+use ontolius::TermId;
+
+use crate::models::fenominal_model::FenominalHit;
+
+/// Determine which hits are redundant according to the hierarchy of the ontology.
+///
+/// Returns a flag for each hit. Hits of terms that are not part of the ontology are never redundant.
+pub fn find_redundant_hits<O>(ontology: &O, hits: &[FenominalHit]) -> Vec<bool>
+where
+    O: HierarchyWalks,
+{
+    let mut ancestors: HashMap<&str, HashSet<String>> = HashMap::new();
+    for hit in hits {
+        ancestors.entry(hit.term_id.as_str()).or_insert_with(|| {
+            hit.term_id
+                .parse::<TermId>()
+                .map(|tid| ontology.iter_ancestor_ids(&tid).map(TermId::to_string).collect())
+                .unwrap_or_default()
+        });
+    }
+    // is `ancestor` a proper ancestor of `hit`?
+    let is_ancestor = |ancestor: &FenominalHit, hit: &FenominalHit| {
+        ancestors[hit.term_id.as_str()].contains(&ancestor.term_id)
+    };
+    let comparable = |a: &FenominalHit, b: &FenominalHit| a.experiencer == b.experiencer;
+    // excluded hits that are contradicted by an observed hit of the same term or of a descendant
+    let contradicted: Vec<bool> = hits
+        .iter()
+        .map(|h| {
+            !h.is_observed
+                && hits.iter().any(|o| {
+                    o.is_observed && comparable(h, o) && (o.term_id == h.term_id || is_ancestor(h, o))
+                })
+        })
+        .collect();
+    hits.iter()
+        .map(|hit| {
+            if hit.is_observed {
+                hits.iter()
+                    .any(|other| other.is_observed && comparable(hit, other) && is_ancestor(hit, other))
+            } else {
+                hits.iter().zip(&contradicted).any(|(other, &other_contradicted)| {
+                    !other.is_observed && !other_contradicted && comparable(hit, other) && is_ancestor(other, hit)
+                })
+            }
+        })
+        .collect()
+}
@@ -7,58 +7,148 @@
 //! on each sentence in this module.
 
 use std::cmp::min;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::sync::Arc;
-use once_cell::sync::Lazy;
 use ontolius::ontology::{HierarchyWalks, OntologyTerms};
 use ontolius::term::{MinimalTerm, Synonymous};
-use std::collections::HashSet;
-use crate::models::fenominal_model::FenominalHit;
-use crate::stopwords::is_stop;
+use ontolius::TermId;
+use crate::models::fenominal_model::{FenominalHit, OntologyClass};
+use crate::options::FenominalOptions;
 use crate::util::error::FenominalError;
+use crate::util::experiencer::ExperiencerDetector;
 use crate::util::negex::NegEx;
 use crate::{simple_sentence::SimpleSentence, simple_token::SimpleToken};
-use crate::hpo::default_hpo_mapper::DefaultHpoMapper;
-
-/// This is a set of words that we use to indentify exclusion (negation) of phenotypic abnormality
-///
-/// e.g. "Proband 1 did not have arachnodactyly" would be flagged as negated because of the word "not".
-static NEGATION_CLUES: Lazy<HashSet<String>> = Lazy::new(|| {
-    let mut set = HashSet::new();
-    set.insert("no".to_string());
-    set.insert("nil".to_string());
-    set.insert("denies".to_string());
-    set.insert("not".to_string());
-    set.insert("exclude".to_string());
-    set.insert("excluded".to_string());
-    set.insert("screen".to_string());
-    set.insert("screening".to_string());
-    set.insert("normal".to_string());
-    set
-});
+use crate::hpo::default_term_mapper::DefaultTermMapper;
+use crate::hpo::branches::PHENOTYPIC_ABNORMALITY;
+use crate::hpo::hpo_loader::{get_term_to_branch_map, get_text_to_term_map_for_roots, LabelFilter};
+use crate::hpo::mining_dictionary::MiningDictionary;
+use crate::hpo::modifiers::ModifierExtractor;
+use crate::hpo::normal_findings::NormalFindingRules;
+use crate::hpo::onset::OnsetExtractor;
+use crate::hpo::organ_systems::OrganSystems;
+use crate::hpo::redundancy::find_redundant_hits;
 
 pub struct SentenceMapper<O, T> where
         O: OntologyTerms<T> + HierarchyWalks,
         T: MinimalTerm + Synonymous {
-    hpo_mapper: DefaultHpoMapper,
-    ontology: Arc<O>,
+    term_mapper: DefaultTermMapper,
+    /// Not available if the mapper was created from a precompiled dictionary alone
+    ontology: Option<Arc<O>>,
+    /// The release of the ontology of the precompiled dictionary (empty if compiled from the ontology)
+    dictionary_version: String,
+    /// The roots of the mined branches
+    roots: Vec<TermId>,
+    /// The labels of the minable terms if there is no ontology to look them up
+    labels: HashMap<TermId, String>,
     _marker: PhantomData<T>,
+    /// Words that are skipped when matching
+    stopwords: HashSet<String>,
     negex: NegEx,
+    experiencer: ExperiencerDetector,
+    /// Only available if Phenotypic abnormality is one of the mined branches
+    normal_findings: Option<NormalFindingRules>,
+    /// Onset and modifier extraction are only available for the HPO
+    onset: Option<OnsetExtractor>,
+    modifiers: Option<ModifierExtractor>,
+    /// Only available for the HPO
+    organ_systems: Option<OrganSystems>,
+    /// Maps each minable term to the root of its branch
+    term_to_branch: HashMap<TermId, OntologyClass>,
 }
 
 impl<O, T>  SentenceMapper<O, T> where
         O: OntologyTerms<T> + HierarchyWalks,
         T: MinimalTerm + Synonymous {
-    pub fn new(ontology: Arc<O>) -> Self {
-        let mapper = DefaultHpoMapper::new(ontology.clone());
+    /// Create a mapper for the descendants of the root terms (e.g., Phenotypic abnormality HP:0000118).
+    pub fn new(ontology: Arc<O>, roots: &[TermId]) -> Self {
+        Self::with_options(ontology, roots, &FenominalOptions::default())
+    }
+
+    /// Create a mapper with the label filter, stop words and negation window of the options.
+    /// The roots of the options are ignored in favor of `roots`.
+    pub fn with_options(ontology: Arc<O>, roots: &[TermId], options: &FenominalOptions) -> Self {
+        let text_to_term_map = get_text_to_term_map_for_roots(ontology.as_ref(), roots, &LabelFilter::from_options(options));
+        let term_to_branch = get_term_to_branch_map(ontology.as_ref(), roots)
+            .into_iter()
+            .filter_map(|(tid, root)| {
+                let root_term = ontology.term_by_id(&root)?;
+                Some((tid, OntologyClass::new(root.to_string(), root_term.name())))
+            })
+            .collect();
+        let normal_findings = roots
+            .contains(&PHENOTYPIC_ABNORMALITY)
+            .then(|| NormalFindingRules::new(ontology.as_ref(), &text_to_term_map));
+        Self::from_maps(&text_to_term_map, term_to_branch, normal_findings, roots.to_vec(), Some(ontology), options)
+    }
+
+    /// Create a mapper for the terms of a dictionary (see [`MiningDictionary`]). Without the ontology,
+    /// onsets, modifiers, organ systems and redundant hits are not determined.
+    pub fn from_dictionary(dictionary: &MiningDictionary, ontology: Option<Arc<O>>, options: &FenominalOptions) -> Self {
+        let text_to_term_map = dictionary.text_to_term_map();
+        let roots = dictionary.root_ids();
+        let term_to_branch = dictionary.term_to_branch();
+        let normal_findings = roots.contains(&PHENOTYPIC_ABNORMALITY).then(|| match &ontology {
+            Some(ontology) => NormalFindingRules::new(ontology.as_ref(), &text_to_term_map),
+            None => NormalFindingRules::with_resolver(&text_to_term_map, |tid| {
+                term_to_branch
+                    .get(tid)
+                    .filter(|branch| branch.id == PHENOTYPIC_ABNORMALITY.to_string())
+                    .map(|_| tid.clone())
+            }),
+        });
+        let labels = if ontology.is_none() { dictionary.term_labels() } else { HashMap::new() };
+        let mut mapper = Self::from_maps(&text_to_term_map, term_to_branch, normal_findings, roots, ontology, options);
+        mapper.dictionary_version = dictionary.ontology_version().version.clone();
+        mapper.labels = labels;
+        mapper
+    }
+
+    fn from_maps(
+        text_to_term_map: &HashMap<String, TermId>,
+        term_to_branch: HashMap<TermId, OntologyClass>,
+        normal_findings: Option<NormalFindingRules>,
+        roots: Vec<TermId>,
+        ontology: Option<Arc<O>>,
+        options: &FenominalOptions,
+    ) -> Self {
+        let stopwords = options.stopword_set();
+        let mapper = DefaultTermMapper::from_map(text_to_term_map.iter().map(|(k, v)| (k.as_ref(), v)), &stopwords);
+        let hpo = ontology.as_deref().filter(|_| is_hpo(&roots));
+        let onset = hpo.map(|hpo| OnsetExtractor::new(hpo));
+        let modifiers = hpo.map(|hpo| ModifierExtractor::new(hpo, text_to_term_map));
+        let organ_systems = hpo.map(|hpo| OrganSystems::new(hpo));
         SentenceMapper { 
-            hpo_mapper: mapper,
-            ontology: ontology.clone(),
+            term_mapper: mapper,
+            dictionary_version: String::new(),
+            ontology,
+            roots,
+            labels: HashMap::new(),
             _marker: PhantomData,
-            negex: NegEx::from_embedded(),
+            stopwords,
+            negex: NegEx::from_embedded().with_window(options.negation_window),
+            experiencer: ExperiencerDetector::from_embedded(),
+            normal_findings,
+            onset,
+            modifiers,
+            organ_systems,
+            term_to_branch,
         }
     }
 
+    pub fn ontology(&self) -> Option<&O> {
+        self.ontology.as_deref()
+    }
+
+    pub fn dictionary_version(&self) -> &str {
+        &self.dictionary_version
+    }
+
+    pub fn roots(&self) -> &[TermId] {
+        &self.roots
+    }
+
     pub fn map_sentence(&self, simple_sentence: &SimpleSentence) -> Result<Vec<FenominalHit>, FenominalError> {
         let full_sentence_refs: Vec<&str> = simple_sentence.get_tokens()
             .iter()
@@ -68,14 +158,16 @@ impl<O, T>  SentenceMapper<O, T> where
         // remove stop words from tokens
         let nonstop_tokens: Vec<&SimpleToken> = tokens
             .iter()
-            .filter(|tk| !is_stop(tk.get_original_token()))
+            .filter(|tk| !self.stopwords.contains(tk.get_lc_original_token()))
             .collect();
         let start_pos_offset = simple_sentence.get_start_pos();
         let mut mapped_sentence_part_list = Vec::new();
         // Check window sizes from largest to smallest
-        let max_window = min(DefaultHpoMapper::MAX_HPO_TERM_TOKEN_COUNT, tokens.len());
+        let max_window = min(self.term_mapper.max_token_count(), tokens.len());
         // was the corresponding token already used for a "hit"?
         let mut token_used = vec![false; nonstop_tokens.len()];
+        // the same information, indexed by the position in the full sentence
+        let mut sentence_token_used = vec![false; tokens.len()];
         for window_size in (1..=max_window).rev() {
             // .windows(n) slides 1 token at a time: [0,1,2], [1,2,3], [2,3,4]...
             for (idx, chunks) in nonstop_tokens.windows(window_size).enumerate() {
@@ -87,10 +179,8 @@ impl<O, T>  SentenceMapper<O, T> where
                     .iter()
                     .map(|stoken| stoken.get_lc_original_token())
                     .collect();
-                if let Some(hpo_match) = self.hpo_mapper.get_match(&string_chunk_refs) {
-                    let hpo_id = hpo_match.get_hpo_id();
-                    let term = self.ontology.term_by_id(hpo_id)
-                        .ok_or_else(|| FenominalError::term_retrieval_error(hpo_id))?;
+                if let Some(term_match) = self.term_mapper.get_match(&string_chunk_refs) {
+                    let term_id = term_match.get_term_id();
                     // Get character positions from the tokens
                     let start_char = chunks[0].get_start_pos() + start_pos_offset;
                     let end_char = chunks[chunks.len() - 1].get_end_pos() + start_pos_offset;
@@ -98,76 +188,132 @@ impl<O, T>  SentenceMapper<O, T> where
                     let first_token_idx = chunks[0].index;
                     let last_token_idx = chunks[chunks.len() - 1].index;
                     let hit_idx_range = first_token_idx..(last_token_idx + 1);
-                    let is_excluded = self.negex.is_negated(&full_sentence_refs, hit_idx_range);
-
-                    let hit = FenominalHit::new(
-                        hpo_id.to_string(),
-                        term.name(),
-                        start_char..end_char,
-                        !is_excluded,
-                    );
-                    for i in idx..idx + window_size {
-                        token_used[i] = true;
-                    }
+                    sentence_token_used[hit_idx_range.clone()].fill(true);
+                    let is_excluded = self.negex.is_negated(&full_sentence_refs, hit_idx_range.clone());
+
+                    let mut hit = self.create_hit(term_id, start_char..end_char, !is_excluded)?;
+                    hit.experiencer = self.experiencer.detect(&full_sentence_refs, hit_idx_range);
+                    token_used[idx..idx + window_size].fill(true);
                     mapped_sentence_part_list.push(hit);
                 } 
             }
         }
+        // "normal hearing", "echocardiogram was normal", ... exclude an abnormality
+        let normal_findings = self.normal_findings
+            .as_ref()
+            .map(|rules| rules.find(&full_sentence_refs, &sentence_token_used))
+            .unwrap_or_default();
+        for (hit_idx_range, term_id) in normal_findings {
+            let start_char = tokens[hit_idx_range.start].get_start_pos() + start_pos_offset;
+            let end_char = tokens[hit_idx_range.end - 1].get_end_pos() + start_pos_offset;
+            let mut hit = self.create_hit(&term_id, start_char..end_char, false)?;
+            hit.experiencer = self.experiencer.detect(&full_sentence_refs, hit_idx_range);
+            mapped_sentence_part_list.push(hit);
+        }
         // Sort according to order of appearance
         mapped_sentence_part_list.sort_by_key(|h| h.span.start);
+        // "severe intellectual disability", "ptosis on the left", "occasional seizures"
+        if let Some(modifiers) = &self.modifiers {
+            modifiers.annotate(simple_sentence, &mut mapped_sentence_part_list);
+        }
+        // "seizures since age 3 years", "neonatal hypotonia"
+        if let Some(onset) = &self.onset {
+            onset.annotate(simple_sentence, &mut mapped_sentence_part_list);
+        }
+        for hit in mapped_sentence_part_list.iter_mut() {
+            self.classify_hit(hit);
+        }
         Ok(mapped_sentence_part_list)
     }
 
-    fn has_negation(&self, tokens: &[SimpleToken]) -> bool {
-        tokens
-            .iter()
-            .any(|token| NEGATION_CLUES.contains(token.get_lc_original_token()))
+    /// Set the branch and the organ-system categories of the hit from its term ID, e.g., after the
+    /// ID was replaced by a current term. Returns false (and leaves the hit as it is) if the term
+    /// is not in the branches of this mapper.
+    pub fn classify_hit(&self, hit: &mut FenominalHit) -> bool {
+        let Ok(tid) = hit.term_id.parse::<TermId>() else {
+            return false;
+        };
+        let Some(branch) = self.term_to_branch.get(&tid) else {
+            return false;
+        };
+        hit.branch = Some(branch.clone());
+        if let (Some(organ_systems), Some(ontology)) = (&self.organ_systems, &self.ontology) {
+            hit.categories = organ_systems.get_categories(ontology.as_ref(), &tid);
+        }
+        true
+    }
+
+    /// Flag the hits that are made redundant by more specific observed hits or by more general
+    /// excluded hits according to the hierarchy of this mapper's ontology (see [`crate::hpo::redundancy`]).
+    /// Without the ontology, no hit is redundant.
+    pub fn find_redundant_hits(&self, hits: &[FenominalHit]) -> Vec<bool> {
+        match &self.ontology {
+            Some(ontology) => find_redundant_hits(ontology.as_ref(), hits),
+            None => vec![false; hits.len()],
+        }
+    }
+
+    fn create_hit(&self, term_id: &TermId, span: Range<usize>, is_observed: bool) -> Result<FenominalHit, FenominalError> {
+        let label = match &self.ontology {
+            Some(ontology) => ontology.term_by_id(term_id).map(|term| term.name()),
+            None => self.labels.get(term_id).map(String::as_str),
+        }
+        .ok_or_else(|| FenominalError::term_retrieval_error(term_id))?;
+        let mut hit = FenominalHit::new(term_id.to_string(), label, span, is_observed);
+        hit.ontology = Some(term_id.prefix().to_string());
+        Ok(hit)
     }
 }
 
+/// Onsets, modifiers and organ systems are determined if the roots are HPO terms
+fn is_hpo(roots: &[TermId]) -> bool {
+    !roots.is_empty() && roots.iter().all(|root| root.prefix() == PHENOTYPIC_ABNORMALITY.prefix())
+}
 
 // region:    --- Tests
 
 #[cfg(test)]
 mod tests {
 
-    use std::{collections::HashMap, str::FromStr};
+    use std::{collections::{HashMap, HashSet}, str::FromStr};
 
     use ontolius::TermId;
     use rstest::{fixture, rstest};
 
-    use crate::hpo::hpo_concept::HpoConcept;
+    use crate::hpo::concept::Concept;
+    use crate::stopwords::STOP_WORDS;
 
-    use super::*;
+fn stopwords() -> HashSet<String> {
+    STOP_WORDS.iter().map(|s| s.to_string()).collect()
+}
 
-    
 #[fixture]
-pub fn paramedian_cleft_palate() -> HpoConcept {
+pub fn paramedian_cleft_palate() -> Concept {
     let hpo_id = TermId::from_str("HP:0009099").unwrap();
     let label = "paramedian cleft lip";
-    HpoConcept::new(label, hpo_id)
+    Concept::with_stopwords(label, hpo_id, &stopwords())
 } 
 
 #[fixture]
-fn decreased_hc() -> HpoConcept {
+fn decreased_hc() -> Concept {
     // Microcephaly HP:0000252
     let hpo_id = TermId::from_str("HP:0040195").unwrap();
     let label = "Decreased head circumference";
-    HpoConcept::new(label, hpo_id)
+    Concept::with_stopwords(label, hpo_id, &stopwords())
 }
 
 #[fixture]
 fn component_token_to_concept_map(
-    decreased_hc: HpoConcept,
-    paramedian_cleft_palate: HpoConcept
-) -> HashMap<String, Vec<HpoConcept>> {
-    let mut map: HashMap<String, Vec<HpoConcept>> = HashMap::new();
+    decreased_hc: Concept,
+    paramedian_cleft_palate: Concept
+) -> HashMap<String, Vec<Concept>> {
+    let mut map: HashMap<String, Vec<Concept>> = HashMap::new();
     let dch = vec![decreased_hc];
-    for token in vec!["Decreased", "head", "circumference"] {
+    for token in ["Decreased", "head", "circumference"] {
         map.insert(token.to_string(), dch.clone());
     };
     let pcp = vec![paramedian_cleft_palate];
-    for token in vec!["paramedian", "cleft", "lip"] {
+    for token in ["paramedian", "cleft", "lip"] {
         map.insert(token.to_string(), pcp.clone());
     };
     map
@@ -177,19 +323,29 @@ fn component_token_to_concept_map(
 
 #[rstest]
 fn paramedian_cp(
-    component_token_to_concept_map:HashMap<String, Vec<HpoConcept>>,
-    paramedian_cleft_palate: HpoConcept
+    component_token_to_concept_map:HashMap<String, Vec<Concept>>,
+    paramedian_cleft_palate: Concept
 )  {
     let result = component_token_to_concept_map.get("cleft");
     assert!(result.is_some());
     let hpo_concept_list = result.unwrap();
     assert_eq!(1, hpo_concept_list.len());
     let hpo_concept = hpo_concept_list[0].clone();
-    let expected_term_id: &TermId = paramedian_cleft_palate.get_hpo_id();
-    let observed_term_id: &TermId = hpo_concept.get_hpo_id();
+    let expected_term_id: &TermId = paramedian_cleft_palate.get_term_id();
+    let observed_term_id: &TermId = hpo_concept.get_term_id();
     assert_eq!(expected_term_id, observed_term_id);
 }
 
+#[rstest]
+#[case(vec!["HP:0000118", "HP:0000005"], true)]
+#[case(vec!["MONDO:0000001"], false)]
+#[case(vec!["HP:0000118", "MONDO:0000001"], false)]
+#[case(vec![], false)]
+fn test_is_hpo(#[case] roots: Vec<&str>, #[case] expected: bool) {
+    let roots: Vec<TermId> = roots.iter().map(|r| TermId::from_str(r).unwrap()).collect();
+    assert_eq!(expected, super::is_hpo(&roots));
+}
+
 
     
 }
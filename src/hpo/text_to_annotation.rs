@@ -3,47 +3,24 @@ use crate::{FenominalHit, FenominalSegment, FenominalSentence, util::error::Feno
 
 
 
-/// Converts a raw input string and a list of structural hits into a sequence of
-/// displayable text annotations, ensuring safe handling of Unicode character boundaries.
-///
-/// This function iterates through a list of `fenominal_hits` (which contain byte spans)
-/// and segments the `input_text` into two types of annotations:
-/// 1. Non-hit (plain) text segments, which are HTML-escaped.
-/// 2. Matched hit segments, which contain associated metadata.
-///
-/// **Safety & Error Handling:**
-///
-/// Due to the nature of UTF-8 strings in Rust, slicing (`&str[start..end]`)
-/// requires that the provided indices (`start` and `end`) fall exactly on
-/// valid character boundaries. Indices derived from external systems (like
-/// text mining tools) may sometimes be misaligned, causing a runtime panic.
-///
-/// To prevent crashes, this function includes a safety guard:
-/// - If a hit's span exceeds the text length or is otherwise invalid, it is skipped.
-/// - **Crucially, if a hit's span indices do not align with valid UTF-8 character
-///   boundaries in the `input_text`, the hit is safely skipped and logged to stderr.**
-///   The function continues processing the remaining text and hits, maintaining
-///   the last valid index (`last_index`).
+/// Converts a sentence and the hits that were found in it into a [`FenominalSentence`], i.e., a
+/// sequence of segments that cover the entire sentence:
+/// 1. Non-hit (plain) text segments, which contain the text verbatim. No escaping is done here;
+///    see [`crate::export::html`] for rendering sentences as HTML.
+/// 2. Matched hit segments, which contain the matched text and the hit.
 ///
 /// # Arguments
 ///
-/// * `input_text`: The full, raw UTF-8 string to be annotated.
-/// * `fenominal_hits`: A slice of `FenominalHit` structures, where each hit
-///   contains a byte span (`hit.span.start` and `hit.span.end`) relative to
-///   the `input_text`.
-///
-/// # Returns
-///
-/// A `Result` containing:
-/// * `Ok(Vec<TextAnnotationDto>)`: A vector of successfully generated text annotations.
-/// * `Err(String)`: An error string, though the function is primarily designed
-///   to skip errors and continue (error return paths are limited to unhandled
-///   internal logic failures).
+/// * `input_text`: The text of the sentence.
+/// * `start_pos`: The start of the sentence in the document.
+/// * `fenominal_hits`: The hits of the sentence, sorted by position. Their byte spans
+///   (`hit.span.start` and `hit.span.end`) are relative to the document.
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function is designed not to panic, but relies on `html_escape::encode_text`
-/// and the internal logic of `TextAnnotationDto` and `FenominalHit` being correct.
+/// Hits that start before the sentence are skipped and logged to stderr. A hit whose span is empty,
+/// exceeds the sentence, or does not fall on UTF-8 character boundaries of `input_text` is an error
+/// ([`FenominalError::invalid_span`]), because slicing the text would panic.
 pub fn fenominal_hits_to_sentence(
     input_text: &str,
     start_pos: usize,
@@ -65,21 +65,40 @@
 
 
 mod autocomplete;
+mod builder;
+#[cfg(feature = "serde")]
+pub mod export;
+mod clinical_section;
 mod core_document;
 mod fenominal;
 mod util;
 mod hpo;
 mod models;
+mod options;
 mod simple_sentence;
 mod simple_token;
 mod stopwords;
+mod structured_form;
 
 
-pub use crate::autocomplete::{AutoCompleter, HpoMatch};
+#[allow(deprecated)]
+pub use crate::autocomplete::{AutoCompleter, HpoMatch, TermMatch};
+pub use crate::builder::FenominalBuilder;
+pub use crate::clinical_section::{ClinicalSection, SectionFilter, SectionLexicon};
 pub use crate::models::fenominal_model::{
-    FenominalHit, FenominalHitSegment, FenominalSegment, FenominalSentence, FenominalText,
+    Experiencer, FenominalHit, FenominalHitSegment, FenominalSegment, FenominalSentence, FenominalText, Modifier,
+    Onset, OnsetValue, OntologyClass,
 };
+pub use crate::models::batch::{BatchResult, BatchStats, DocumentResult};
+pub use crate::models::offset_unit::OffsetUnit;
+pub use crate::models::organ_system_summary::{OrganSystemEntry, OrganSystemSummary};
+pub use crate::models::phenotype_summary::{ConflictPolicy, Mention, PhenotypeEntry, PhenotypeSummary};
+pub use crate::models::provenance::{Configuration, MatchMode, MiningResult, OntologyVersion, Provenance};
 pub use crate::fenominal::Fenominal;
+pub use crate::options::{FenominalOptions, DEFAULT_MIN_SYNONYM_LENGTH, DEFAULT_OMITTABLE_LABELS};
+pub use crate::hpo::branches;
+pub use crate::hpo::mining_dictionary::{MiningDictionary, DICTIONARY_FORMAT_VERSION};
+pub use crate::hpo::obsolete::{OutdatedTermId, TermIdResolver, TermIdStatus};
 pub use crate::util::text_util::sanitize;
 pub use crate::util::text_util::sentence_split;
 pub use crate::util::error::FenominalError;
@@ -0,0 +1,143 @@
+//! Batch mining
+//!
+//! The results of [`crate::Fenominal::process_batch`]: the hits of each document, or the error that prevented
+//! mining it, in the order of the input, and the throughput of the batch.
+
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::models::fenominal_model::FenominalHit;
+use crate::util::error::FenominalError;
+
+/// The hits of one document of a batch
+#[derive(Debug)]
+pub struct DocumentResult {
+    pub document_id: String,
+    /// The length of the text in bytes
+    pub text_length: usize,
+    /// The hits, or the error that prevented mining the document
+    pub hits: Result<Vec<FenominalHit>, FenominalError>,
+}
+
+/// Throughput statistics of a batch
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchStats {
+    /// The number of documents, including failed ones
+    pub documents: usize,
+    /// The number of documents that could not be mined
+    pub failed: usize,
+    /// The total length of the texts in bytes
+    pub bytes: usize,
+    /// The total number of hits
+    pub hits: usize,
+    /// The wall-clock time of the batch
+    pub elapsed: Duration,
+    /// The number of threads that mined documents
+    pub threads: usize,
+}
+
+impl BatchStats {
+    fn per_second(&self, count: usize) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            count as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// The number of documents mined per second (0 if no time elapsed)
+    pub fn documents_per_second(&self) -> f64 {
+        self.per_second(self.documents)
+    }
+
+    /// The number of bytes of text mined per second (0 if no time elapsed)
+    pub fn bytes_per_second(&self) -> f64 {
+        self.per_second(self.bytes)
+    }
+}
+
+impl fmt::Display for BatchStats {
+    /// e.g., `1000 documents (2 failed), 2.5 MB, 12000 hits in 1.20 s on 8 threads (833.3 documents/s, 2.08 MB/s)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} documents ({} failed), {:.1} MB, {} hits in {:.2} s on {} threads ({:.1} documents/s, {:.2} MB/s)",
+            self.documents,
+            self.failed,
+            self.bytes as f64 / 1e6,
+            self.hits,
+            self.elapsed.as_secs_f64(),
+            self.threads,
+            self.documents_per_second(),
+            self.bytes_per_second() / 1e6,
+        )
+    }
+}
+
+/// The results of a batch, in the order of the input documents
+#[derive(Debug)]
+pub struct BatchResult {
+    pub documents: Vec<DocumentResult>,
+    pub stats: BatchStats,
+}
+
+impl BatchResult {
+    pub(crate) fn new(documents: Vec<DocumentResult>, elapsed: Duration, threads: usize) -> Self {
+        let stats = BatchStats {
+            documents: documents.len(),
+            failed: documents.iter().filter(|d| d.hits.is_err()).count(),
+            bytes: documents.iter().map(|d| d.text_length).sum(),
+            hits: documents.iter().filter_map(|d| d.hits.as_ref().ok()).map(Vec::len).sum(),
+            elapsed,
+            threads,
+        };
+        Self { documents, stats }
+    }
+
+    /// The IDs of the documents that could not be mined, with their errors
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &FenominalError)> {
+        self.documents
+            .iter()
+            .filter_map(|d| d.hits.as_ref().err().map(|e| (d.document_id.as_str(), e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document(document_id: &str, text_length: usize, hit_count: Option<usize>) -> DocumentResult {
+        let hits = match hit_count {
+            Some(n) => Ok((0..n).map(|i| FenominalHit::new("HP:0001250".to_string(), "Seizure", i..i + 1, true)).collect()),
+            None => Err(FenominalError::Mining { reason: "test".to_string() }),
+        };
+        DocumentResult { document_id: document_id.to_string(), text_length, hits }
+    }
+
+    #[test]
+    fn test_stats() {
+        let documents = vec![document("a", 1_000_000, Some(3)), document("b", 500_000, None), document("c", 500_000, Some(0))];
+        let batch = BatchResult::new(documents, Duration::from_millis(500), 4);
+        let stats = &batch.stats;
+        assert_eq!((3, 1, 2_000_000, 3), (stats.documents, stats.failed, stats.bytes, stats.hits));
+        assert_eq!(6.0, stats.documents_per_second());
+        assert_eq!(4_000_000.0, stats.bytes_per_second());
+        assert_eq!(
+            "3 documents (1 failed), 2.0 MB, 3 hits in 0.50 s on 4 threads (6.0 documents/s, 4.00 MB/s)",
+            stats.to_string()
+        );
+        let errors: Vec<&str> = batch.errors().map(|(id, _)| id).collect();
+        assert_eq!(vec!["b"], errors);
+    }
+
+    #[test]
+    fn test_empty_batch() {
+        let batch = BatchResult::new(Vec::new(), Duration::ZERO, 1);
+        assert_eq!(0, batch.stats.documents);
+        assert_eq!(0.0, batch.stats.documents_per_second());
+    }
+}
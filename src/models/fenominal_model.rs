@@ -2,6 +2,8 @@ use std::fmt;
 use std::ops::Range;
 use serde::{Deserialize, Serialize};
 
+use crate::clinical_section::ClinicalSection;
+
 /// A sentence of the original text
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -11,7 +13,10 @@ pub struct FenominalSentence {
     /// start are directly comparable).
     pub start: usize,
     pub original_text: String,
-    pub segments: Vec<FenominalSegment>
+    pub segments: Vec<FenominalSegment>,
+    /// The section of the clinical note that contains this sentence, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub section: Option<ClinicalSection>,
 }
 
 impl FenominalSentence {
@@ -22,7 +27,8 @@ impl FenominalSentence {
         Self { 
             start, 
             original_text: original.into(),
-            segments 
+            segments,
+            section: None,
         }
     }
 
@@ -65,10 +71,33 @@ pub struct FenominalHit {
     pub term_id: String,
     /// The entity's label.
     pub label: String,
-    /// The coordinates of the entity within the source text.
+    /// The byte offsets of the entity within the source text (see [`crate::OffsetUnit`] for
+    /// converting them to character or UTF-16 offsets).
     pub span: Range<usize>,
     /// The observation status (present/excluded).
     pub is_observed: bool,
+    /// The person who experienced the finding (by default, the proband).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub experiencer: Experiencer,
+    /// The section of the clinical note in which the entity was found, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub section: Option<ClinicalSection>,
+    /// The age of onset of the finding, if it is stated near the entity.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub onset: Option<Onset>,
+    /// Severity, laterality and frequency modifiers stated near the entity.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modifiers: Vec<Modifier>,
+    /// The root of the HPO branch of the entity, e.g., Phenotypic abnormality HP:0000118.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub branch: Option<OntologyClass>,
+    /// The prefix of the ontology of the entity, e.g., `HP` or `MONDO`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ontology: Option<String>,
+    /// The organ systems of the entity, i.e., its ancestors among the children of Phenotypic abnormality
+    /// HP:0000118 (e.g., Abnormality of the nervous system HP:0000707).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub categories: Vec<OntologyClass>,
 }
 
 impl FenominalHit {
@@ -78,9 +107,26 @@ impl FenominalHit {
             label: label.to_string(),
             span,
             is_observed,
+            experiencer: Experiencer::default(),
+            section: None,
+            onset: None,
+            modifiers: Vec::new(),
+            branch: None,
+            ontology: None,
+            categories: Vec::new(),
         }
     }
 
+    /// Does the entity belong to the branch with the given root (e.g., `HP:0000005` Mode of inheritance)?
+    pub fn is_in_branch(&self, root_id: &str) -> bool {
+        self.branch.as_ref().is_some_and(|b| b.id == root_id)
+    }
+
+    /// Was the finding experienced by the proband (and not, e.g., by a family member)?
+    pub fn is_proband(&self) -> bool {
+        self.experiencer == Experiencer::Proband
+    }
+
     /// get the start/end position of a 'Hit'
     pub fn get_span(&self) -> Range<usize> {
         Clone::clone(&self.span)
@@ -102,6 +148,95 @@ impl fmt::Display for FenominalHit {
     }
 }
 
+/// The person who experienced a finding.
+///
+/// For instance, "his mother has hearing loss" refers to a family member and not to the proband.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum Experiencer {
+    /// The individual the text is about.
+    #[default]
+    Proband,
+    /// A relative of the proband, with the relation (e.g., "paternal uncle") if it is known.
+    FamilyMember { relation: Option<String> },
+    /// Any other person, e.g., a friend or spouse.
+    Other,
+}
+
+impl fmt::Display for Experiencer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Experiencer::Proband => write!(f, "proband"),
+            Experiencer::FamilyMember { relation: Some(relation) } => write!(f, "family member ({})", relation),
+            Experiencer::FamilyMember { relation: None } => write!(f, "family member"),
+            Experiencer::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// A term of an ontology, e.g., Congenital onset HP:0003577.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OntologyClass {
+    pub id: String,
+    pub label: String,
+}
+
+impl OntologyClass {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+        }
+    }
+}
+
+impl fmt::Display for OntologyClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} [{}]", self.label, self.id)
+    }
+}
+
+/// The age of onset of a finding, e.g., "since age 3 years" or "neonatal hypotonia".
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Onset {
+    pub value: OnsetValue,
+    /// The coordinates of the onset expression within the source text.
+    pub span: Range<usize>,
+}
+
+/// An age of onset, normalized to an ISO 8601 duration or to a descendant of Onset HP:0003674.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum OnsetValue {
+    /// An age such as `P3Y` or `P5M`
+    Age { iso8601duration: String },
+    /// An HPO onset term such as Neonatal onset HP:0003623
+    OntologyClass(OntologyClass),
+}
+
+impl fmt::Display for OnsetValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OnsetValue::Age { iso8601duration } => write!(f, "{}", iso8601duration),
+            OnsetValue::OntologyClass(term) => write!(f, "{}", term),
+        }
+    }
+}
+
+/// A modifier of a finding, e.g., Severe HP:0012828 for "severe intellectual disability".
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Modifier {
+    /// A descendant of Clinical modifier HP:0012823 or of Frequency HP:0040279
+    pub term: OntologyClass,
+    /// The coordinates of the modifier within the source text.
+    pub span: Range<usize>,
+}
+
 /// Text from a sentence that was not parsed as a hit (i.e., "in-between")
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -113,8 +248,7 @@ pub struct FenominalText {
 
 impl fmt::Display for FenominalText {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.text);
-        Ok(())  
+        write!(f, "{}", self.text)
     }
 }
 
@@ -138,7 +272,7 @@ impl fmt::Display for FenominalHitSegment {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum FenominalSegment {
-    Hit(FenominalHitSegment),
+    Hit(Box<FenominalHitSegment>),
     Text(FenominalText),
 }
 
@@ -167,11 +301,11 @@ impl FenominalSegment {
     }
 
      pub fn from_hit(hit: &FenominalHit, matched_text: impl Into<String>) -> Self {
-        FenominalSegment::Hit(
+        FenominalSegment::Hit(Box::new(
             FenominalHitSegment {
                 text: matched_text.into(),
                 hit: hit.clone()
             }
-        )
+        ))
     }
 }
\ No newline at end of file
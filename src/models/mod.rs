@@ -1 +1,6 @@
+pub mod batch;
 pub mod fenominal_model;
+pub mod offset_unit;
+pub mod organ_system_summary;
+pub mod phenotype_summary;
+pub mod provenance;
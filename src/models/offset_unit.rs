@@ -0,0 +1,196 @@
+//! Offset units
+//!
+//! The spans of hits, text segments and sentences are byte offsets into the mined text, which can be used
+//! to slice Rust strings. Other consumers count differently: Python and brat count Unicode scalar values
+//! (characters), and JavaScript string indices are UTF-16 code units, e.g., `é` is 2 bytes, 1 character
+//! and 1 UTF-16 unit, and `💥` is 4 bytes, 1 character and 2 UTF-16 units.
+//!
+//! The `with_offset_unit` methods of [`FenominalHit`], [`FenominalText`] and [`FenominalSentence`]
+//! return a copy whose spans are counted in the requested [`OffsetUnit`], for serialization. They fail
+//! if a span is not a valid byte range of the given text.
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::models::fenominal_model::{FenominalHit, FenominalSegment, FenominalSentence, FenominalText};
+use crate::util::error::FenominalError;
+
+/// The unit of the offsets of serialized spans
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OffsetUnit {
+    /// UTF-8 bytes, as used by Rust (and fenominal)
+    #[default]
+    Bytes,
+    /// Unicode scalar values, as used by Python
+    Chars,
+    /// UTF-16 code units, as used by JavaScript and Java
+    Utf16,
+}
+
+impl OffsetUnit {
+    /// Convert the byte offset `byte` of `text` to this unit. Offsets beyond the end of the text or
+    /// inside a character are rejected, e.g., spans of hits that were mined from another text.
+    pub fn offset(self, text: &str, byte: usize) -> Result<usize, FenominalError> {
+        let prefix = text.get(..byte).ok_or_else(|| FenominalError::invalid_offset(byte, text.len()))?;
+        Ok(match self {
+            OffsetUnit::Bytes => prefix.len(),
+            OffsetUnit::Chars => prefix.chars().count(),
+            OffsetUnit::Utf16 => prefix.encode_utf16().count(),
+        })
+    }
+
+    /// Convert a byte range of `text` to this unit
+    pub fn range(self, text: &str, range: &Range<usize>) -> Result<Range<usize>, FenominalError> {
+        Ok(self.offset(text, range.start)?..self.offset(text, range.end)?)
+    }
+}
+
+impl fmt::Display for OffsetUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OffsetUnit::Bytes => write!(f, "bytes"),
+            OffsetUnit::Chars => write!(f, "chars"),
+            OffsetUnit::Utf16 => write!(f, "utf16"),
+        }
+    }
+}
+
+impl FromStr for OffsetUnit {
+    type Err = FenominalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bytes" | "utf8" | "utf-8" => Ok(OffsetUnit::Bytes),
+            "chars" | "unicode" | "scalars" => Ok(OffsetUnit::Chars),
+            "utf16" | "utf-16" => Ok(OffsetUnit::Utf16),
+            _ => Err(FenominalError::Parsing {
+                reason: format!("unknown offset unit '{}' (expected bytes, chars or utf16)", s),
+            }),
+        }
+    }
+}
+
+impl FenominalHit {
+    /// A copy of the hit with the spans of the hit, its onset and its modifiers in the given unit;
+    /// `text` is the text that the hit was mined from.
+    pub fn with_offset_unit(&self, text: &str, unit: OffsetUnit) -> Result<FenominalHit, FenominalError> {
+        let mut hit = self.clone();
+        hit.span = unit.range(text, &self.span)?;
+        if let Some(onset) = hit.onset.as_mut() {
+            onset.span = unit.range(text, &onset.span)?;
+        }
+        for modifier in hit.modifiers.iter_mut() {
+            modifier.span = unit.range(text, &modifier.span)?;
+        }
+        Ok(hit)
+    }
+}
+
+impl FenominalText {
+    /// A copy of the segment with its span in the given unit; `text` is the text of the span coordinates.
+    pub fn with_offset_unit(&self, text: &str, unit: OffsetUnit) -> Result<FenominalText, FenominalError> {
+        Ok(FenominalText {
+            text: self.text.clone(),
+            span: unit.range(text, &self.span)?,
+        })
+    }
+}
+
+impl FenominalSentence {
+    /// A copy of the sentence with the start and the spans of all segments in the given unit; `text` is
+    /// the text of the sentence coordinates, i.e., the document that the sentence was mined from.
+    pub fn with_offset_unit(&self, text: &str, unit: OffsetUnit) -> Result<FenominalSentence, FenominalError> {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                FenominalSegment::Text(t) => Ok(FenominalSegment::Text(t.with_offset_unit(text, unit)?)),
+                FenominalSegment::Hit(h) => Ok(FenominalSegment::from_hit(&h.hit.with_offset_unit(text, unit)?, h.text.clone())),
+            })
+            .collect::<Result<Vec<_>, FenominalError>>()?;
+        Ok(FenominalSentence {
+            start: unit.offset(text, self.start)?,
+            original_text: self.original_text.clone(),
+            segments,
+            section: self.section.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    const TEXT: &str = "Café 💥 患者 seizures";
+
+    #[rstest]
+    #[case(OffsetUnit::Bytes, 0, 0)]
+    #[case(OffsetUnit::Bytes, 26, 26)]
+    #[case(OffsetUnit::Chars, 5, 4)]
+    #[case(OffsetUnit::Chars, 10, 6)]
+    #[case(OffsetUnit::Chars, 18, 10)]
+    #[case(OffsetUnit::Utf16, 10, 7)]
+    #[case(OffsetUnit::Utf16, 18, 11)]
+    #[case(OffsetUnit::Utf16, 26, 19)]
+    fn test_offset(#[case] unit: OffsetUnit, #[case] byte: usize, #[case] expected: usize) {
+        assert_eq!(expected, unit.offset(TEXT, byte).unwrap());
+    }
+
+    #[rstest]
+    #[case(4)] // the second byte of é
+    #[case(27)] // beyond the end of the text
+    fn test_invalid_offset(#[case] byte: usize) {
+        assert!(OffsetUnit::Chars.offset(TEXT, byte).is_err());
+        let hit = FenominalHit::new("HP:0001250".to_string(), "Seizure", 0..byte, true);
+        assert!(hit.with_offset_unit(TEXT, OffsetUnit::Utf16).is_err());
+    }
+
+    #[test]
+    fn test_hit_with_utf16_offsets() {
+        let start = TEXT.find("seizures").unwrap();
+        let hit = FenominalHit::new("HP:0001250".to_string(), "Seizure", start..TEXT.len(), true);
+        let converted = hit.with_offset_unit(TEXT, OffsetUnit::Utf16).unwrap();
+        let utf16: Vec<u16> = TEXT.encode_utf16().collect();
+        assert_eq!("seizures", String::from_utf16(&utf16[converted.span]).unwrap());
+    }
+
+    #[test]
+    fn test_sentence_with_char_offsets() {
+        let text = format!("Some 💥. {}", TEXT);
+        let start = text.find("Café").unwrap();
+        let seizures = text.find("seizures").unwrap();
+        let hit = FenominalHit::new("HP:0001250".to_string(), "Seizure", seizures..seizures + 8, true);
+        let segments = vec![
+            FenominalSegment::plain_text(&text[start..seizures], start),
+            FenominalSegment::from_hit(&hit, "seizures"),
+        ];
+        let sentence = FenominalSentence::new(start, TEXT, segments);
+        let converted = sentence.with_offset_unit(&text, OffsetUnit::Chars).unwrap();
+        let chars: Vec<char> = text.chars().collect();
+        assert_eq!(8, converted.start);
+        for segment in converted.segments() {
+            let (span, expected) = match segment {
+                FenominalSegment::Text(t) => (t.span.clone(), t.text.clone()),
+                FenominalSegment::Hit(h) => (h.hit.span.clone(), h.text.clone()),
+            };
+            assert_eq!(expected, chars[span].iter().collect::<String>());
+        }
+    }
+
+    #[rstest]
+    #[case("bytes", OffsetUnit::Bytes)]
+    #[case("chars", OffsetUnit::Chars)]
+    #[case("UTF-16", OffsetUnit::Utf16)]
+    fn test_from_str(#[case] s: &str, #[case] expected: OffsetUnit) {
+        assert_eq!(expected, s.parse::<OffsetUnit>().unwrap());
+        assert!("words".parse::<OffsetUnit>().is_err());
+    }
+}
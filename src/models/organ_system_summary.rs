@@ -0,0 +1,147 @@
+//! OrganSystemSummary
+//!
+//! Groups the findings of a document by organ system, i.e., by the top-level categories
+//! below Phenotypic abnormality HP:0000118 that are reported in [`FenominalHit::categories`].
+//! A finding that belongs to several organ systems is listed in each of them.
+
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::models::fenominal_model::{FenominalHit, OntologyClass};
+
+/// The findings of one organ system
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrganSystemEntry {
+    /// A child of Phenotypic abnormality, e.g., Abnormality of the nervous system HP:0000707.
+    pub category: OntologyClass,
+    /// The distinct terms that were observed in this organ system, in order of first mention.
+    pub observed: Vec<OntologyClass>,
+    /// The distinct terms that were excluded (and never observed), in order of first mention.
+    pub excluded: Vec<OntologyClass>,
+    /// The number of mentions of the terms of this organ system.
+    pub mention_count: usize,
+}
+
+impl OrganSystemEntry {
+    /// Is the organ system involved, i.e., was at least one of its findings observed?
+    pub fn is_involved(&self) -> bool {
+        !self.observed.is_empty()
+    }
+}
+
+impl fmt::Display for OrganSystemEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} observed, {} excluded",
+            self.category,
+            self.observed.len(),
+            self.excluded.len()
+        )
+    }
+}
+
+/// The organ systems of the proband's findings, sorted by category ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrganSystemSummary {
+    pub entries: Vec<OrganSystemEntry>,
+}
+
+impl OrganSystemSummary {
+    /// Group the hits by organ system. Hits that refer to family members or other persons and hits
+    /// without a category (e.g., terms of other branches) are ignored.
+    pub fn from_hits(hits: &[FenominalHit]) -> Self {
+        let mut sorted: Vec<&FenominalHit> = hits.iter().filter(|h| h.is_proband()).collect();
+        sorted.sort_by_key(|h| h.span.start);
+        let mut entries: Vec<OrganSystemEntry> = Vec::new();
+        for hit in sorted {
+            let term = OntologyClass::new(hit.term_id.clone(), hit.label.clone());
+            for category in &hit.categories {
+                let i = match entries.iter().position(|e| e.category == *category) {
+                    Some(i) => i,
+                    None => {
+                        entries.push(OrganSystemEntry {
+                            category: category.clone(),
+                            observed: Vec::new(),
+                            excluded: Vec::new(),
+                            mention_count: 0,
+                        });
+                        entries.len() - 1
+                    }
+                };
+                let entry = &mut entries[i];
+                entry.mention_count += 1;
+                if hit.is_observed {
+                    entry.excluded.retain(|t| *t != term);
+                    if !entry.observed.contains(&term) {
+                        entry.observed.push(term.clone());
+                    }
+                } else if !entry.observed.contains(&term) && !entry.excluded.contains(&term) {
+                    entry.excluded.push(term.clone());
+                }
+            }
+        }
+        entries.sort_by(|a, b| a.category.id.cmp(&b.category.id));
+        Self { entries }
+    }
+
+    /// The entry of the given organ system, e.g., `HP:0000707`
+    pub fn get(&self, category_id: &str) -> Option<&OrganSystemEntry> {
+        self.entries.iter().find(|e| e.category.id == category_id)
+    }
+
+    /// The organ systems with at least one observed finding
+    pub fn involved(&self) -> impl Iterator<Item = &OrganSystemEntry> {
+        self.entries.iter().filter(|e| e.is_involved())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OrganSystemEntry> {
+        self.entries.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NERVOUS_SYSTEM: &str = "HP:0000707";
+    const HEAD_OR_NECK: &str = "HP:0000152";
+
+    fn hit(term_id: &str, label: &str, start: usize, is_observed: bool, categories: &[&str]) -> FenominalHit {
+        let mut hit = FenominalHit::new(term_id.to_string(), label, start..start + 5, is_observed);
+        hit.categories = categories.iter().map(|c| OntologyClass::new(*c, "category")).collect();
+        hit
+    }
+
+    #[test]
+    fn test_group_by_organ_system() {
+        let hits = vec![
+            hit("HP:0001250", "Seizure", 0, true, &[NERVOUS_SYSTEM]),
+            hit("HP:0000252", "Microcephaly", 10, false, &[HEAD_OR_NECK, NERVOUS_SYSTEM]),
+            hit("HP:0001250", "Seizure", 20, true, &[NERVOUS_SYSTEM]),
+            hit("HP:0000006", "Autosomal dominant inheritance", 30, true, &[]),
+        ];
+        let summary = OrganSystemSummary::from_hits(&hits);
+        assert_eq!(vec![HEAD_OR_NECK, NERVOUS_SYSTEM], summary.iter().map(|e| e.category.id.as_str()).collect::<Vec<_>>());
+        let nervous_system = summary.get(NERVOUS_SYSTEM).unwrap();
+        assert_eq!(3, nervous_system.mention_count);
+        assert_eq!(vec!["HP:0001250"], nervous_system.observed.iter().map(|t| t.id.as_str()).collect::<Vec<_>>());
+        assert_eq!(vec!["HP:0000252"], nervous_system.excluded.iter().map(|t| t.id.as_str()).collect::<Vec<_>>());
+        assert_eq!(1, summary.involved().count());
+    }
+
+    #[test]
+    fn test_observed_mention_overrides_exclusion() {
+        let hits = vec![
+            hit("HP:0001250", "Seizure", 0, false, &[NERVOUS_SYSTEM]),
+            hit("HP:0001250", "Seizure", 20, true, &[NERVOUS_SYSTEM]),
+        ];
+        let summary = OrganSystemSummary::from_hits(&hits);
+        let entry = summary.get(NERVOUS_SYSTEM).unwrap();
+        assert!(entry.excluded.is_empty());
+        assert_eq!(1, entry.observed.len());
+    }
+}
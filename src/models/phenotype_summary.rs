@@ -0,0 +1,242 @@
+//! PhenotypeSummary
+//!
+//! [`crate::Fenominal::process`] returns one [`FenominalHit`] per mention. A phenotype summary
+//! aggregates the mentions of the proband's findings by term, which is what is usually stored
+//! per patient: one entry per term, with the number of mentions, the first and last positions,
+//! the coordinates of every mention, and a single final status.
+//!
+//! A term may be mentioned as both observed and excluded ("no seizures until age 3 ... seizures").
+//! The final status of such a term is determined by a [`ConflictPolicy`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::models::fenominal_model::{FenominalHit, Modifier, Onset, OntologyClass};
+
+/// How to determine the status of a term that was mentioned both as observed and as excluded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConflictPolicy {
+    /// The term is observed if it was mentioned as observed at least once. A finding that is reported
+    /// anywhere in a note is usually present (e.g., "no seizures until age 3 ... seizures since age 3"),
+    /// while the scope of a negation cue is sometimes overestimated.
+    #[default]
+    ObservedWins,
+    /// The status of the last mention, which often reflects the latest state of the patient.
+    LastMentionWins,
+    /// The status of the majority of the mentions; ties are resolved as observed.
+    Majority,
+}
+
+/// One mention of a term in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Mention {
+    /// The coordinates of the mention within the source text.
+    pub span: Range<usize>,
+    pub is_observed: bool,
+}
+
+/// All mentions of a term in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PhenotypeEntry {
+    pub term: OntologyClass,
+    /// The final observation status (see [`ConflictPolicy`]).
+    pub is_observed: bool,
+    /// Was the term mentioned both as observed and as excluded?
+    pub is_conflicting: bool,
+    pub mention_count: usize,
+    /// Start of the first mention.
+    pub first_position: usize,
+    /// Start of the last mention.
+    pub last_position: usize,
+    /// All mentions, in order of appearance.
+    pub mentions: Vec<Mention>,
+    /// The first onset that was stated for any of the mentions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub onset: Option<Onset>,
+    /// The modifiers of all mentions (each modifier term once).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modifiers: Vec<Modifier>,
+}
+
+impl PhenotypeEntry {
+    fn new(hit: &FenominalHit) -> Self {
+        Self {
+            term: OntologyClass::new(hit.term_id.clone(), hit.label.clone()),
+            is_observed: hit.is_observed,
+            is_conflicting: false,
+            mention_count: 0,
+            first_position: hit.span.start,
+            last_position: hit.span.start,
+            mentions: Vec::new(),
+            onset: None,
+            modifiers: Vec::new(),
+        }
+    }
+
+    fn add_mention(&mut self, hit: &FenominalHit) {
+        self.mentions.push(Mention {
+            span: hit.get_span(),
+            is_observed: hit.is_observed,
+        });
+        if self.onset.is_none() {
+            self.onset = hit.onset.clone();
+        }
+        for modifier in &hit.modifiers {
+            if !self.modifiers.iter().any(|m| m.term == modifier.term) {
+                self.modifiers.push(modifier.clone());
+            }
+        }
+    }
+
+    fn resolve(&mut self, policy: ConflictPolicy) {
+        let observed = self.mentions.iter().filter(|m| m.is_observed).count();
+        self.mention_count = self.mentions.len();
+        self.first_position = self.mentions.first().map_or(0, |m| m.span.start);
+        self.last_position = self.mentions.last().map_or(0, |m| m.span.start);
+        self.is_conflicting = observed > 0 && observed < self.mention_count;
+        self.is_observed = match policy {
+            ConflictPolicy::ObservedWins => observed > 0,
+            ConflictPolicy::LastMentionWins => self.mentions.last().is_some_and(|m| m.is_observed),
+            ConflictPolicy::Majority => 2 * observed >= self.mention_count,
+        };
+    }
+}
+
+impl fmt::Display for PhenotypeEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, {} mention{})",
+            self.term,
+            if self.is_observed { "observed" } else { "excluded" },
+            self.mention_count,
+            if self.mention_count == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// The findings of the proband, one entry per term, in order of first mention.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PhenotypeSummary {
+    pub entries: Vec<PhenotypeEntry>,
+}
+
+impl PhenotypeSummary {
+    /// Aggregate the hits by term ID using the default [`ConflictPolicy::ObservedWins`].
+    pub fn from_hits(hits: &[FenominalHit]) -> Self {
+        Self::from_hits_with_policy(hits, ConflictPolicy::default())
+    }
+
+    /// Aggregate the hits by term ID. Hits that refer to family members or other persons are ignored.
+    pub fn from_hits_with_policy(hits: &[FenominalHit], policy: ConflictPolicy) -> Self {
+        let mut sorted: Vec<&FenominalHit> = hits.iter().filter(|h| h.is_proband()).collect();
+        sorted.sort_by_key(|h| h.span.start);
+        let mut entries: Vec<PhenotypeEntry> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for hit in sorted {
+            let i = *index.entry(hit.term_id.as_str()).or_insert_with(|| {
+                entries.push(PhenotypeEntry::new(hit));
+                entries.len() - 1
+            });
+            entries[i].add_mention(hit);
+        }
+        for entry in entries.iter_mut() {
+            entry.resolve(policy);
+        }
+        Self { entries }
+    }
+
+    /// The entry of the given term, e.g., `HP:0001250`
+    pub fn get(&self, term_id: &str) -> Option<&PhenotypeEntry> {
+        self.entries.iter().find(|e| e.term.id == term_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PhenotypeEntry> {
+        self.entries.iter()
+    }
+
+    pub fn observed(&self) -> impl Iterator<Item = &PhenotypeEntry> {
+        self.entries.iter().filter(|e| e.is_observed)
+    }
+
+    pub fn excluded(&self) -> impl Iterator<Item = &PhenotypeEntry> {
+        self.entries.iter().filter(|e| !e.is_observed)
+    }
+
+    /// Entries whose mentions disagree on the observation status
+    pub fn conflicts(&self) -> impl Iterator<Item = &PhenotypeEntry> {
+        self.entries.iter().filter(|e| e.is_conflicting)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::models::fenominal_model::Experiencer;
+
+    fn hit(term_id: &str, start: usize, is_observed: bool) -> FenominalHit {
+        FenominalHit::new(term_id.to_string(), "Seizure", start..start + 8, is_observed)
+    }
+
+    #[test]
+    fn test_aggregate_by_term() {
+        let hits = vec![hit("HP:0001250", 40, true), hit("HP:0002650", 20, true), hit("HP:0001250", 3, true)];
+        let summary = PhenotypeSummary::from_hits(&hits);
+        assert_eq!(2, summary.len());
+        let seizure = &summary.entries[0];
+        assert_eq!("HP:0001250", seizure.term.id);
+        assert_eq!(2, seizure.mention_count);
+        assert_eq!(3, seizure.first_position);
+        assert_eq!(40, seizure.last_position);
+        assert_eq!(vec![3..11, 40..48], seizure.mentions.iter().map(|m| m.span.clone()).collect::<Vec<_>>());
+        assert!(!seizure.is_conflicting);
+        assert_eq!(Some(1), summary.get("HP:0002650").map(|e| e.mention_count));
+    }
+
+    #[rstest]
+    #[case(ConflictPolicy::ObservedWins, vec![false, true, false], true)]
+    #[case(ConflictPolicy::LastMentionWins, vec![false, true, false], false)]
+    #[case(ConflictPolicy::LastMentionWins, vec![false, false, true], true)]
+    #[case(ConflictPolicy::Majority, vec![false, true, false], false)]
+    #[case(ConflictPolicy::Majority, vec![false, true], true)]
+    fn test_conflict_policy(#[case] policy: ConflictPolicy, #[case] statuses: Vec<bool>, #[case] expected: bool) {
+        let hits: Vec<FenominalHit> = statuses
+            .iter()
+            .enumerate()
+            .map(|(i, &is_observed)| hit("HP:0001250", 10 * i, is_observed))
+            .collect();
+        let summary = PhenotypeSummary::from_hits_with_policy(&hits, policy);
+        let entry = summary.get("HP:0001250").unwrap();
+        assert!(entry.is_conflicting);
+        assert_eq!(expected, entry.is_observed);
+    }
+
+    #[test]
+    fn test_family_members_are_ignored() {
+        let mut relative = hit("HP:0001250", 0, true);
+        relative.experiencer = Experiencer::FamilyMember { relation: None };
+        let summary = PhenotypeSummary::from_hits(&[relative, hit("HP:0001250", 20, false)]);
+        let entry = summary.get("HP:0001250").unwrap();
+        assert_eq!(1, entry.mention_count);
+        assert!(!entry.is_observed);
+        assert_eq!(0, summary.conflicts().count());
+    }
+}
@@ -0,0 +1,193 @@
+//! Provenance
+//!
+//! Mining results depend on the ontology release, the version of fenominal and its configuration.
+//! A [`Provenance`] records all of them together with the ID of the mined document, so that an
+//! annotation set can be reproduced later. [`MiningResult`] wraps the hits of a document in this envelope.
+
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::clinical_section::SectionFilter;
+use crate::models::fenominal_model::FenominalHit;
+use crate::models::offset_unit::OffsetUnit;
+use crate::util::error::FenominalError;
+
+/// How the words of a text are matched to the labels and synonyms of the terms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MatchMode {
+    /// The non-stop words of a window of the sentence must be the same set of (lower-case, ASCII) words as
+    /// a label or synonym, in any order; longer matches take precedence.
+    #[default]
+    TokenSet,
+}
+
+impl fmt::Display for MatchMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatchMode::TokenSet => write!(f, "token_set"),
+        }
+    }
+}
+
+/// The release of a mined ontology
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OntologyVersion {
+    /// e.g., `HP`
+    pub prefix: String,
+    /// e.g., `2025-03-03`
+    pub version: String,
+}
+
+/// The settings of a [`crate::Fenominal`] that affect the hits
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Configuration {
+    pub match_mode: MatchMode,
+    /// FNV-1a (64 bit) hash of the negation triggers, e.g., `fnv1a64:8a3c...`
+    pub negation_lexicon_hash: String,
+    /// The roots of the mined branches of all ontologies
+    pub branches: Vec<String>,
+    /// The roots of the branches whose hits are reported, if restricted
+    pub branch_filter: Option<Vec<String>>,
+    pub section_filter: SectionFilter,
+    pub proband_only: bool,
+    pub structured_forms: bool,
+    pub remove_redundant: bool,
+    /// Labels and synonyms that are not mined
+    pub omittable_labels: Vec<String>,
+    pub min_synonym_length: usize,
+    pub stopwords: Vec<String>,
+    /// The number of tokens before and after a hit that are searched for negation triggers
+    pub negation_window: usize,
+}
+
+/// Where a set of hits came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Provenance {
+    pub document_id: String,
+    pub fenominal_version: String,
+    pub ontologies: Vec<OntologyVersion>,
+    pub configuration: Configuration,
+}
+
+impl Provenance {
+    /// The version of the ontology with the given prefix, e.g., `HP`
+    pub fn ontology_version(&self, prefix: &str) -> Option<&str> {
+        self.ontologies.iter().find(|o| o.prefix == prefix).map(|o| o.version.as_str())
+    }
+
+    /// The provenance as flat key-value pairs, e.g., `("hp_version", "2025-03-03")`, for formats
+    /// without nested metadata. Lists are comma-separated.
+    pub fn to_pairs(&self) -> Vec<(String, String)> {
+        let configuration = &self.configuration;
+        let mut pairs = vec![
+            ("document_id".to_string(), self.document_id.clone()),
+            ("fenominal_version".to_string(), self.fenominal_version.clone()),
+        ];
+        for ontology in &self.ontologies {
+            pairs.push((format!("{}_version", ontology.prefix.to_lowercase()), ontology.version.clone()));
+        }
+        pairs.push(("match_mode".to_string(), configuration.match_mode.to_string()));
+        pairs.push(("negation_lexicon_hash".to_string(), configuration.negation_lexicon_hash.clone()));
+        pairs.push(("branches".to_string(), configuration.branches.join(",")));
+        if let Some(branch_filter) = &configuration.branch_filter {
+            pairs.push(("branch_filter".to_string(), branch_filter.join(",")));
+        }
+        pairs.push(("section_filter".to_string(), configuration.section_filter.to_string()));
+        pairs.push(("proband_only".to_string(), configuration.proband_only.to_string()));
+        pairs.push(("structured_forms".to_string(), configuration.structured_forms.to_string()));
+        pairs.push(("remove_redundant".to_string(), configuration.remove_redundant.to_string()));
+        pairs.push(("omittable_labels".to_string(), configuration.omittable_labels.join(",")));
+        pairs.push(("min_synonym_length".to_string(), configuration.min_synonym_length.to_string()));
+        pairs.push(("stopwords".to_string(), configuration.stopwords.join(",")));
+        pairs.push(("negation_window".to_string(), configuration.negation_window.to_string()));
+        pairs
+    }
+}
+
+/// The hits of a document with their provenance
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MiningResult {
+    pub provenance: Provenance,
+    /// The unit of the spans of the hits
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub offset_unit: OffsetUnit,
+    pub hits: Vec<FenominalHit>,
+}
+
+impl MiningResult {
+    /// Convert the spans of the hits (byte offsets into `text`, the mined text) to the given unit.
+    /// Results whose spans were already converted are returned unchanged. Fails if a span is not a
+    /// valid byte range of `text`.
+    pub fn with_offset_unit(mut self, text: &str, unit: OffsetUnit) -> Result<Self, FenominalError> {
+        if self.offset_unit == OffsetUnit::Bytes {
+            self.hits = self.hits.iter().map(|h| h.with_offset_unit(text, unit)).collect::<Result<_, _>>()?;
+            self.offset_unit = unit;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clinical_section::ClinicalSection;
+
+    fn provenance() -> Provenance {
+        Provenance {
+            document_id: "note-1".to_string(),
+            fenominal_version: "0.2.10".to_string(),
+            ontologies: vec![OntologyVersion { prefix: "HP".to_string(), version: "2025-03-03".to_string() }],
+            configuration: Configuration {
+                match_mode: MatchMode::TokenSet,
+                negation_lexicon_hash: "fnv1a64:0123456789abcdef".to_string(),
+                branches: vec!["HP:0000118".to_string()],
+                branch_filter: None,
+                section_filter: SectionFilter::Exclude(vec![ClinicalSection::FamilyHistory]),
+                proband_only: true,
+                structured_forms: false,
+                remove_redundant: false,
+                omittable_labels: vec!["negative".to_string(), "weakness".to_string()],
+                min_synonym_length: 4,
+                stopwords: vec!["of".to_string(), "the".to_string()],
+                negation_window: 5,
+            },
+        }
+    }
+
+    #[test]
+    fn test_ontology_version() {
+        assert_eq!(Some("2025-03-03"), provenance().ontology_version("HP"));
+        assert_eq!(None, provenance().ontology_version("MONDO"));
+    }
+
+    #[test]
+    fn test_to_pairs() {
+        let pairs = provenance().to_pairs();
+        let value = |key: &str| pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+        assert_eq!(Some("note-1"), value("document_id"));
+        assert_eq!(Some("2025-03-03"), value("hp_version"));
+        assert_eq!(Some("token_set"), value("match_mode"));
+        assert_eq!(Some("HP:0000118"), value("branches"));
+        assert_eq!(Some("exclude family_history"), value("section_filter"));
+        assert_eq!(None, value("branch_filter"));
+        assert_eq!(Some("of,the"), value("stopwords"));
+        assert_eq!(Some("5"), value("negation_window"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_roundtrip() {
+        let json = serde_json::to_value(provenance()).unwrap();
+        assert_eq!("token_set", json["configuration"]["match_mode"]);
+        assert_eq!(serde_json::json!({"exclude": ["family_history"]}), json["configuration"]["section_filter"]);
+        let roundtrip: Provenance = serde_json::from_value(json).unwrap();
+        assert_eq!(provenance(), roundtrip);
+    }
+}
@@ -0,0 +1,236 @@
+//! Options
+//!
+//! The settings of a [`crate::Fenominal`] that can be loaded from a configuration file (see
+//! [`crate::FenominalBuilder`]). Options that are missing from a file keep their default values,
+//! and unknown options are an error, e.g., in TOML:
+//!
+//! ```toml
+//! roots = ["HP:0000118", "HP:0000005"]
+//! min_synonym_length = 4
+//! negation_window = 5
+//! proband_only = true
+//! section_filter = { exclude = ["family_history"] }
+//! ```
+
+use std::collections::HashSet;
+
+use ontolius::TermId;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::clinical_section::SectionFilter;
+use crate::hpo::branches::PHENOTYPIC_ABNORMALITY;
+use crate::stopwords::STOP_WORDS;
+use crate::util::error::FenominalError;
+use crate::util::negex::DEFAULT_NEGATION_WINDOW;
+
+/// Labels and synonyms that are not mined, because they are common words in other contexts
+pub const DEFAULT_OMITTABLE_LABELS: [&str; 2] = ["negative", "weakness"];
+/// Labels and synonyms shorter than this (in bytes) are not mined
+pub const DEFAULT_MIN_SYNONYM_LENGTH: usize = 4;
+
+/// The options of text mining
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct FenominalOptions {
+    /// The roots of the mined branches (by default, Phenotypic abnormality HP:0000118)
+    pub roots: Vec<String>,
+    /// Only report hits of the branches with these roots (all branches if not set)
+    pub branch_filter: Option<Vec<String>>,
+    /// Labels and synonyms that are not mined (case-insensitive)
+    pub omittable_labels: Vec<String>,
+    /// Labels and synonyms shorter than this are not mined
+    pub min_synonym_length: usize,
+    /// Words that are ignored when matching labels and synonyms
+    pub stopwords: Vec<String>,
+    /// The number of words before and after a hit that are searched for negation triggers
+    pub negation_window: usize,
+    pub proband_only: bool,
+    pub section_filter: SectionFilter,
+    pub structured_forms: bool,
+    pub remove_redundant: bool,
+}
+
+impl Default for FenominalOptions {
+    fn default() -> Self {
+        Self {
+            roots: vec![PHENOTYPIC_ABNORMALITY.to_string()],
+            branch_filter: None,
+            omittable_labels: DEFAULT_OMITTABLE_LABELS.iter().map(|s| s.to_string()).collect(),
+            min_synonym_length: DEFAULT_MIN_SYNONYM_LENGTH,
+            stopwords: STOP_WORDS.iter().map(|s| s.to_string()).collect(),
+            negation_window: DEFAULT_NEGATION_WINDOW,
+            proband_only: false,
+            section_filter: SectionFilter::All,
+            structured_forms: false,
+            remove_redundant: false,
+        }
+    }
+}
+
+impl FenominalOptions {
+    /// Check that the options are consistent. Whether the roots are terms of the ontology, and the roots
+    /// of the branch filter are mined, is checked by [`crate::FenominalBuilder::build`].
+    pub fn validate(&self) -> Result<(), FenominalError> {
+        if self.root_ids()?.is_empty() {
+            return Err(FenominalError::invalid_option("roots", "at least one root is required"));
+        }
+        self.branch_filter_ids()?;
+        if self.min_synonym_length == 0 {
+            return Err(FenominalError::invalid_option("min_synonym_length", "must be at least 1"));
+        }
+        if self.negation_window == 0 {
+            return Err(FenominalError::invalid_option("negation_window", "must be at least 1"));
+        }
+        // the stop words are compared to single lower-case tokens
+        if let Some(word) = self.stopwords.iter().find(|w| w.is_empty() || w.contains(char::is_whitespace)) {
+            return Err(FenominalError::invalid_option("stopwords", format!("'{}' is not a single word", word)));
+        }
+        Ok(())
+    }
+
+    /// The roots as term IDs
+    pub fn root_ids(&self) -> Result<Vec<TermId>, FenominalError> {
+        parse_term_ids("roots", &self.roots)
+    }
+
+    /// The branch filter as term IDs
+    pub fn branch_filter_ids(&self) -> Result<Option<Vec<TermId>>, FenominalError> {
+        self.branch_filter
+            .as_ref()
+            .map(|ids| parse_term_ids("branch_filter", ids))
+            .transpose()
+    }
+
+    /// The lower-case omittable labels
+    pub(crate) fn omittable_label_set(&self) -> HashSet<String> {
+        self.omittable_labels.iter().map(|l| l.to_lowercase()).collect()
+    }
+
+    /// The lower-case stop words
+    pub(crate) fn stopword_set(&self) -> HashSet<String> {
+        self.stopwords.iter().map(|w| w.to_lowercase()).collect()
+    }
+
+    /// Read options in JSON format
+    #[cfg(feature = "serde")]
+    pub fn from_json_str(json: &str) -> Result<Self, FenominalError> {
+        let options: Self = serde_json::from_str(json).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not read options: {}", e),
+        })?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Read options in TOML format
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(toml: &str) -> Result<Self, FenominalError> {
+        let options: Self = toml::from_str(toml).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not read options: {}", e),
+        })?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Read options from a `.json` or `.toml` file
+    #[cfg(feature = "serde")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, FenominalError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&contents),
+            _ => Err(FenominalError::Parsing {
+                reason: format!("unsupported options file {} (expected .json or .toml)", path.display()),
+            }),
+        }
+    }
+
+    /// Write the options in JSON format
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, FenominalError> {
+        serde_json::to_string_pretty(self).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not write options: {}", e),
+        })
+    }
+
+    /// Write the options in TOML format
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, FenominalError> {
+        toml::to_string(self).map_err(|e| FenominalError::Parsing {
+            reason: format!("could not write options: {}", e),
+        })
+    }
+}
+
+fn parse_term_ids(option: &str, ids: &[String]) -> Result<Vec<TermId>, FenominalError> {
+    ids.iter()
+        .map(|id| {
+            id.parse::<TermId>()
+                .map_err(|_| FenominalError::invalid_option(option, format!("'{}' is not a term ID", id)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::clinical_section::ClinicalSection;
+
+    #[test]
+    fn test_default_is_valid() {
+        let options = FenominalOptions::default();
+        assert!(options.validate().is_ok());
+        assert_eq!(vec![PHENOTYPIC_ABNORMALITY.clone()], options.root_ids().unwrap());
+        assert_eq!(4, options.min_synonym_length);
+        assert_eq!(5, options.negation_window);
+    }
+
+    #[rstest]
+    #[case::no_roots(FenominalOptions { roots: vec![], ..Default::default() }, "roots")]
+    #[case::invalid_root(FenominalOptions { roots: vec!["seizure".to_string()], ..Default::default() }, "roots")]
+    #[case::invalid_branch_filter(FenominalOptions { branch_filter: Some(vec!["seizure".to_string()]), ..Default::default() }, "branch_filter")]
+    #[case::min_synonym_length(FenominalOptions { min_synonym_length: 0, ..Default::default() }, "min_synonym_length")]
+    #[case::negation_window(FenominalOptions { negation_window: 0, ..Default::default() }, "negation_window")]
+    #[case::stopword(FenominalOptions { stopwords: vec!["of the".to_string()], ..Default::default() }, "stopwords")]
+    fn test_invalid_options(#[case] options: FenominalOptions, #[case] option: &str) {
+        let error = options.validate().unwrap_err().to_string();
+        assert!(error.contains(option), "{}", error);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json() {
+        let options = FenominalOptions::from_json_str(r#"{"negation_window": 3, "section_filter": {"exclude": ["family_history"]}}"#).unwrap();
+        assert_eq!(3, options.negation_window);
+        assert_eq!(SectionFilter::Exclude(vec![ClinicalSection::FamilyHistory]), options.section_filter);
+        assert_eq!(FenominalOptions::default().stopwords, options.stopwords);
+        let roundtrip = FenominalOptions::from_json_str(&options.to_json().unwrap()).unwrap();
+        assert_eq!(options, roundtrip);
+        assert!(FenominalOptions::from_json_str(r#"{"negation_windows": 3}"#).is_err());
+        assert!(FenominalOptions::from_json_str(r#"{"negation_window": 0}"#).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let toml = r#"
+            roots = ["HP:0000118", "HP:0000005"]
+            branch_filter = ["HP:0000005"]
+            omittable_labels = ["negative", "weakness", "pain"]
+            proband_only = true
+            section_filter = { include = ["physical_examination"] }
+        "#;
+        let options = FenominalOptions::from_toml_str(toml).unwrap();
+        assert_eq!(2, options.root_ids().unwrap().len());
+        assert_eq!(3, options.omittable_labels.len());
+        assert!(options.proband_only);
+        assert_eq!(SectionFilter::Include(vec![ClinicalSection::PhysicalExamination]), options.section_filter);
+        let roundtrip = FenominalOptions::from_toml_str(&options.to_toml().unwrap()).unwrap();
+        assert_eq!(options, roundtrip);
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
+use crate::clinical_section::ClinicalSection;
 use crate::simple_token::SimpleToken;
+use crate::util::text_util::Transliteration;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -12,17 +14,23 @@ pub struct SimpleSentence {
     start_pos: usize,
     end_pos: usize,
     tokens: Vec<SimpleToken>,
+    section: Option<ClinicalSection>,
 }
 
 impl SimpleSentence {
+    /// Tokenize the sentence. Words are matched in the ASCII transliteration of the text
+    /// (e.g., "Café" -> "Cafe"), but the tokens keep their original text ("Café"), and the
+    /// token positions are byte offsets into `text`.
     pub fn new(text: &str, start: usize, end: usize) -> Self {
         let mut stokens = Vec::new();
-        for (i, mat) in WORD_PATTERN.find_iter(text).enumerate() {
+        let transliteration = Transliteration::new(text);
+        for (i, mat) in WORD_PATTERN.find_iter(&transliteration.text).enumerate() {
+            let original = transliteration.original_range(mat.start(), mat.end());
             stokens.push(SimpleToken::new(
                 mat.as_str(),
-                mat.as_str(),
-                mat.start(),
-                mat.end(),
+                &text[original.clone()],
+                original.start,
+                original.end,
                 i
             ));
         }
@@ -31,13 +39,25 @@ impl SimpleSentence {
             start_pos: start,
             end_pos: end,
             tokens: stokens,
+            section: None,
         }
     }
 
+    /// Set the section of the clinical note that contains this sentence
+    pub fn with_section(mut self, section: Option<ClinicalSection>) -> Self {
+        self.section = section;
+        self
+    }
+
+    pub fn get_section(&self) -> Option<&ClinicalSection> {
+        self.section.as_ref()
+    }
+
     pub fn get_start_pos(&self) -> usize {
         self.start_pos
     }
 
+    #[allow(dead_code)]
     pub fn get_end_pos(&self) -> usize {
         self.end_pos
     }
@@ -89,7 +109,7 @@ mod test {
     fn test_equality_of_tokens() {
         let ssentence = SimpleSentence::new(SENTENCE1, 0, 2);
         let tokens = &ssentence.tokens;
-        assert_eq!("The", tokens.get(0).unwrap().get_original_token());
+        assert_eq!("The", tokens.first().unwrap().get_original_token());
         assert_eq!("quick", tokens.get(1).unwrap().get_original_token());
         assert_eq!("brown", tokens.get(2).unwrap().get_original_token());
         assert_eq!("fox", tokens.get(3).unwrap().get_original_token());
@@ -99,6 +119,17 @@ mod test {
         assert_eq!("lazy", tokens.get(7).unwrap().get_original_token());
         assert_eq!("dog", tokens.get(8).unwrap().get_original_token());
     }
+    #[test]
+    fn test_tokens_of_non_ascii_text() {
+        let text = "Café-au-lait spots";
+        let ssentence = SimpleSentence::new(text, 0, text.len());
+        let tokens = ssentence.get_tokens();
+        assert_eq!("Café", tokens[0].get_original_token());
+        assert_eq!("cafe", tokens[0].get_lc_original_token());
+        assert_eq!("Café", &text[tokens[0].get_start_pos()..tokens[0].get_end_pos()]);
+        assert_eq!("spots", &text[tokens[3].get_start_pos()..tokens[3].get_end_pos()]);
+    }
+
     #[test]
     fn test_test_positions() {
         let ssentence = SimpleSentence::new(SENTENCE1, 106, 202);
@@ -18,12 +18,14 @@ pub struct SimpleToken {
 }
 
 impl SimpleToken {
+    /// `token` is the form that is matched (e.g., the ASCII transliteration "Cafe"), `orig_token` the text
+    /// of the token ("Café").
     pub fn new<S: Into<String>>(token: S, orig_token: S, start: usize, end: usize, idx: usize) -> Self {
-        let o_token_string: String = orig_token.into();
-        let lc_token = o_token_string.to_lowercase();
+        let token: String = token.into();
+        let lc_token = token.to_lowercase();
         SimpleToken {
-            token: token.into(),
-            original_token: o_token_string,
+            token,
+            original_token: orig_token.into(),
             lowercase_token: lc_token,
             start_pos: start,
             end_pos: end,
@@ -31,6 +33,7 @@ impl SimpleToken {
         }
     }
 
+    #[allow(dead_code)]
     pub fn get_original_token(&self) -> &str {
         &self.original_token
     }
@@ -47,6 +50,7 @@ impl SimpleToken {
         self.end_pos
     }
 
+    #[allow(dead_code)]
     pub fn length(&self) -> usize {
         1 + self.end_pos - self.start_pos
     }
@@ -61,7 +65,7 @@ mod test {
     #[test]
     fn test_lower_case() {
         let tests = vec![("Orange", "orange"), ("Apple", "apple"), ("pear", "pear")];
-        let fake_idx = 42 as usize;
+        let fake_idx = 42_usize;
         for test in tests {
             let st = SimpleToken::new(test.0, test.0, 1, 2, fake_idx);
             assert_eq!(test.1, st.get_lc_original_token());
@@ -0,0 +1,157 @@
+//! Structured form fields
+//!
+//! Intake forms and templated notes list findings as key/value pairs or checklists, e.g.
+//!
+//! ```text
+//! Seizures: No
+//! Hypotonia - yes
+//! Cardiac defect: none
+//! [x] Scoliosis  [ ] Kyphosis
+//! ```
+//!
+//! The observed/excluded status of such a finding is given by the value (or the checkbox) rather than
+//! by free-text negation cues. This module recognizes these layouts line by line and returns the
+//! coordinates of the keys, which are then mapped with the ordinary concept matcher.
+
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// `key: value`, `key - value` or `key = value`, where the value is a yes/no answer
+static KEY_VALUE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^\s*(?:[-*\u{2022}]\s+)?(?P<key>[^:=\[\]]*?[a-z][^:=\[\]]*?)\s*(?::|=|\s[-\u{2013}]\s)\s*(?P<value>yes|y|no|n|none|present|absent|positive|negative|pos|neg|denied|denies|not present|true|false|\+|-)\s*[.,]?\s*$",
+    )
+    .unwrap()
+});
+
+/// `[x]`, `[X]`, `[ ]`, `[]` and the ballot box characters
+static CHECKBOX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[\s*(?P<mark>[xX\u{2713}\u{2714}*])?\s*\]|(?P<box>[\u{2610}\u{2611}\u{2612}])").unwrap());
+
+const OBSERVED_VALUES: [&str; 7] = ["yes", "y", "present", "positive", "pos", "true", "+"];
+
+/// The key of a structured field with the status given by its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FormField {
+    /// Byte coordinates of the key in the document
+    pub key: Range<usize>,
+    pub is_observed: bool,
+}
+
+/// A line of a document that is laid out as a structured form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FormLine {
+    /// Byte coordinates of the line (without the line break)
+    pub line: Range<usize>,
+    pub fields: Vec<FormField>,
+}
+
+/// Find the lines of `text` that consist of key/value fields or checkboxes.
+pub(crate) fn parse_form_lines(text: &str) -> Vec<FormLine> {
+    let mut form_lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let fields = parse_checkbox_line(content, offset).or_else(|| parse_key_value_line(content, offset));
+        if let Some(fields) = fields {
+            form_lines.push(FormLine {
+                line: offset..offset + content.len(),
+                fields,
+            });
+        }
+        offset += line.len();
+    }
+    form_lines
+}
+
+/// `[x] Scoliosis  [ ] Kyphosis`. The line must start with a checkbox; each key extends to the next checkbox.
+fn parse_checkbox_line(line: &str, offset: usize) -> Option<Vec<FormField>> {
+    let boxes: Vec<_> = CHECKBOX.captures_iter(line).collect();
+    let first = boxes.first()?.get(0)?;
+    if !line[..first.start()].trim().is_empty() {
+        return None;
+    }
+    let mut fields = Vec::new();
+    for (i, caps) in boxes.iter().enumerate() {
+        let checkbox = caps.get(0)?;
+        let key_end = boxes.get(i + 1).and_then(|c| c.get(0)).map_or(line.len(), |m| m.start());
+        let is_observed = caps.name("mark").is_some()
+            || caps.name("box").is_some_and(|m| m.as_str() != "\u{2610}");
+        if let Some(key) = trim_key(line, checkbox.end()..key_end) {
+            fields.push(FormField {
+                key: offset + key.start..offset + key.end,
+                is_observed,
+            });
+        }
+    }
+    Some(fields).filter(|f| !f.is_empty())
+}
+
+/// `Seizures: No`. Several fields on one line may be separated by semicolons or vertical bars.
+fn parse_key_value_line(line: &str, offset: usize) -> Option<Vec<FormField>> {
+    let mut fields = Vec::new();
+    let mut part_start = 0;
+    for part in line.split_inclusive([';', '|']) {
+        let content = part.trim_end_matches([';', '|']);
+        let caps = KEY_VALUE.captures(content)?;
+        let key = caps.name("key")?;
+        let value = caps.name("value")?.as_str().to_ascii_lowercase();
+        fields.push(FormField {
+            key: offset + part_start + key.start()..offset + part_start + key.end(),
+            is_observed: OBSERVED_VALUES.contains(&value.as_str()),
+        });
+        part_start += part.len();
+    }
+    Some(fields).filter(|f| !f.is_empty())
+}
+
+/// Remove whitespace and separators around a key
+fn trim_key(line: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let key = &line[range.clone()];
+    let separators: &[char] = &[' ', '\t', ',', ';', '|'];
+    let start = range.start + (key.len() - key.trim_start_matches(separators).len());
+    let end = range.end - (key.len() - key.trim_end_matches(separators).len());
+    Some(start..end).filter(|r| r.start < r.end)
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn fields(text: &str) -> Vec<(&str, bool)> {
+        parse_form_lines(text)
+            .iter()
+            .flat_map(|l| l.fields.iter())
+            .map(|f| (&text[f.key.clone()], f.is_observed))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("Seizures: No", vec![("Seizures", false)])]
+    #[case("Hypotonia - yes", vec![("Hypotonia", true)])]
+    #[case("  - Cardiac defect: none.", vec![("Cardiac defect", false)])]
+    #[case("Scoliosis = +", vec![("Scoliosis", true)])]
+    #[case("[x] Scoliosis  [ ] Kyphosis", vec![("Scoliosis", true), ("Kyphosis", false)])]
+    #[case("\u{2611} Ptosis \u{2610} Myopia", vec![("Ptosis", true), ("Myopia", false)])]
+    #[case("Seizures: no; Hypotonia: yes", vec![("Seizures", false), ("Hypotonia", true)])]
+    #[case("Chief complaint: seizures", vec![])]
+    #[case("The child has no seizures.", vec![])]
+    #[case("Seizures: no; started at age 3", vec![])]
+    fn test_parse_form_lines(#[case] text: &str, #[case] expected: Vec<(&str, bool)>) {
+        assert_eq!(expected, fields(text));
+    }
+
+    #[test]
+    fn test_form_line_coordinates() {
+        let text = "Intake form\nSeizures: No\r\n[x] Scoliosis";
+        let lines = parse_form_lines(text);
+        assert_eq!(2, lines.len());
+        assert_eq!("Seizures: No", &text[lines[0].line.clone()]);
+        assert_eq!("[x] Scoliosis", &text[lines[1].line.clone()]);
+        assert_eq!("Scoliosis", &text[lines[1].fields[0].key.clone()]);
+    }
+}
@@ -10,6 +10,12 @@ pub enum FenominalError {
     #[error("ontology error: {reason}")]
     Ontology { reason: String },
 
+    #[error("mining error: {reason}")]
+    Mining { reason: String },
+
+    #[error("invalid option {option}: {reason}")]
+    Configuration { option: String, reason: String },
+
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -18,10 +24,23 @@ pub enum FenominalError {
 impl FenominalError {
 
     pub fn io_error(reason: impl Into<String>) -> Self {
-        FenominalError::Io(std::io::Error::new(std::io::ErrorKind::Other, reason.into()))
+        FenominalError::Io(std::io::Error::other(reason.into()))
     }
 
 
+    pub fn invalid_option(option: impl Into<String>, reason: impl Into<String>) -> Self {
+        FenominalError::Configuration {
+            option: option.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn invalid_dictionary(reason: impl Into<String>) -> Self {
+        FenominalError::Parsing {
+            reason: format!("invalid mining dictionary: {}", reason.into()),
+        }
+    }
+
     pub fn term_retrieval_error(hpo_id: &TermId) -> Self {
         FenominalError::Ontology {
             reason: format!("could not retrieve term for {}", hpo_id),
@@ -37,4 +56,13 @@ impl FenominalError {
         }
     }
 
+    pub fn invalid_offset(byte: usize, text_len: usize) -> Self {
+        FenominalError::Parsing {
+            reason: format!(
+                "invalid byte offset {} for text length {} (beyond the end or inside a character)",
+                byte, text_len
+            ),
+        }
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,173 @@
+//! experiencer
+//! Heuristics for deciding who experienced a finding (proband, family member, or another person).
+//! The approach follows the ConText algorithm,
+//! Harkema H, et al. ConText: an algorithm for determining negation, experiencer, and temporal status
+//! from clinical reports. J Biomed Inform. 2009;42(5):839-51. PMID:19435614.
+//!
+//! A relation word (e.g., "mother") that precedes a hit within a window of tokens, or that follows
+//! a hit and is only separated from it by connector words ("polydactyly in his paternal uncle"),
+//! assigns the hit to that relation. A family history cue ("Family history: ...") before the hit
+//! assigns it to an unspecified family member.
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::models::fenominal_model::Experiencer;
+
+/// Number of tokens before and after a hit that are searched for relation words
+const EXPERIENCER_WINDOW: usize = 5;
+
+pub struct ExperiencerDetector {
+    family_triggers: HashSet<String>,
+    kin_triggers: HashSet<String>,
+    qualifiers: HashSet<String>,
+    other_triggers: HashSet<String>,
+    history_cues: Vec<Vec<String>>,
+    connectors: HashSet<String>,
+    terminators: HashSet<String>,
+    window: usize,
+}
+
+impl ExperiencerDetector {
+    pub fn from_embedded() -> Self {
+        let data = include_str!("../../data/experiencer_triggers.tsv");
+
+        let mut family = HashSet::new();
+        let mut kin = HashSet::new();
+        let mut qual = HashSet::new();
+        let mut other = HashSet::new();
+        let mut hist = Vec::new();
+        let mut conn = HashSet::new();
+        let mut term = HashSet::new();
+
+        for line in data.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() >= 2 {
+                let phrase = parts[0].to_lowercase();
+                match parts[1] {
+                    "FAM" => { family.insert(phrase); }
+                    "KIN" => { kin.insert(phrase); }
+                    "QUAL" => { qual.insert(phrase); }
+                    "OTH" => { other.insert(phrase); }
+                    "HIST" => hist.push(phrase.split_whitespace().map(str::to_string).collect()),
+                    "CONN" => { conn.insert(phrase); }
+                    "TERM" => { term.insert(phrase); }
+                    _ => {}
+                };
+            }
+        }
+
+        Self {
+            family_triggers: family,
+            kin_triggers: kin,
+            qualifiers: qual,
+            other_triggers: other,
+            history_cues: hist,
+            connectors: conn,
+            terminators: term,
+            window: EXPERIENCER_WINDOW,
+        }
+    }
+
+    /// Determine the experiencer of a hit at a specific range within a (lower-case) token slice.
+    pub fn detect(&self, tokens: &[&str], hit_range: Range<usize>) -> Experiencer {
+        // 1. Look back for a relation word ("his mother has hearing loss")
+        let start_lookback = hit_range.start.saturating_sub(self.window);
+        for i in (start_lookback..hit_range.start).rev() {
+            if self.terminators.contains(tokens[i]) {
+                break;
+            }
+            if let Some(experiencer) = self.experiencer_at(tokens, i) {
+                return experiencer;
+            }
+        }
+
+        // 2. Look forward across connector words ("polydactyly in paternal uncle")
+        let end_lookforward = std::cmp::min(tokens.len(), hit_range.end + self.window);
+        for i in hit_range.end..end_lookforward {
+            if let Some(experiencer) = self.experiencer_at(tokens, i) {
+                return experiencer;
+            }
+            if !self.connectors.contains(tokens[i]) && !self.qualifiers.contains(tokens[i]) {
+                break;
+            }
+        }
+
+        // 3. A family history cue in the same clause before the hit ("Family history: polydactyly")
+        let clause_start = tokens[..hit_range.start]
+            .iter()
+            .rposition(|tk| self.terminators.contains(*tk))
+            .map_or(0, |i| i + 1);
+        if self.has_history_cue(&tokens[clause_start..hit_range.start]) {
+            return Experiencer::FamilyMember { relation: None };
+        }
+
+        Experiencer::Proband
+    }
+
+    /// Check whether the token slice contains a family history cue such as "family history"
+    pub fn has_history_cue(&self, tokens: &[&str]) -> bool {
+        self.history_cues.iter().any(|cue| {
+            tokens
+                .windows(cue.len())
+                .any(|window| window.iter().zip(cue).all(|(tk, c)| *tk == c))
+        })
+    }
+
+    fn starts_with_history_cue(&self, tokens: &[&str]) -> bool {
+        self.history_cues
+            .iter()
+            .any(|cue| tokens.len() >= cue.len() && tokens.iter().zip(cue).all(|(tk, c)| *tk == c))
+    }
+
+    fn experiencer_at(&self, tokens: &[&str], idx: usize) -> Option<Experiencer> {
+        let word = tokens[idx];
+        if self.family_triggers.contains(word) {
+            let relation = match idx.checked_sub(1).map(|j| tokens[j]) {
+                Some(qualifier) if self.qualifiers.contains(qualifier) => format!("{} {}", qualifier, word),
+                _ => word.to_string(),
+            };
+            return Some(Experiencer::FamilyMember { relation: Some(relation) });
+        }
+        if self.kin_triggers.contains(word) && !self.starts_with_history_cue(&tokens[idx..]) {
+            return Some(Experiencer::FamilyMember { relation: None });
+        }
+        if self.other_triggers.contains(word) {
+            return Some(Experiencer::Other);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn detect(sentence: &str, hit: &str) -> Experiencer {
+        let tokens: Vec<&str> = sentence.split_whitespace().collect();
+        let hit_tokens: Vec<&str> = hit.split_whitespace().collect();
+        let start = tokens
+            .windows(hit_tokens.len())
+            .position(|w| w == hit_tokens.as_slice())
+            .expect("hit should be in sentence");
+        ExperiencerDetector::from_embedded().detect(&tokens, start..start + hit_tokens.len())
+    }
+
+    fn family(relation: &str) -> Experiencer {
+        Experiencer::FamilyMember { relation: Some(relation.to_string()) }
+    }
+
+    #[rstest]
+    #[case("his mother has hearing loss", "hearing loss", family("mother"))]
+    #[case("family history polydactyly in paternal uncle", "polydactyly", family("paternal uncle"))]
+    #[case("family history significant for seizures", "seizures", Experiencer::FamilyMember { relation: None })]
+    #[case("his friend has asthma", "asthma", Experiencer::Other)]
+    #[case("the patient has scoliosis", "scoliosis", Experiencer::Proband)]
+    #[case("mother reports that he has seizures", "seizures", Experiencer::Proband)]
+    #[case("hearing loss like his father", "hearing loss", Experiencer::Proband)]
+    #[case("no family history of seizures he has hypotonia", "hypotonia", Experiencer::Proband)]
+    fn test_experiencer(#[case] sentence: &str, #[case] hit: &str, #[case] expected: Experiencer) {
+        assert_eq!(expected, detect(sentence, hit));
+    }
+}
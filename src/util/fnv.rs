@@ -0,0 +1,29 @@
+//! FNV-1a hash (64 bit)
+//!
+//! A simple, stable hash that is used to fingerprint lexicons. Unlike [`std::hash::DefaultHasher`],
+//! its values do not change between Rust releases, so they can be stored.
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(OFFSET_BASIS, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(PRIME))
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    // test vectors of the reference implementation
+    #[rstest]
+    #[case("", 0xcbf29ce484222325)]
+    #[case("a", 0xaf63dc4c8601ec8c)]
+    #[case("foobar", 0x85944171f73967e8)]
+    fn test_fnv1a_64(#[case] input: &str, #[case] expected: u64) {
+        assert_eq!(expected, fnv1a_64(input.as_bytes()));
+    }
+}
@@ -1,3 +1,5 @@
 pub mod error;
+pub mod experiencer;
+pub mod fnv;
 pub mod negex;
 pub mod text_util;
\ No newline at end of file
@@ -5,28 +5,27 @@
 //! J Biomed Inform. 2001;34(5):301-10. PMID:12123149.
 use std::collections::HashSet;
 
-pub enum NegationType {
-    Pre,
-    Post,
-    Terminator,
-}
+use crate::util::fnv::fnv1a_64;
+
+/// The trigger phrases (tab-separated phrase and type)
+const TRIGGERS: &str = include_str!("../../data/negex_triggers.tsv");
+/// The number of tokens before and after a hit that are searched for triggers
+pub const DEFAULT_NEGATION_WINDOW: usize = 5;
 
 pub struct NegEx {
     pre_triggers: HashSet<String>,
     post_triggers: HashSet<String>,
     terminators: HashSet<String>,
+    window: usize,
 }
 
 impl NegEx {
     pub fn from_embedded() -> Self {
-        // Embed the file at compile time
-        let data = include_str!("../../data/negex_triggers.tsv");
-        
         let mut pre = HashSet::new();
         let mut post = HashSet::new();
         let mut term = HashSet::new();
 
-        for line in data.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        for line in TRIGGERS.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
             let parts: Vec<&str> = line.split('\t').collect();
             if parts.len() >= 2 {
                 let phrase = parts[0].to_lowercase();
@@ -43,23 +42,33 @@ impl NegEx {
             pre_triggers: pre,
             post_triggers: post,
             terminators: term,
+            window: DEFAULT_NEGATION_WINDOW,
         }
     }
 
+    /// Search the given number of tokens before and after a hit for triggers
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// The FNV-1a hash of the embedded trigger list, e.g., `fnv1a64:8a3c...`
+    pub fn lexicon_hash() -> String {
+        format!("fnv1a64:{:016x}", fnv1a_64(TRIGGERS.as_bytes()))
+    }
+
     /// Checks if a hit at a specific range is negated within a token slice.
     pub fn is_negated(&self, tokens: &[&str], hit_range: std::ops::Range<usize>) -> bool {
-        // 1. Check Pre-negation (Look back 5 tokens)
-        let start_lookback = hit_range.start.saturating_sub(5);
-        for i in (start_lookback..hit_range.start).rev() {
-            let word = tokens[i];
+        // 1. Check Pre-negation (Look back `window` tokens)
+        let start_lookback = hit_range.start.saturating_sub(self.window);
+        for &word in tokens[start_lookback..hit_range.start].iter().rev() {
             if self.terminators.contains(word) { break; }
             if self.pre_triggers.contains(word) { return true; }
         }
 
-        // 2. Check Post-negation (Look forward 5 tokens)
-        let end_lookforward = std::cmp::min(tokens.len(), hit_range.end + 5);
-        for i in hit_range.end..end_lookforward {
-            let word = tokens[i];
+        // 2. Check Post-negation (Look forward `window` tokens)
+        let end_lookforward = std::cmp::min(tokens.len(), hit_range.end + self.window);
+        for &word in &tokens[hit_range.end..end_lookforward] {
             if self.terminators.contains(word) { break; }
             if self.post_triggers.contains(word) { return true; }
         }
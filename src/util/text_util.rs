@@ -1,10 +1,9 @@
 
-use deunicode::deunicode;
+use deunicode::{deunicode, deunicode_char};
 use regex::Regex;
 use once_cell::sync::Lazy;
 
 // We split on punctuation followed by a space, keeping the punctuation
-static SENTENCE_DELIMSOLD: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?<=[.!?])\s+").unwrap());
 static SENTENCE_DELIMS: Lazy<Regex> = Lazy::new(|| Regex::new(r"([.!?])\s+").unwrap());
 
 // Remove spaces that occur before punctuation marks
@@ -38,7 +37,7 @@ pub fn sanitize(input_text: &str) -> String {
     let fixed_punctuation = PUNCTUATION_GAP.replace_all(&collapsed, "$1");
     let cleaned_text = fixed_punctuation.trim();
 
-    return cleaned_text.to_string();
+    cleaned_text.to_string()
 }
 
 
@@ -61,6 +60,55 @@ pub fn sentence_split(input_text: &str) -> Vec<String> {
 }
 
 
+/// ASCII transliteration of a text (e.g., "Café" -> "Cafe") that keeps track of the original positions.
+pub(crate) struct Transliteration {
+    pub text: String,
+    /// For each byte of `text`, the start of the original character it was derived from
+    starts: Vec<usize>,
+    /// For each byte of `text`, the end of the original character it was derived from
+    ends: Vec<usize>,
+}
+
+impl Transliteration {
+    pub fn new(original: &str) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut starts = Vec::with_capacity(original.len());
+        let mut ends = Vec::with_capacity(original.len());
+        for (i, c) in original.char_indices() {
+            let ascii = if c.is_ascii() {
+                &original[i..i + 1]
+            } else if c.is_alphabetic() {
+                deunicode_char(c).filter(|s| s.is_ascii()).unwrap_or(" ")
+            } else {
+                // symbols (e.g., emoji) are word boundaries rather than words
+                " "
+            };
+            text.push_str(ascii);
+            starts.extend(std::iter::repeat_n(i, ascii.len()));
+            ends.extend(std::iter::repeat_n(i + c.len_utf8(), ascii.len()));
+        }
+        Self { text, starts, ends }
+    }
+
+    /// The original range of the transliterated (non-empty) range `start..end`
+    pub fn original_range(&self, start: usize, end: usize) -> std::ops::Range<usize> {
+        self.starts[start]..self.ends[end - 1]
+    }
+}
+
+
+/// Lower-case words separated by a single space, e.g., "Late-onset" -> "late onset".
+///
+/// Phrases of lexicons (onset terms, modifiers, ...) are stored in this form so that they
+/// can be compared with the joined tokens of a [`crate::simple_sentence::SimpleSentence`].
+pub(crate) fn normalize_phrase(text: &str) -> String {
+    text.split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 
 #[cfg(test)]
 mod test {
@@ -91,4 +139,27 @@ mod test {
         assert_eq!(sanitize(input), expected);
     }
 
+    #[rstest]
+    #[case("Café au lait", "Cafe au lait", 0..4, 0..5)]
+    #[case("Naïve  dæmon", "Naive  daemon", 7..13, 8..14)]
+    #[case("No 💥 seizures", "No   seizures", 5..13, 8..16)]
+    fn test_transliteration(
+        #[case] input: &str,
+        #[case] expected: &str,
+        #[case] range: std::ops::Range<usize>,
+        #[case] original: std::ops::Range<usize>,
+    ) {
+        let transliteration = Transliteration::new(input);
+        assert_eq!(expected, transliteration.text);
+        assert_eq!(original, transliteration.original_range(range.start, range.end));
+    }
+
+    #[rstest]
+    #[case("Late-onset", "late onset")]
+    #[case("Onset in  infancy", "onset in infancy")]
+    #[case("Very rare (<4-1%)", "very rare")]
+    fn test_normalize_phrase(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, normalize_phrase(input));
+    }
+
 }
\ No newline at end of file
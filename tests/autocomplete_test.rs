@@ -3,7 +3,7 @@ mod common;
 use std::sync::Arc;
 
 use ontolius::ontology::csr::FullCsrOntology;
-use fenominal::{AutoCompleter, HpoMatch};
+use fenominal::{AutoCompleter, TermMatch};
 use rstest::rstest;
 use common::hpo;
 
@@ -17,7 +17,7 @@ fn test_macrocephaly_autocomplete(
     let text="macroceph"; // user is searching for Macrocephaly and has entered this so far
     let autocompleter = AutoCompleter::new(hpo);
     let hits_limit = 20;
-    let hits: Vec<HpoMatch> = autocompleter.search_hpo(&text, hits_limit);
+    let hits: Vec<TermMatch> = autocompleter.search(text, hits_limit);
     let expected_hpo_id = "HP:0000256";
     let mut found = false;
     for hit in hits {
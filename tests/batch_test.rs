@@ -0,0 +1,119 @@
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use ontolius::ontology::{HierarchyWalks, OntologyTerms};
+use ontolius::term::simple::SimpleTerm;
+use ontolius::{Identified, TermId};
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+#[rstest]
+fn test_process_batch(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let texts = [
+        "The patient had seizures.",
+        "",
+        "No scoliosis, but café au lait spots.",
+        "Grand mal seizures since age 3 years.",
+    ];
+    let documents: Vec<(String, &str)> = (0..200).map(|i| (format!("note-{}", i), texts[i % texts.len()])).collect();
+    let batch = fenominal.process_batch(documents.iter().map(|(id, text)| (id.as_str(), *text)));
+
+    // the results are in the order of the input
+    assert_eq!(200, batch.documents.len());
+    for ((id, text), result) in documents.iter().zip(&batch.documents) {
+        assert_eq!(id, &result.document_id);
+        assert_eq!(text.len(), result.text_length);
+        assert_eq!(&fenominal.process(text).unwrap(), result.hits.as_ref().unwrap());
+    }
+    let stats = &batch.stats;
+    assert_eq!(200, stats.documents);
+    assert_eq!(0, stats.failed);
+    assert_eq!(documents.iter().map(|(_, t)| t.len()).sum::<usize>(), stats.bytes);
+    let hit_count: usize = batch.documents.iter().map(|d| d.hits.as_ref().unwrap().len()).sum();
+    assert_eq!(hit_count, stats.hits);
+    assert!(stats.threads >= 1);
+    assert!(stats.documents_per_second() > 0.0);
+    assert_eq!(0, batch.errors().count());
+}
+
+#[rstest]
+fn test_empty_batch(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let batch = fenominal.process_batch(Vec::<(String, String)>::new());
+    assert!(batch.documents.is_empty());
+    assert_eq!(0, batch.stats.documents);
+}
+
+/// The HPO with a term that cannot be retrieved once `fail` is set, e.g., after the ontology was
+/// reloaded without it while the fenominal is in use
+struct FailingOntology {
+    hpo: Arc<FullCsrOntology>,
+    missing: TermId,
+    fail: AtomicBool,
+}
+
+impl OntologyTerms<SimpleTerm> for FailingOntology {
+    fn iter_terms<'a>(&'a self) -> impl Iterator<Item = &'a SimpleTerm>
+    where
+        SimpleTerm: 'a,
+    {
+        self.hpo.iter_terms()
+    }
+
+    fn term_by_id<ID: Identified>(&self, id: &ID) -> Option<&SimpleTerm> {
+        if self.fail.load(Ordering::Relaxed) && id.identifier() == &self.missing {
+            return None;
+        }
+        self.hpo.term_by_id(id)
+    }
+}
+
+impl HierarchyWalks for FailingOntology {
+    fn iter_parent_ids<'a, I: Identified>(&'a self, query: &I) -> impl Iterator<Item = &'a TermId> {
+        self.hpo.iter_parent_ids(query)
+    }
+
+    fn iter_child_ids<'a, I: Identified>(&'a self, query: &I) -> impl Iterator<Item = &'a TermId> {
+        self.hpo.iter_child_ids(query)
+    }
+
+    fn iter_ancestor_ids<'a, I: Identified>(&'a self, query: &I) -> impl Iterator<Item = &'a TermId> {
+        self.hpo.iter_ancestor_ids(query)
+    }
+
+    fn iter_descendant_ids<'a, I: Identified>(&'a self, query: &I) -> impl Iterator<Item = &'a TermId> {
+        self.hpo.iter_descendant_ids(query)
+    }
+}
+
+#[rstest]
+fn test_batch_with_failed_document(hpo: Arc<FullCsrOntology>) {
+    let ontology = Arc::new(FailingOntology {
+        hpo,
+        missing: "HP:0001250".parse().unwrap(),
+        fail: AtomicBool::new(false),
+    });
+    let fenominal = Fenominal::new(ontology.clone());
+    ontology.fail.store(true, Ordering::Relaxed);
+    let documents = [
+        ("note-1", "No scoliosis."),
+        ("note-2", "The patient had seizures."),
+        ("note-3", "Café au lait spots were noted."),
+    ];
+    let batch = fenominal.process_batch(documents);
+
+    assert_eq!(3, batch.documents.len());
+    assert_eq!(1, batch.stats.failed);
+    let errors: Vec<(&str, String)> = batch.errors().map(|(id, e)| (id, e.to_string())).collect();
+    assert_eq!(vec![("note-2", "ontology error: could not retrieve term for HP:0001250".to_string())], errors);
+    // the other documents are mined as usual
+    for (i, term_id) in [(0, "HP:0002650"), (2, "HP:0000957")] {
+        let hits = batch.documents[i].hits.as_ref().unwrap();
+        assert_eq!(vec![term_id], hits.iter().map(|h| h.term_id.as_str()).collect::<Vec<_>>());
+    }
+}
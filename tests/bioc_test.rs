@@ -0,0 +1,70 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::bioc::{annotate_collection, BioCCollection, BioCLocation};
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+const COLLECTION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE collection SYSTEM "BioC.dtd">
+<collection>
+  <source>PubMed</source>
+  <date>20250303</date>
+  <key>BioC.key</key>
+  <document>
+    <id>1</id>
+    <passage>
+      <infon key="type">title</infon>
+      <offset>0</offset>
+      <text>Café owner with seizures</text>
+    </passage>
+    <passage>
+      <infon key="type">abstract</infon>
+      <offset>25</offset>
+      <text>The proband had seizures. There was no scoliosis.</text>
+    </passage>
+  </document>
+</collection>
+"#;
+
+/// (identifier, assertion, location, text) of every annotation of the document
+fn annotations(collection: &BioCCollection) -> Vec<(String, String, BioCLocation, String)> {
+    collection.documents[0]
+        .passages
+        .iter()
+        .flat_map(|p| p.annotations.iter())
+        .map(|a| (
+            a.infons["identifier"].clone(),
+            a.infons["assertion"].clone(),
+            a.locations[0],
+            a.text.clone(),
+        ))
+        .collect()
+}
+
+#[rstest]
+fn test_annotate_bioc_xml(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let mut collection = BioCCollection::from_xml_str(COLLECTION).unwrap();
+    annotate_collection(&fenominal, &mut collection).unwrap();
+    let expected = vec![
+        ("HP:0001250".to_string(), "present".to_string(), BioCLocation { offset: 16, length: 8 }, "seizures".to_string()),
+        ("HP:0001250".to_string(), "present".to_string(), BioCLocation { offset: 41, length: 8 }, "seizures".to_string()),
+        ("HP:0002650".to_string(), "absent".to_string(), BioCLocation { offset: 64, length: 9 }, "scoliosis".to_string()),
+    ];
+    assert_eq!(expected, annotations(&collection));
+    let ids: Vec<&str> = collection.documents[0].passages.iter()
+        .flat_map(|p| p.annotations.iter().map(|a| a.id.as_str()))
+        .collect();
+    assert_eq!(vec!["1", "2", "3"], ids);
+    let label = &collection.documents[0].passages[1].annotations[1].infons["label"];
+    assert_eq!("Scoliosis", label);
+    // The annotations survive a round trip through both serializations
+    let xml = collection.to_xml().unwrap();
+    assert_eq!(collection, BioCCollection::from_xml_str(&xml).unwrap());
+    let json = collection.to_json().unwrap();
+    assert_eq!(collection, BioCCollection::from_json_str(&json).unwrap());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::branches::{MODE_OF_INHERITANCE, PHENOTYPIC_ABNORMALITY};
+use fenominal::{AutoCompleter, Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+const TEXT: &str = "Autosomal dominant inheritance was suspected. The boy has scoliosis.";
+
+#[rstest]
+fn test_mine_several_branches(hpo: Arc<FullCsrOntology>) {
+    let roots = [PHENOTYPIC_ABNORMALITY.clone(), MODE_OF_INHERITANCE.clone()];
+    let fenominal = Fenominal::from_roots(hpo.clone(), &roots);
+    let hits: Vec<FenominalHit> = fenominal.process(TEXT).unwrap();
+    assert_eq!(2, hits.len(), "unexpected hits: {:#?}", hits);
+    assert_eq!("HP:0000006", hits[0].term_id);
+    assert!(hits[0].is_in_branch("HP:0000005"));
+    assert_eq!("Mode of inheritance", hits[0].branch.as_ref().unwrap().label);
+    assert_eq!("HP:0002650", hits[1].term_id);
+    assert!(hits[1].is_in_branch("HP:0000118"));
+
+    let fenominal = Fenominal::builder(hpo).roots(&roots).branch_filter(&roots[1..]).build().unwrap();
+    let hits = fenominal.process(TEXT).unwrap();
+    assert_eq!(1, hits.len());
+    assert_eq!("HP:0000006", hits[0].term_id);
+}
+
+#[rstest]
+fn test_default_branch(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(TEXT).unwrap();
+    assert_eq!(1, hits.len());
+    assert_eq!("HP:0002650", hits[0].term_id);
+}
+
+#[rstest]
+fn test_autocomplete_with_roots(hpo: Arc<FullCsrOntology>) {
+    let roots = vec![MODE_OF_INHERITANCE.clone()];
+    let autocompleter = AutoCompleter::from_roots(hpo.clone(), &roots);
+    let matches = autocompleter.search("autosomal dominant inh", 3);
+    assert_eq!("HP:0000006", matches[0].id);
+    let autocompleter = AutoCompleter::new(hpo);
+    assert!(autocompleter.search("HP:0000006", 3).is_empty());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::brat::BratDocument;
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+/// Non-ASCII characters and irregular whitespace before the hits must not shift the offsets
+const TEXT: &str = "Naïve  patient with multiple café au lait spots.\n\nNo   scoliosis.";
+
+#[rstest]
+fn test_brat_offsets_are_characters_of_the_original_text(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(TEXT).unwrap();
+    let document = BratDocument::from_hits(TEXT, &hits);
+    let chars: Vec<char> = TEXT.chars().collect();
+    let entities: Vec<(&str, bool, String)> = document
+        .entities
+        .iter()
+        .map(|e| {
+            let span = &e.spans[0];
+            (e.term.as_ref().unwrap().id.as_str(), e.is_negated, chars[span.clone()].iter().collect())
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("HP:0000957", false, "café au lait spots".to_string()),
+            ("HP:0002650", true, "scoliosis".to_string()),
+        ],
+        entities
+    );
+    assert!(document.to_ann().contains("T1\tHP 29 47\tcafé au lait spots\n"));
+}
+
+#[rstest]
+fn test_brat_gold_standard(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(TEXT).unwrap();
+    let ann = BratDocument::from_hits(TEXT, &hits).to_ann();
+    let gold = BratDocument::parse(&ann).unwrap().to_hits(TEXT).unwrap();
+    let mined: Vec<_> = hits.iter().map(|h| (h.term_id.as_str(), h.span.clone(), h.is_observed)).collect();
+    let curated: Vec<_> = gold.iter().map(|h| (h.term_id.as_str(), h.span.clone(), h.is_observed)).collect();
+    assert_eq!(mined, curated);
+}
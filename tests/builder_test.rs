@@ -0,0 +1,91 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::branches::MODE_OF_INHERITANCE;
+use fenominal::{Fenominal, FenominalError, FenominalOptions};
+use rstest::rstest;
+use common::hpo;
+
+fn assert_send_sync<S: Send + Sync>(_: &S) {}
+
+#[rstest]
+fn test_default_options_match_new(hpo: Arc<FullCsrOntology>) {
+    let text = "The patient had no seizures but had scoliosis and a weakness of the legs.";
+    let built = Fenominal::builder(hpo.clone()).build().unwrap();
+    assert_send_sync(&built);
+    assert_eq!(Fenominal::new(hpo).process(text).unwrap(), built.process(text).unwrap());
+    assert_eq!(&FenominalOptions::default(), built.options());
+}
+
+#[rstest]
+fn test_options(hpo: Arc<FullCsrOntology>) {
+    let text = "No fever, cough, rash, pain or seizures. Scoliosis was noted.";
+    let ids = |fenominal: &Fenominal<FullCsrOntology, _>| -> Vec<(String, bool)> {
+        fenominal.process(text).unwrap().into_iter().map(|h| (h.term_id, h.is_observed)).collect()
+    };
+    let default = Fenominal::builder(hpo.clone()).build().unwrap();
+    assert!(ids(&default).contains(&("HP:0001250".to_string(), true)));
+    assert!(ids(&default).contains(&("HP:0002650".to_string(), true)));
+
+    let configured = Fenominal::builder(hpo)
+        .negation_window(8)
+        .omittable_labels(["negative", "weakness", "scoliosis"])
+        .build()
+        .unwrap();
+    // "No" is six words before "seizures"
+    assert!(ids(&configured).contains(&("HP:0001250".to_string(), false)));
+    assert!(!ids(&configured).iter().any(|(id, _)| id == "HP:0002650"));
+    let configuration = configured.configuration();
+    assert_eq!(8, configuration.negation_window);
+    assert!(configuration.omittable_labels.contains(&"scoliosis".to_string()));
+}
+
+#[rstest]
+fn test_stopwords_are_case_insensitive(hpo: Arc<FullCsrOntology>) {
+    let text = "Abnormality Of The Nervous System was suspected.";
+    let ids = |fenominal: Fenominal<FullCsrOntology, _>| -> Vec<String> {
+        fenominal.process(text).unwrap().into_iter().map(|h| h.term_id).collect()
+    };
+    assert_eq!(vec!["HP:0000707"], ids(Fenominal::new(hpo.clone())));
+    // configured stop words are compared to lower-case tokens
+    let configured = Fenominal::builder(hpo).stopwords(["OF", "The"]).build().unwrap();
+    assert_eq!(vec!["HP:0000707"], ids(configured));
+}
+
+#[rstest]
+fn test_invalid_options(hpo: Arc<FullCsrOntology>) {
+    let unknown_root = Fenominal::builder(hpo.clone()).roots(&["HP:9999999".parse().unwrap()]).build();
+    assert!(matches!(unknown_root, Err(FenominalError::Configuration { ref option, .. }) if option == "roots"));
+    let filter = Fenominal::builder(hpo.clone()).branch_filter(std::slice::from_ref(&MODE_OF_INHERITANCE)).build();
+    assert!(matches!(filter, Err(FenominalError::Configuration { ref option, .. }) if option == "branch_filter"));
+    assert!(Fenominal::builder(hpo).min_synonym_length(0).build().is_err());
+}
+
+#[cfg(feature = "toml")]
+#[rstest]
+fn test_options_from_toml(hpo: Arc<FullCsrOntology>) {
+    let options = FenominalOptions::from_toml_str(
+        r#"
+        roots = ["HP:0000118", "HP:0000005"]
+        branch_filter = ["HP:0000005"]
+        "#,
+    )
+    .unwrap();
+    let fenominal = Arc::new(Fenominal::builder(hpo).options(options).build().unwrap());
+    let text = "Seizures. The disorder shows autosomal dominant inheritance.";
+    // the same instance can be shared between threads
+    let hits = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let fenominal = Arc::clone(&fenominal);
+                scope.spawn(move || fenominal.process(text).unwrap())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>()
+    });
+    assert_eq!(hits[0], hits[1]);
+    let ids: Vec<&str> = hits[0].iter().map(|h| h.term_id.as_str()).collect();
+    assert_eq!(vec!["HP:0000006"], ids);
+}
@@ -19,7 +19,7 @@ fn test_ftt(
 ) {
     let text="Failure to thrive";
     let fenominal = Fenominal::new(hpo);
-    let fenominal_hits: Vec<FenominalHit> = fenominal.process(&text).unwrap();
+    let fenominal_hits: Vec<FenominalHit> = fenominal.process(text).unwrap();
     assert_eq!(1, fenominal_hits.len());
     let fhit = &fenominal_hits[0];
     assert_eq!("Failure to thrive", fhit.label);
@@ -111,8 +111,8 @@ fn test_sentence_miner_1(
     );
     assert_eq!(s3.text_length(), 74);
     let hits = s3.hits();
-    assert_hit(&s3.hits(), "HP:0001639", "Hypertrophic cardiomyopathy");
-    assert_hit(&s3.hits(), "HP:0001642", "Pulmonic stenosis");
+    assert_hit(&hits, "HP:0001639", "Hypertrophic cardiomyopathy");
+    assert_hit(&hits, "HP:0001642", "Pulmonic stenosis");
 }
 
 
@@ -13,8 +13,7 @@ pub fn hpo() -> Arc<FullCsrOntology> {
     let reader = GzDecoder::new(BufReader::new(File::open(path).unwrap()));
     let loader = OntologyLoaderBuilder::new().obographs_parser().build();
     let hpo = loader.load_from_read(reader).unwrap();
-    let hpo = Arc::new(hpo);
-    hpo
+    Arc::new(hpo)
 }
 
 
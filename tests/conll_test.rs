@@ -0,0 +1,30 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::conll::{tag_sentences, to_conll, to_spacy_jsonl, TagScheme, TaggingOptions};
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+const TEXT: &str = "She had grand mal seizures. No scoliosis.";
+
+#[rstest]
+fn test_conll_and_spacy_export(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(TEXT).unwrap();
+    let options = TaggingOptions { scheme: TagScheme::Bioes, term_id_labels: true };
+    let sentences = tag_sentences(&fenominal, TEXT, &hits, options);
+    let conll = to_conll(&sentences);
+    let expected = "-DOCSTART- -X- -X- O\n\n\
+                    She _ _ O\nhad _ _ O\n\
+                    grand _ _ B-HP:0002069\nmal _ _ I-HP:0002069\nseizures _ _ E-HP:0002069\n\n\
+                    No _ _ O\nscoliosis _ _ S-HP:0002650\n\n";
+    assert_eq!(expected, conll);
+    let jsonl = to_spacy_jsonl(&sentences);
+    assert_eq!(
+        "{\"entities\":[[8,26,\"HP:0002069\"]],\"text\":\"She had grand mal seizures.\"}",
+        jsonl.lines().next().unwrap()
+    );
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Experiencer, Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+
+const GENETICS_NOTE: &str = "The proband has scoliosis. His mother has hearing loss. Family history: polydactyly in paternal uncle.";
+
+
+#[rstest]
+fn test_family_members_are_detected(
+    hpo: Arc<FullCsrOntology>
+) {
+    let fenominal = Fenominal::new(hpo);
+    let hits: Vec<FenominalHit> = fenominal.process(GENETICS_NOTE).unwrap();
+    assert_eq!(3, hits.len(), "unexpected hits: {:#?}", hits);
+    assert_eq!("Scoliosis", hits[0].label);
+    assert_eq!(Experiencer::Proband, hits[0].experiencer);
+    assert_eq!(
+        Experiencer::FamilyMember { relation: Some("mother".to_string()) },
+        hits[1].experiencer
+    );
+    assert_eq!("Polydactyly", hits[2].label);
+    assert_eq!(
+        Experiencer::FamilyMember { relation: Some("paternal uncle".to_string()) },
+        hits[2].experiencer
+    );
+}
+
+#[rstest]
+fn test_proband_only(
+    hpo: Arc<FullCsrOntology>
+) {
+    let fenominal = Fenominal::builder(hpo).proband_only(true).build().unwrap();
+    let hits: Vec<FenominalHit> = fenominal.process(GENETICS_NOTE).unwrap();
+    assert_eq!(1, hits.len(), "unexpected hits: {:#?}", hits);
+    assert_eq!("Scoliosis", hits[0].label);
+}
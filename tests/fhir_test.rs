@@ -0,0 +1,29 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::fhir::{Bundle, FhirResource};
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+#[rstest]
+fn test_fhir_bundle_from_text(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let text = "Naïve patient with seizures. No scoliosis.";
+    let hits = fenominal.process(text).unwrap();
+    let bundle = Bundle::from_hits("note-7", text, &hits);
+    let FhirResource::DocumentReference(document) = &bundle.entry[0].resource else {
+        panic!("the first entry must be the note");
+    };
+    assert_eq!("note-7", document.id);
+    let observations: Vec<_> = bundle
+        .observations()
+        .map(|o| (o.code.coding[0].code.as_str(), o.value_boolean, o.span().unwrap()))
+        .collect();
+    assert_eq!(vec![("HP:0001250", true, 19..27), ("HP:0002650", false, 32..41)], observations);
+    for observation in bundle.observations() {
+        assert_eq!("DocumentReference/note-7", observation.derived_from[0].reference);
+    }
+}
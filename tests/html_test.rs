@@ -0,0 +1,23 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::html::render_html;
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+#[rstest]
+fn test_html_report(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let sentences = fenominal
+        .mine_sentences("Seizures & hypotonia were noted. No scoliosis. His brother has <b>seizures</b>.")
+        .unwrap();
+    let html = render_html("Patient 1", &sentences);
+    assert!(html.contains("<mark class=\"observed\" title=\"HP:0001250 Seizure&#10;matched: Seizures\">Seizures</mark> &amp; "));
+    assert!(html.contains("<mark class=\"excluded\" title=\"HP:0002650 Scoliosis&#10;matched: scoliosis\">scoliosis</mark>"));
+    assert!(html.contains("&lt;b&gt;<mark class=\"other\""));
+    assert!(html.contains("<h2>Terms (3)</h2>"));
+    assert!(!html.contains("<b>"));
+}
@@ -34,7 +34,7 @@ fn test_parse_para_1(
     FenominalHit { term_id: "HP:0000072", label: "Hydroureter", span: 725..739, is_observed: true }
     */
     assert_eq!(4, fenominal_hits.len());
-    let hit1 = fenominal_hits.get(0).unwrap();
+    let hit1 = fenominal_hits.first().unwrap();
     let hit2 = fenominal_hits.get(1).unwrap();
     let hit3 = fenominal_hits.get(2).unwrap();
     
@@ -0,0 +1,83 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use ontolius::term::simple::SimpleTerm;
+use fenominal::branches::{MODE_OF_INHERITANCE, PHENOTYPIC_ABNORMALITY};
+use fenominal::{Fenominal, FenominalBuilder, FenominalHit, FenominalOptions, MiningDictionary};
+use rstest::rstest;
+use common::hpo;
+
+const TEXT: &str = "The patient had recurrent seizures and scoliosis. Hearing was normal. His mother has café au lait spots. \
+    The disorder shows autosomal dominant inheritance.";
+
+/// The parts of a hit that do not depend on the ontology: term, label, span, observed and branch
+type HitSummary = (String, String, std::ops::Range<usize>, bool, Option<String>);
+
+fn summary(hits: &[FenominalHit]) -> Vec<HitSummary> {
+    hits.iter()
+        .map(|h| (h.term_id.clone(), h.label.clone(), h.span.clone(), h.is_observed, h.branch.as_ref().map(|b| b.id.clone())))
+        .collect()
+}
+
+#[rstest]
+fn test_dictionary_without_ontology(hpo: Arc<FullCsrOntology>) {
+    let options = FenominalOptions {
+        roots: vec![PHENOTYPIC_ABNORMALITY.to_string(), MODE_OF_INHERITANCE.to_string()],
+        ..Default::default()
+    };
+    let dictionary = MiningDictionary::compile(hpo.as_ref(), &options).unwrap();
+    assert_eq!("HP", dictionary.ontology_version().prefix);
+    assert_eq!("2025-03-03", dictionary.ontology_version().version);
+    assert!(dictionary.len() > 10_000);
+
+    let bytes = dictionary.to_bytes();
+    let loaded = MiningDictionary::from_bytes(&bytes).unwrap();
+    assert_eq!(dictionary, loaded);
+    assert_eq!(dictionary.checksum(), loaded.checksum());
+
+    let expected = Fenominal::builder(hpo).options(options.clone()).build().unwrap().process(TEXT).unwrap();
+    let fenominal: Fenominal<FullCsrOntology, SimpleTerm> =
+        FenominalBuilder::from_dictionary(loaded).options(options).build().unwrap();
+    let hits = fenominal.process(TEXT).unwrap();
+    assert_eq!(summary(&expected), summary(&hits));
+    // "Hearing was normal" excludes Abnormality of hearing
+    assert!(hits.iter().any(|h| h.term_id == "HP:0000364" && !h.is_observed));
+    // onsets, modifiers and organ systems require the ontology
+    assert!(expected.iter().any(|h| !h.categories.is_empty()));
+    assert!(hits.iter().all(|h| h.categories.is_empty() && h.modifiers.is_empty()));
+
+    let provenance = fenominal.provenance("note-1");
+    assert_eq!(Some("2025-03-03"), provenance.ontology_version("HP"));
+}
+
+#[rstest]
+fn test_dictionary_file(hpo: Arc<FullCsrOntology>) {
+    let dictionary = MiningDictionary::compile(hpo.as_ref(), &FenominalOptions::default()).unwrap();
+    let path = std::env::temp_dir().join(format!("fenominal-{}.dict", std::process::id()));
+    dictionary.write_to_path(&path).unwrap();
+    let loaded = MiningDictionary::from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+    let fenominal: Fenominal<FullCsrOntology, SimpleTerm> = Fenominal::from_dictionary(loaded.unwrap()).unwrap();
+    let labels: Vec<String> = fenominal.process("Seizures and scoliosis").unwrap().into_iter().map(|h| h.label).collect();
+    assert_eq!(vec!["Seizure", "Scoliosis"], labels);
+}
+
+#[rstest]
+fn test_dictionary_options(hpo: Arc<FullCsrOntology>) {
+    let dictionary = MiningDictionary::compile(hpo.as_ref(), &FenominalOptions::default()).unwrap();
+    let build = |builder: FenominalBuilder<FullCsrOntology, SimpleTerm>| builder.build().map(|_| ()).unwrap_err().to_string();
+    // the label filter is compiled into the dictionary
+    let error = build(FenominalBuilder::from_dictionary(dictionary.clone()).min_synonym_length(3));
+    assert!(error.contains("min_synonym_length"), "{}", error);
+    let error = build(FenominalBuilder::from_dictionary(dictionary.clone()).remove_redundant(true));
+    assert!(error.contains("remove_redundant"), "{}", error);
+    // stop words and the negation window are applied when mining
+    let fenominal: Fenominal<FullCsrOntology, SimpleTerm> = FenominalBuilder::from_dictionary(dictionary).negation_window(8).build().unwrap();
+    let hits = fenominal.process("No fever, cough, rash, pain or seizures.").unwrap();
+    assert!(hits.iter().any(|h| h.term_id == "HP:0001250" && !h.is_observed));
+
+    let unknown_root = FenominalOptions { roots: vec!["HP:9999999".to_string()], ..Default::default() };
+    assert!(MiningDictionary::compile(hpo.as_ref(), &unknown_root).is_err());
+}
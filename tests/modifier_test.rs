@@ -0,0 +1,38 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+
+#[rstest]
+#[case("He has left-sided ptosis.", "HP:0000508", "ptosis", vec![("HP:0012835", "left-sided")])]
+#[case("Occasional seizures were reported.", "HP:0001250", "seizures", vec![("HP:0040283", "Occasional")])]
+#[case("Rare seizures were reported.", "HP:0001250", "seizures", vec![])]
+#[case("She has severe intellectual disability.", "HP:0010864", "severe intellectual disability", vec![])]
+#[case("Ptosis, bilateral.", "HP:0001488", "Ptosis, bilateral", vec![])]
+#[case("Bilateral cleft lip.", "HP:0100336", "Bilateral cleft lip", vec![])]
+#[case("Mild, bilateral hearing impairment.", "HP:0012712", "Mild, bilateral hearing impairment", vec![("HP:0012832", "bilateral")])]
+fn test_modifiers(
+    hpo: Arc<FullCsrOntology>,
+    #[case] text: &str,
+    #[case] term_id: &str,
+    #[case] matched_text: &str,
+    #[case] modifiers: Vec<(&str, &str)>,
+) {
+    let fenominal = Fenominal::new(hpo);
+    let hits: Vec<FenominalHit> = fenominal.process(text).unwrap();
+    assert_eq!(1, hits.len(), "unexpected hits: {:#?}", hits);
+    let hit = &hits[0];
+    assert_eq!(term_id, hit.term_id, "{:#?}", hit);
+    assert_eq!(matched_text, &text[hit.get_span()]);
+    let found: Vec<(&str, &str)> = hit
+        .modifiers
+        .iter()
+        .map(|m| (m.term.id.as_str(), &text[m.span.clone()]))
+        .collect();
+    assert_eq!(modifiers, found);
+}
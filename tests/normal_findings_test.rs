@@ -0,0 +1,30 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+
+#[rstest]
+#[case("Physical examination revealed normal hearing.", "HP:0000364", "normal hearing")]
+#[case("Her hearing was normal.", "HP:0000364", "hearing was normal")]
+#[case("He had a normal head circumference.", "HP:0000240", "normal head circumference")]
+#[case("Head size was normal.", "HP:0000240", "Head size was normal")]
+#[case("Echocardiogram normal.", "HP:0001627", "Echocardiogram normal")]
+fn test_normal_finding_is_excluded(
+    hpo: Arc<FullCsrOntology>,
+    #[case] text: &str,
+    #[case] term_id: &str,
+    #[case] matched_text: &str,
+) {
+    let fenominal = Fenominal::new(hpo);
+    let hits: Vec<FenominalHit> = fenominal.process(text).unwrap();
+    assert_eq!(1, hits.len(), "unexpected hits: {:#?}", hits);
+    let hit = &hits[0];
+    assert_eq!(term_id, hit.term_id);
+    assert!(!hit.is_observed);
+    assert_eq!(matched_text, &text[hit.get_span()]);
+}
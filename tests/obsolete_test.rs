@@ -0,0 +1,108 @@
+mod common;
+
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+use flate2::bufread::GzDecoder;
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{AutoCompleter, Fenominal, FenominalHit, TermIdResolver, TermIdStatus};
+use rstest::{fixture, rstest};
+use common::hpo;
+
+/// Clitoromegaly HP:0000057 is obsolete and replaced by HP:0008665
+const OBSOLETE_CLITOROMEGALY: &str = "HP:0000057";
+/// An alternative ID of Seizure HP:0001250
+const SEIZURE_ALT_ID: &str = "HP:0001275";
+
+#[fixture]
+fn resolver() -> TermIdResolver {
+    let path = "resources/hp.v2025-03-03.json.gz";
+    let reader = GzDecoder::new(BufReader::new(File::open(path).unwrap()));
+    TermIdResolver::from_obographs(reader).unwrap()
+}
+
+#[rstest]
+fn test_resolve_outdated_ids(resolver: TermIdResolver) {
+    assert_eq!(TermIdStatus::Current, resolver.status("HP:0001250"));
+    assert_eq!(
+        TermIdStatus::AlternativeId { primary: "HP:0001250".to_string() },
+        resolver.status(SEIZURE_ALT_ID)
+    );
+    assert!(matches!(
+        resolver.status(OBSOLETE_CLITOROMEGALY),
+        TermIdStatus::Obsolete { replaced_by: Some(ref id), .. } if id == "HP:0008665"
+    ));
+    assert_eq!("Clitoral hypertrophy", resolver.resolve(OBSOLETE_CLITOROMEGALY).unwrap().label);
+}
+
+#[rstest]
+fn test_report_and_remap_hits(resolver: TermIdResolver) {
+    let mut hits = vec![
+        FenominalHit::new("HP:0001250".to_string(), "Seizure", 0..8, true),
+        FenominalHit::new(SEIZURE_ALT_ID.to_string(), "Seizures", 10..18, true),
+        FenominalHit::new(OBSOLETE_CLITOROMEGALY.to_string(), "Clitoromegaly", 20..33, false),
+    ];
+    let outdated = resolver.find_outdated_ids(hits.iter().map(|h| h.term_id.as_str()));
+    assert_eq!(vec![SEIZURE_ALT_ID, OBSOLETE_CLITOROMEGALY], outdated.iter().map(|o| o.id.as_str()).collect::<Vec<_>>());
+
+    let remapped = resolver.remap_hits(&mut hits);
+    assert_eq!(outdated, remapped);
+    let ids: Vec<&str> = hits.iter().map(|h| h.term_id.as_str()).collect();
+    assert_eq!(vec!["HP:0001250", "HP:0001250", "HP:0008665"], ids);
+    assert_eq!("Seizure", hits[1].label);
+    assert!(!hits[2].is_observed);
+}
+
+#[rstest]
+fn test_autocomplete_outdated_ids(hpo: Arc<FullCsrOntology>, resolver: TermIdResolver) {
+    let autocompleter = AutoCompleter::new(hpo);
+    assert_eq!("HP:0001250", autocompleter.search(SEIZURE_ALT_ID, 3)[0].id);
+    let autocompleter = autocompleter.with_id_resolver(resolver);
+    let matches = autocompleter.search(OBSOLETE_CLITOROMEGALY, 3);
+    assert_eq!(1, matches.len());
+    assert_eq!("HP:0008665", matches[0].id);
+}
+
+/// A (hypothetical) later release in which Seizure HP:0001250 is obsolete and replaced by the given term
+fn next_release(replaced_by: &str, label: &str) -> TermIdResolver {
+    let iri = format!("http://purl.obolibrary.org/obo/{}", replaced_by.replace(':', "_"));
+    let json = format!(r#"{{"graphs":[{{"nodes":[
+        {{"id":"http://purl.obolibrary.org/obo/HP_0001250","lbl":"obsolete Seizure","type":"CLASS",
+         "meta":{{"deprecated":true,"basicPropertyValues":[{{"pred":"http://purl.obolibrary.org/obo/IAO_0100001","val":"{replaced_by}"}}]}}}},
+        {{"id":"{iri}","lbl":"{label}","type":"CLASS"}},
+        {{"id":"http://purl.obolibrary.org/obo/HP_0002650","lbl":"Scoliosis","type":"CLASS"}}]}}]}}"#);
+    TermIdResolver::from_obographs(json.as_bytes()).unwrap()
+}
+
+#[rstest]
+fn test_fenominal_remaps_outdated_hits(hpo: Arc<FullCsrOntology>) {
+    let resolver = next_release("HP:9000001", "Epileptic seizure");
+    let fenominal = Fenominal::builder(hpo).id_resolver(resolver).build().unwrap();
+    let hits = fenominal.process("Seizures and scoliosis").unwrap();
+    let terms: Vec<(&str, &str)> = hits.iter().map(|h| (h.term_id.as_str(), h.label.as_str())).collect();
+    assert_eq!(vec![("HP:9000001", "Epileptic seizure"), ("HP:0002650", "Scoliosis")], terms);
+    // the replacing term is not in the ontology of the mapper
+    assert!(hits[0].branch.is_none() && hits[0].categories.is_empty());
+    assert!(hits[1].branch.is_some());
+}
+
+#[rstest]
+fn test_mine_sentences_remaps_outdated_hits(hpo: Arc<FullCsrOntology>) {
+    let resolver = next_release("HP:9000001", "Epileptic seizure");
+    let fenominal = Fenominal::builder(hpo).id_resolver(resolver).build().unwrap();
+    let sentences = fenominal.mine_sentences("Seizures were noted. No scoliosis.").unwrap();
+    let ids: Vec<&str> = sentences.iter().flat_map(|s| s.hit_iter()).map(|h| h.term_id.as_str()).collect();
+    assert_eq!(vec!["HP:9000001", "HP:0002650"], ids);
+}
+
+#[rstest]
+fn test_remapped_hits_are_classified_anew(hpo: Arc<FullCsrOntology>) {
+    let resolver = next_release("HP:0001252", "Hypotonia");
+    let fenominal = Fenominal::builder(hpo).id_resolver(resolver).build().unwrap();
+    let hits = fenominal.process("Seizures").unwrap();
+    assert_eq!("HP:0001252", hits[0].term_id);
+    // Musculoskeletal system HP:0033127 instead of Nervous system HP:0000707
+    let categories: Vec<&str> = hits[0].categories.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(vec!["HP:0033127"], categories);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Fenominal, FenominalSegment, FenominalSentence, OffsetUnit};
+use rstest::rstest;
+use serde_json::Value;
+use common::hpo;
+
+const TEXT: &str = "患者 💥 presented with café au lait spots and seizures.";
+
+/// The substring of `text` between the offsets `begin` and `end` in the given unit
+fn slice(text: &str, unit: OffsetUnit, begin: usize, end: usize) -> String {
+    match unit {
+        OffsetUnit::Bytes => text[begin..end].to_string(),
+        OffsetUnit::Chars => text.chars().skip(begin).take(end - begin).collect(),
+        OffsetUnit::Utf16 => {
+            let units: Vec<u16> = text.encode_utf16().collect();
+            String::from_utf16(&units[begin..end]).unwrap()
+        }
+    }
+}
+
+#[rstest]
+fn test_hit_offsets(hpo: Arc<FullCsrOntology>, #[values(OffsetUnit::Bytes, OffsetUnit::Chars, OffsetUnit::Utf16)] unit: OffsetUnit) {
+    let fenominal = Fenominal::new(hpo);
+    let result = fenominal.mine_document("note-1", TEXT).unwrap().with_offset_unit(TEXT, unit).unwrap();
+    assert_eq!(unit, result.offset_unit);
+    let matched: Vec<String> = result.hits.iter().map(|h| slice(TEXT, unit, h.span.start, h.span.end)).collect();
+    assert_eq!(vec!["café au lait spots", "seizures"], matched);
+
+    // the serialized spans can be used as, e.g., JavaScript string indices
+    let value: Value = serde_json::to_value(&result).unwrap();
+    assert_eq!(unit.to_string(), value["offset_unit"]);
+    let span = &value["hits"][0]["span"];
+    let begin = span["start"].as_u64().unwrap() as usize;
+    let end = span["end"].as_u64().unwrap() as usize;
+    assert_eq!("café au lait spots", slice(TEXT, unit, begin, end));
+}
+
+#[rstest]
+fn test_sentence_offsets(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(TEXT).unwrap();
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for hit in &hits {
+        segments.push(FenominalSegment::plain_text(&TEXT[last..hit.span.start], last));
+        segments.push(FenominalSegment::from_hit(hit, &TEXT[hit.span.clone()]));
+        last = hit.span.end;
+    }
+    segments.push(FenominalSegment::plain_text(&TEXT[last..], last));
+    let sentence = FenominalSentence::new(0, TEXT, segments);
+
+    let converted = sentence.with_offset_unit(TEXT, OffsetUnit::Utf16).unwrap();
+    for segment in converted.segments() {
+        let (span, text) = match segment {
+            FenominalSegment::Text(t) => (t.span.clone(), &t.text),
+            FenominalSegment::Hit(h) => (h.hit.span.clone(), &h.text),
+        };
+        assert_eq!(*text, slice(TEXT, OffsetUnit::Utf16, span.start, span.end));
+    }
+    // 患者 and é are one UTF-16 unit each, 💥 is a surrogate pair
+    let FenominalSegment::Hit(cafe) = &converted.segments()[1] else {
+        panic!("expected a hit");
+    };
+    assert_eq!(21..39, cafe.hit.span);
+    assert_eq!(TEXT.encode_utf16().count(), converted.segments().last().map_or(0, |s| match s {
+        FenominalSegment::Text(t) => t.span.end,
+        FenominalSegment::Hit(h) => h.hit.span.end,
+    }));
+}
+
+#[rstest]
+fn test_process_spans_are_byte_offsets(hpo: Arc<FullCsrOntology>) {
+    // the non-ASCII characters of the first sentences must not shift the spans of later sentences
+    let text = "Le patient a été vu à l'hôpital. 患者 💥 was seen. He had seizures. No scoliosis.";
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(text).unwrap();
+    let matched: Vec<&str> = hits.iter().map(|h| &text[h.span.clone()]).collect();
+    assert_eq!(vec!["seizures", "scoliosis"], matched);
+}
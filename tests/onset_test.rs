@@ -0,0 +1,44 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Fenominal, FenominalHit, OnsetValue};
+use rstest::rstest;
+use common::hpo;
+
+
+#[rstest]
+#[case("Seizures since age 3 years.", "HP:0001250", "since age 3 years", "P3Y")]
+#[case("Hypotonia was noted at the age of 2 months.", "HP:0001252", "at the age of 2 months", "P2M")]
+#[case("He has a congenital heart defect.", "HP:0001627", "congenital", "HP:0003577")]
+#[case("Neonatal hypotonia was noted.", "HP:0001319", "Neonatal", "HP:0003623")]
+#[case("Scoliosis with onset in adolescence.", "HP:0002650", "onset in adolescence", "HP:0003621")]
+#[case("Ataxia since infancy.", "HP:0001251", "since infancy", "HP:0003593")]
+fn test_onset(
+    hpo: Arc<FullCsrOntology>,
+    #[case] text: &str,
+    #[case] term_id: &str,
+    #[case] onset_text: &str,
+    #[case] onset_value: &str,
+) {
+    let fenominal = Fenominal::new(hpo);
+    let hits: Vec<FenominalHit> = fenominal.process(text).unwrap();
+    assert_eq!(1, hits.len(), "unexpected hits: {:#?}", hits);
+    let hit = &hits[0];
+    assert_eq!(term_id, hit.term_id);
+    let onset = hit.onset.as_ref().expect("hit should have an onset");
+    assert_eq!(onset_text, &text[onset.span.clone()]);
+    match &onset.value {
+        OnsetValue::Age { iso8601duration } => assert_eq!(onset_value, iso8601duration),
+        OnsetValue::OntologyClass(term) => assert_eq!(onset_value, term.id),
+    }
+}
+
+#[rstest]
+fn test_no_onset(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process("A 3-year-old boy with scoliosis.").unwrap();
+    assert_eq!(1, hits.len());
+    assert_eq!(None, hits[0].onset);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::io::OntologyLoaderBuilder;
+use ontolius::ontology::csr::FullCsrOntology;
+use ontolius::TermId;
+use fenominal::{AutoCompleter, Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+fn load_ontology(path: &str) -> Arc<FullCsrOntology> {
+    let loader = OntologyLoaderBuilder::new().obographs_parser().build();
+    Arc::new(loader.load_from_path(path).unwrap())
+}
+
+fn root(curie: &str) -> Vec<TermId> {
+    vec![curie.parse().unwrap()]
+}
+
+#[rstest]
+fn test_mine_other_ontology() {
+    let mondo = load_ontology("tests/data/mondo_mini.json");
+    let fenominal = Fenominal::from_roots(mondo, &root("MONDO:0000001"));
+    let hits: Vec<FenominalHit> = fenominal.process("Marfan syndrome was diagnosed at age 12 years.").unwrap();
+    assert_eq!(1, hits.len(), "unexpected hits: {:#?}", hits);
+    assert_eq!("MONDO:0007947", hits[0].term_id);
+    assert_eq!("Marfan syndrome", hits[0].label);
+    assert_eq!(Some("MONDO".to_string()), hits[0].ontology);
+    assert!(hits[0].is_in_branch("MONDO:0000001"));
+    assert!(hits[0].onset.is_none());
+}
+
+#[rstest]
+fn test_mine_several_ontologies(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::builder(hpo)
+        .ontology(load_ontology("tests/data/mondo_mini.json"), &root("MONDO:0000001"))
+        .ontology(load_ontology("tests/data/maxo_mini.json"), &root("MAXO:0000001"))
+        .build()
+        .unwrap();
+    let text = "The patient has Marfan syndrome and scoliosis. Echocardiography was performed.";
+    let hits = fenominal.process(text).unwrap();
+    let found: Vec<(&str, Option<&str>)> = hits
+        .iter()
+        .map(|h| (h.term_id.as_str(), h.ontology.as_deref()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("MONDO:0007947", Some("MONDO")),
+            ("HP:0002650", Some("HP")),
+            ("MAXO:0001175", Some("MAXO")),
+        ],
+        found
+    );
+    assert_eq!("Marfan syndrome", &text[hits[0].span.clone()]);
+}
+
+#[rstest]
+fn test_autocomplete_other_ontology(hpo: Arc<FullCsrOntology>) {
+    let autocompleter = AutoCompleter::new(hpo)
+        .with_ontology(load_ontology("tests/data/mondo_mini.json"), &root("MONDO:0000001"));
+    let matches = autocompleter.search("MONDO:0007947", 3);
+    assert_eq!(1, matches.len());
+    assert_eq!("Marfan syndrome", matches[0].label);
+    let matches = autocompleter.search("noonan syn", 3);
+    assert_eq!("MONDO:0018997", matches[0].id);
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::branches::{MODE_OF_INHERITANCE, PHENOTYPIC_ABNORMALITY};
+use fenominal::Fenominal;
+use rstest::rstest;
+use common::hpo;
+
+const NERVOUS_SYSTEM: &str = "HP:0000707";
+const HEAD_OR_NECK: &str = "HP:0000152";
+const MUSCULOSKELETAL: &str = "HP:0033127";
+const CARDIOVASCULAR: &str = "HP:0001626";
+
+#[rstest]
+fn test_hit_categories(hpo: Arc<FullCsrOntology>) {
+    let roots = [PHENOTYPIC_ABNORMALITY.clone(), MODE_OF_INHERITANCE.clone()];
+    let fenominal = Fenominal::from_roots(hpo, &roots);
+    let hits = fenominal.process("Macrocephaly and seizures. Autosomal dominant inheritance.").unwrap();
+    let categories: Vec<Vec<&str>> = hits
+        .iter()
+        .map(|h| h.categories.iter().map(|c| c.id.as_str()).collect())
+        .collect();
+    assert_eq!(
+        vec![vec![HEAD_OR_NECK, MUSCULOSKELETAL], vec![NERVOUS_SYSTEM], vec![]],
+        categories
+    );
+    assert_eq!("Abnormality of the nervous system", hits[1].categories[0].label);
+}
+
+#[rstest]
+fn test_summarize_organ_systems(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let text = "The girl has seizures and global developmental delay. No cardiomyopathy. Scoliosis.";
+    let summary = fenominal.summarize_organ_systems(text).unwrap();
+    let involved: Vec<&str> = summary.involved().map(|e| e.category.id.as_str()).collect();
+    assert_eq!(vec![NERVOUS_SYSTEM, MUSCULOSKELETAL], involved);
+    let nervous_system = summary.get(NERVOUS_SYSTEM).unwrap();
+    assert_eq!(2, nervous_system.observed.len());
+    let cardiovascular = summary.get(CARDIOVASCULAR).unwrap();
+    assert!(!cardiovascular.is_involved());
+    assert_eq!("HP:0001638", cardiovascular.excluded[0].id);
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use ontolius::ontology::MetadataAware;
+use fenominal::export::phenopacket::Phenopacket;
+use fenominal::Fenominal;
+use rstest::rstest;
+use serde_json::{json, Value};
+use common::hpo;
+
+#[rstest]
+fn test_phenopacket_from_text(hpo: Arc<FullCsrOntology>) {
+    let hpo_version = hpo.version().to_string();
+    let fenominal = Fenominal::new(hpo);
+    let text = "Seizures since age 3 years. No scoliosis. Mild, bilateral hearing impairment. Seizures recur.";
+    let hits = fenominal.process(text).unwrap();
+    let phenopacket = Phenopacket::from_hits("note-1", &hits, &hpo_version);
+    let value: Value = serde_json::to_value(&phenopacket).unwrap();
+    assert_eq!(
+        json!([
+            {
+                "type": {"id": "HP:0001250", "label": "Seizure"},
+                "onset": {"age": {"iso8601duration": "P3Y"}}
+            },
+            {
+                "type": {"id": "HP:0002650", "label": "Scoliosis"},
+                "excluded": true
+            },
+            {
+                "type": {"id": "HP:0012712", "label": "Mild hearing impairment"},
+                "modifiers": [{"id": "HP:0012832", "label": "Bilateral"}]
+            }
+        ]),
+        value["phenotypicFeatures"]
+    );
+    assert_eq!("2025-03-03", value["metaData"]["resources"][0]["version"]);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::brat::BratDocument;
+use fenominal::export::conll::{tag_sentences, to_conll_with_provenance, to_spacy_jsonl_with_provenance, TaggingOptions};
+use fenominal::export::fhir::Bundle;
+use fenominal::export::html::render_html_with_provenance;
+use fenominal::export::phenopacket::Phenopacket;
+use fenominal::export::pubannotation::PubAnnotation;
+use fenominal::export::table::write_provenance;
+use fenominal::{Fenominal, MatchMode, MiningResult};
+use rstest::rstest;
+use serde_json::Value;
+use common::hpo;
+
+const TEXT: &str = "The patient had seizures. No scoliosis.";
+
+#[rstest]
+fn test_mine_document(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::builder(hpo).proband_only(true).build().unwrap();
+    let result = fenominal.mine_document("note-1", TEXT).unwrap();
+    assert_eq!(fenominal.process(TEXT).unwrap(), result.hits);
+
+    let provenance = &result.provenance;
+    assert_eq!("note-1", provenance.document_id);
+    assert_eq!(env!("CARGO_PKG_VERSION"), provenance.fenominal_version);
+    assert_eq!(Some("2025-03-03"), provenance.ontology_version("HP"));
+    let configuration = &provenance.configuration;
+    assert_eq!(MatchMode::TokenSet, configuration.match_mode);
+    assert!(configuration.negation_lexicon_hash.starts_with("fnv1a64:"));
+    assert_eq!(24, configuration.negation_lexicon_hash.len());
+    assert!(configuration.branches.contains(&"HP:0000118".to_string()));
+    assert!(configuration.proband_only);
+
+    let json = serde_json::to_string(&result).unwrap();
+    let roundtrip: MiningResult = serde_json::from_str(&json).unwrap();
+    assert_eq!(result, roundtrip);
+}
+
+#[rstest]
+fn test_provenance_in_output_formats(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let MiningResult { provenance, hits, .. } = fenominal.mine_document("note-1", TEXT).unwrap();
+    let hash = provenance.configuration.negation_lexicon_hash.clone();
+
+    let phenopacket = Phenopacket::from_hits("note-1", &hits, "unknown").with_provenance(&provenance);
+    let value: Value = serde_json::to_value(&phenopacket).unwrap();
+    assert_eq!("2025-03-03", value["metaData"]["resources"][0]["version"]);
+    assert_eq!("note-1", value["metaData"]["externalReferences"][0]["id"]);
+    assert!(value["metaData"]["externalReferences"][0]["description"].as_str().unwrap().contains(&hash));
+
+    let bundle = Bundle::from_hits("note-1", TEXT, &hits).with_provenance(&provenance);
+    let fhir_provenance = bundle.provenance().unwrap();
+    assert_eq!(2, fhir_provenance.target.len());
+    assert_eq!(Some("2025-03-03"), fhir_provenance.value("hp_version"));
+    assert_eq!(Some(hash.as_str()), fhir_provenance.value("negation_lexicon_hash"));
+
+    let document = PubAnnotation::from_hits(TEXT, &hits).with_provenance(provenance.clone());
+    let value: Value = serde_json::to_value(&document).unwrap();
+    assert_eq!("token_set", value["provenance"]["configuration"]["match_mode"]);
+
+    let sentences = fenominal.mine_sentences(TEXT).unwrap();
+    let html = render_html_with_provenance("note-1", &sentences, &provenance);
+    assert!(html.contains("<dt>hp_version</dt><dd>2025-03-03</dd>"));
+
+    let mut table = Vec::new();
+    write_provenance(&mut table, &provenance).unwrap();
+    let table = String::from_utf8(table).unwrap();
+    assert!(table.starts_with("# document_id: note-1\n"));
+    assert!(table.contains("# hp_version: 2025-03-03\n"));
+
+    let ann = BratDocument::from_hits(TEXT, &hits).to_ann_with_provenance(&provenance);
+    assert!(ann.contains("#1\tAnnotatorNotes T1\tdocument_id: note-1\n"));
+    assert!(ann.contains("\tAnnotatorNotes T1\thp_version: 2025-03-03\n"));
+    assert_eq!(BratDocument::from_hits(TEXT, &hits), BratDocument::parse(&ann).unwrap());
+
+    let tagged = tag_sentences(&fenominal, TEXT, &hits, TaggingOptions::default());
+    let conll = to_conll_with_provenance(&tagged, &provenance);
+    assert!(conll.starts_with("# document_id: note-1\n"));
+    assert!(conll.contains("# hp_version: 2025-03-03\n"));
+    assert!(conll.contains("\n-DOCSTART- -X- -X- O\n\n"));
+
+    let jsonl = to_spacy_jsonl_with_provenance(&tagged, &provenance);
+    for line in jsonl.lines() {
+        let example: Value = serde_json::from_str(line).unwrap();
+        assert_eq!("2025-03-03", example["meta"]["hp_version"]);
+        assert_eq!(hash, example["meta"]["negation_lexicon_hash"]);
+    }
+}
+
+#[cfg(feature = "bioc")]
+#[rstest]
+fn test_provenance_in_bioc(hpo: Arc<FullCsrOntology>) {
+    use fenominal::export::bioc::BioCCollection;
+
+    let fenominal = Fenominal::new(hpo);
+    let mut collection = BioCCollection::default();
+    collection.set_provenance(&fenominal.provenance("corpus"));
+    assert_eq!(Some("2025-03-03"), collection.infons.get("hp_version").map(String::as_str));
+    assert!(!collection.infons.contains_key("document_id"));
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::pubannotation::PubAnnotation;
+use fenominal::Fenominal;
+use rstest::rstest;
+use serde_json::{json, Value};
+use common::hpo;
+
+#[rstest]
+fn test_pubannotation_of_mined_sentences(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let text = "The naïve  patient had seizures. No scoliosis.";
+    let sentences = fenominal.mine_sentences(text).unwrap();
+    let document = PubAnnotation::from_sentences(&sentences).with_source("PubMed", "12345");
+    let value: Value = serde_json::to_value(&document).unwrap();
+    // mine_sentences works on the sanitized text
+    assert_eq!("The naive patient had seizures. No scoliosis.", value["text"]);
+    assert_eq!(
+        json!([
+            {"id": "T1", "span": {"begin": 22, "end": 30}, "obj": "http://purl.obolibrary.org/obo/HP_0001250"},
+            {"id": "T2", "span": {"begin": 35, "end": 44}, "obj": "http://purl.obolibrary.org/obo/HP_0002650"}
+        ]),
+        value["denotations"]
+    );
+    assert_eq!(json!([{"id": "A1", "subj": "T2", "pred": "negation", "obj": true}]), value["attributes"]);
+
+    let batch = PubAnnotation::sentence_batch(&sentences);
+    assert_eq!(vec!["The naive patient had seizures.", "No scoliosis."], batch.iter().map(|d| d.text.as_str()).collect::<Vec<_>>());
+    assert_eq!(3, batch[1].denotations[0].span.begin);
+}
+
+#[rstest]
+fn test_pubannotation_of_original_text(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let text = "The naïve  patient had seizures.";
+    let hits = fenominal.process(text).unwrap();
+    let document = PubAnnotation::from_hits(text, &hits);
+    assert_eq!(text, document.text);
+    assert_eq!(23, document.denotations[0].span.begin);
+}
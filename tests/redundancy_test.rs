@@ -0,0 +1,57 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+const SEIZURE: &str = "HP:0001250";
+const GRAND_MAL: &str = "HP:0002069";
+const FOCAL_SEIZURE: &str = "HP:0007359";
+
+fn terms(hits: &[FenominalHit]) -> Vec<(&str, bool)> {
+    hits.iter().map(|h| (h.term_id.as_str(), h.is_observed)).collect()
+}
+
+#[rstest]
+fn test_remove_redundant_hits(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::builder(hpo).remove_redundant(true).build().unwrap();
+    let cases: Vec<(&str, Vec<(&str, bool)>)> = vec![
+        // observed ancestor of an observed term
+        ("She has seizures. Grand mal seizures occur at night.", vec![(GRAND_MAL, true)]),
+        // excluded descendant of an excluded term
+        ("No seizures. No grand mal seizures.", vec![(SEIZURE, false)]),
+        // an observed term with an excluded descendant is not redundant
+        ("She has seizures. No grand mal seizures.", vec![(SEIZURE, true), (GRAND_MAL, false)]),
+        // conflict: the contradicted exclusion is kept and does not remove its excluded descendants
+        (
+            "No seizures. Grand mal seizures occur at night. No focal seizures.",
+            vec![(SEIZURE, false), (GRAND_MAL, true), (FOCAL_SEIZURE, false)],
+        ),
+        // findings of different persons are not compared
+        ("His mother has seizures. He has grand mal seizures.", vec![(SEIZURE, true), (GRAND_MAL, true)]),
+    ];
+    for (text, expected) in cases {
+        let hits = fenominal.process(text).unwrap();
+        assert_eq!(expected, terms(&hits), "{}", text);
+    }
+}
+
+#[rstest]
+fn test_redundancy_removal_is_optional(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process("She has seizures. Grand mal seizures occur at night.").unwrap();
+    assert_eq!(vec![(SEIZURE, true), (GRAND_MAL, true)], terms(&hits));
+}
+
+#[rstest]
+fn test_remove_redundant_sentence_hits(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::builder(hpo).remove_redundant(true).build().unwrap();
+    let sentences = fenominal.mine_sentences("She has seizures. Grand mal seizures occur at night.").unwrap();
+    assert_eq!(2, sentences.len());
+    assert!(sentences[0].hits().is_empty());
+    assert_eq!("She has seizures.", sentences[0].original_text);
+    assert_eq!(vec![(GRAND_MAL, true)], terms(&sentences[1].hits()));
+}
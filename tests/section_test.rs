@@ -0,0 +1,63 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{ClinicalSection, Experiencer, Fenominal, FenominalHit, SectionFilter, SectionLexicon};
+use rstest::rstest;
+use common::hpo;
+
+const NOTE: &str = "Chief complaint: seizures\n\
+Family history: scoliosis\n\
+Physical examination: The boy has polydactyly.";
+
+#[rstest]
+fn test_hits_are_tagged_with_section(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::builder(hpo).section_lexicon(SectionLexicon::default()).build().unwrap();
+    let hits: Vec<FenominalHit> = fenominal.process(NOTE).unwrap();
+    assert_eq!(3, hits.len(), "unexpected hits: {:#?}", hits);
+    assert_eq!("Seizure", hits[0].label);
+    assert_eq!(Some(ClinicalSection::ChiefComplaint), hits[0].section);
+    assert_eq!("Scoliosis", hits[1].label);
+    assert_eq!(Some(ClinicalSection::FamilyHistory), hits[1].section);
+    assert_eq!(Experiencer::FamilyMember { relation: None }, hits[1].experiencer);
+    assert_eq!("Polydactyly", hits[2].label);
+    assert_eq!(Some(ClinicalSection::PhysicalExamination), hits[2].section);
+    assert_eq!("polydactyly", &NOTE[hits[2].get_span()]);
+}
+
+#[rstest]
+fn test_section_filter(hpo: Arc<FullCsrOntology>) {
+    let include = Fenominal::builder(hpo.clone())
+        .section_filter(SectionFilter::Include(vec![ClinicalSection::PhysicalExamination]))
+        .build()
+        .unwrap();
+    let hits = include.process(NOTE).unwrap();
+    assert_eq!(1, hits.len());
+    assert_eq!("Polydactyly", hits[0].label);
+
+    let exclude = Fenominal::builder(hpo)
+        .section_filter(SectionFilter::Exclude(vec![ClinicalSection::FamilyHistory]))
+        .build()
+        .unwrap();
+    let sentences = exclude.mine_sentences(NOTE).unwrap();
+    assert!(sentences.iter().all(|s| s.section != Some(ClinicalSection::FamilyHistory)));
+    let hits = exclude.process(NOTE).unwrap();
+    assert_eq!(2, hits.len());
+}
+
+#[rstest]
+fn test_sections_are_opt_in(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo.clone());
+    let hits = fenominal.process(NOTE).unwrap();
+    assert_eq!(3, hits.len());
+    assert!(hits.iter().all(|h| h.section.is_none()));
+    // without section detection, the sentences are those of the sanitized text
+    let sentences = fenominal.mine_sentences(NOTE).unwrap();
+    let texts: Vec<&str> = sentences.iter().map(|s| s.original_text.as_str()).collect();
+    assert_eq!(fenominal::sentence_split(&fenominal::sanitize(NOTE)), texts);
+
+    let with_sections = Fenominal::builder(hpo).section_lexicon(SectionLexicon::default()).build().unwrap();
+    let sentences = with_sections.mine_sentences(NOTE).unwrap();
+    assert_eq!(Some(ClinicalSection::ChiefComplaint), sentences[0].section);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{Fenominal, FenominalHit};
+use rstest::rstest;
+use common::hpo;
+
+const INTAKE_FORM: &str = "Intake form\n\
+Seizures: No\n\
+Hypotonia - yes\n\
+[x] Scoliosis  [ ] Kyphosis\n\
+The boy has polydactyly.";
+
+#[rstest]
+fn test_structured_form(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::builder(hpo).structured_forms(true).build().unwrap();
+    let hits: Vec<FenominalHit> = fenominal.process(INTAKE_FORM).unwrap();
+    let found: Vec<(&str, &str, bool)> = hits
+        .iter()
+        .map(|h| (h.label.as_str(), &INTAKE_FORM[h.get_span()], h.is_observed))
+        .collect();
+    assert_eq!(
+        vec![
+            ("Seizure", "Seizures", false),
+            ("Hypotonia", "Hypotonia", true),
+            ("Scoliosis", "Scoliosis", true),
+            ("Kyphosis", "Kyphosis", false),
+            ("Polydactyly", "polydactyly", true),
+        ],
+        found
+    );
+}
+
+#[rstest]
+fn test_structured_form_is_opt_in(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process("[ ] Kyphosis").unwrap();
+    assert_eq!(1, hits.len());
+    assert!(hits[0].is_observed);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::{ConflictPolicy, Fenominal};
+use rstest::rstest;
+use common::hpo;
+
+const TEXT: &str = "She has scoliosis. Her mother had seizures. No seizures were observed until age 3. \
+Seizures started at age 3 years. The scoliosis is progressive.";
+
+#[rstest]
+fn test_summarize(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let summary = fenominal.summarize(TEXT, ConflictPolicy::ObservedWins).unwrap();
+    assert_eq!(2, summary.len(), "{:#?}", summary);
+
+    let scoliosis = &summary.entries[0];
+    assert_eq!("HP:0002650", scoliosis.term.id);
+    assert_eq!(2, scoliosis.mention_count);
+    assert_eq!("scoliosis", &TEXT[scoliosis.mentions[0].span.clone()]);
+    assert_eq!(scoliosis.mentions[1].span.start, scoliosis.last_position);
+    assert!(scoliosis.is_observed && !scoliosis.is_conflicting);
+
+    // the mother's seizures are not counted
+    let seizure = summary.get("HP:0001250").unwrap();
+    assert_eq!(2, seizure.mention_count);
+    assert!(seizure.is_conflicting);
+    assert!(seizure.is_observed);
+    assert_eq!(TEXT.find("seizures were").unwrap(), seizure.first_position);
+}
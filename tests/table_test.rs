@@ -0,0 +1,43 @@
+mod common;
+
+use std::sync::Arc;
+
+use ontolius::ontology::csr::FullCsrOntology;
+use fenominal::export::table::{write_hits, write_summary, HitRow, SummaryRow, TableFormat};
+use fenominal::{ConflictPolicy, Fenominal};
+use rstest::rstest;
+use common::hpo;
+
+const TEXT: &str = "No seizures until age 3. Scoliosis was noted.\nSeizures recurred.";
+
+#[rstest]
+fn test_hit_and_summary_tables(hpo: Arc<FullCsrOntology>) {
+    let fenominal = Fenominal::new(hpo);
+    let hits = fenominal.process(TEXT).unwrap();
+    let mut tsv = Vec::new();
+    write_hits(&mut tsv, TableFormat::Tsv, &HitRow::from_hits(&fenominal, "doc-1", TEXT, &hits)).unwrap();
+    let tsv = String::from_utf8(tsv).unwrap();
+    let lines: Vec<&str> = tsv.lines().collect();
+    assert_eq!(
+        vec![
+            "document_id\tsentence_index\tstart\tend\tmatched_text\tterm_id\tlabel\tassertion",
+            "doc-1\t0\t3\t11\tseizures\tHP:0001250\tSeizure\tabsent",
+            "doc-1\t1\t25\t34\tScoliosis\tHP:0002650\tScoliosis\tpresent",
+            "doc-1\t2\t46\t54\tSeizures\tHP:0001250\tSeizure\tpresent",
+        ],
+        lines
+    );
+
+    let summary = fenominal.summarize(TEXT, ConflictPolicy::ObservedWins).unwrap();
+    let mut csv = Vec::new();
+    write_summary(&mut csv, TableFormat::Csv, &SummaryRow::from_summary("doc-1", &summary)).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(
+        vec![
+            "document_id,term_id,label,assertion,mention_count,first_position,last_position,conflicting",
+            "doc-1,HP:0001250,Seizure,present,2,3,46,true",
+            "doc-1,HP:0002650,Scoliosis,present,1,25,25,false",
+        ],
+        csv.lines().collect::<Vec<_>>()
+    );
+}